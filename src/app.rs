@@ -6,20 +6,25 @@ use std::sync::Arc;
 use std::time;
 
 use crate::i18n::{i18n, i18n_f};
+use chrono::{Duration, Utc};
 use futures::channel::oneshot::{self, Sender as OneShotSender};
 use futures::executor::{ThreadPool, ThreadPoolBuilder};
 use futures::FutureExt;
-use gio::{prelude::ApplicationExtManual, ApplicationExt, Notification, NotificationPriority, ThemedIcon};
-use glib::{clone, object::Cast, source::Continue, translate::ToGlib, Receiver, Sender};
+use gio::{
+    prelude::ApplicationExtManual, ApplicationExt, NetworkMonitor, NetworkMonitorExt, Notification,
+    NotificationPriority, ThemedIcon,
+};
+use glib::{clone, object::Cast, source::Continue, translate::ToGlib, ObjectExt, Receiver, Sender};
 use gtk::{
-    prelude::GtkWindowExtManual, Application, ButtonExt, DialogExt, EntryExt, FileChooserAction, FileChooserDialog,
-    FileChooserExt, FileFilter, GtkApplicationExt, GtkWindowExt, ResponseType, Widget, WidgetExt,
+    prelude::GtkWindowExtManual, Application, BoxExt, ButtonExt, CheckButton, ComboBoxExt, ComboBoxText,
+    ComboBoxTextExt, DialogExt, EntryExt, FileChooserAction, FileChooserDialog, FileChooserExt, FileFilter,
+    GtkApplicationExt, GtkWindowExt, Label, Orientation, ResponseType, ToggleButtonExt, Widget, WidgetExt,
 };
 use lazy_static::lazy_static;
 use log::{error, info, warn};
 use news_flash::models::{
-    ArticleID, Category, CategoryID, FatArticle, FavIcon, Feed, FeedID, LoginData, PasswordLogin, PluginCapabilities,
-    PluginID, TagID, Url,
+    Article, ArticleID, Category, CategoryID, FatArticle, FavIcon, Feed, FeedID, LoginData, Marked, PasswordLogin,
+    PluginCapabilities, PluginID, Read, TagID, Url,
 };
 use news_flash::{NewsFlash, NewsFlashError};
 use parking_lot::RwLock;
@@ -28,15 +33,24 @@ use tokio::runtime::Runtime;
 
 use crate::about_dialog::NewsFlashAbout;
 use crate::add_dialog::{AddCategory, AddPopover};
-use crate::article_list::{MarkUpdate, ReadUpdate};
-use crate::article_view::ArticleView;
+use crate::article_list::{BatchOperation, BatchUpdate, MarkUpdate, ReadUpdate};
 use crate::config::APP_ID;
-use crate::content_page::HeaderSelection;
+use crate::content_page::{DateRange, HeaderSelection};
+use crate::dbus::DBusService;
 use crate::discover::DiscoverDialog;
+use crate::enclosures::{Enclosure, EnclosureDownloads, MediaArticles};
+use crate::export::{ArticleExport, DigestExport, ExportFormat, MAX_DIGEST_ARTICLES};
+use crate::filter_rules::{FilterRules, FilterRulesDialog, KnownArticles};
 use crate::main_window::MainWindow;
+use crate::offline::{OfflineCache, OfflineChange, OfflinePreparer, OfflineQueue, SyncScraper};
+use crate::opml_import::{OpmlImport, OpmlImportDialog};
 use crate::rename_dialog::RenameDialog;
-use crate::settings::{NewsFlashShortcutWindow, ProxyProtocoll, Settings, SettingsDialog};
+use crate::settings::{
+    DigestFormat, DigestSchedule, FeedSyncInterval, FeedViewSetting, NewsFlashShortcutWindow, ProxyProtocoll, Settings,
+    SettingsDialog,
+};
 use crate::sidebar::{models::SidebarSelection, FeedListDndAction};
+use crate::smart_folders::SmartFolderDialog;
 use crate::undo_bar::UndoActionModel;
 use crate::util::{FileUtil, GtkUtil, Util, CHANNEL_ERROR, RUNTIME_ERROR};
 
@@ -69,32 +83,53 @@ pub enum Action {
     ShowResetPage,
     ShowDiscoverDialog,
     ShowSettingsWindow,
+    ShowFilterRulesDialog,
+    SmartFolderDialog(Option<String>),
+    DeleteSmartFolder(String),
     ShowShortcutWindow,
     ShowAboutWindow,
+    ShowMainWindow,
     RetryLogin,
     Login(LoginData),
     ResetAccount,
     ResetAccountError(NewsFlashError),
     ScheduleSync,
+    SetFeedSyncInterval(FeedID, Option<FeedSyncInterval>),
+    SetCategorySyncInterval(CategoryID, Option<FeedSyncInterval>),
+    SetFeedViewSetting(FeedViewSetting),
+    ScrapeSyncedArticles,
+    PrepareOffline,
+    OfflinePrepProgress(Option<(usize, usize)>),
     Sync,
     InitSync,
     MarkArticleRead(ReadUpdate),
+    MarkArticlesRead(Vec<ArticleID>),
     MarkArticle(MarkUpdate),
     ToggleArticleRead,
     ToggleArticleMarked,
+    ShowArticleListMenu,
+    Undo,
+    Redo,
+    ApplyUndoAction(UndoActionModel),
+    ArticleBatch(BatchUpdate),
+    ExecuteArticleBatch(BatchUpdate),
     UpdateSidebar,
+    RefreshSidebar,
     UpdateArticleList,
     LoadMoreArticles,
     SidebarSelection(SidebarSelection),
     SelectNextArticle,
     SelectPrevArticle,
     HeaderSelection(HeaderSelection),
+    DateRange(DateRange),
     UpdateArticleHeader,
     ShowArticle(ArticleID),
     RedrawArticle,
     CloseArticle,
     SearchTerm(String),
     SetSidebarRead,
+    SetSidebarReadOlderThan(u32),
+    MarkOlderRead(SidebarSelection, u32, i64),
     AddDialog,
     AddFeed((Url, Option<String>, Option<AddCategory>)),
     AddCategory(String),
@@ -111,6 +146,12 @@ pub enum Action {
     UntagArticle(ArticleID, TagID),
     DragAndDrop(FeedListDndAction),
     ExportArticle,
+    PrintArticle,
+    SaveArticlePdf,
+    DownloadEnclosures,
+    ExportArticles(Vec<ArticleID>),
+    ExportDigest,
+    ExportScheduledDigest(i64),
     StartGrabArticleContent,
     FinishGrabArticleContent(Option<FatArticle>),
     ImportOpml,
@@ -118,6 +159,9 @@ pub enum Action {
     QueueQuit,
     ForceQuit,
     SetOfflineMode(bool),
+    NetworkChanged(bool),
+    QueueOfflineChanges(Vec<OfflineChange>),
+    FlushOfflineQueue,
     IgnoreTLSErrors,
     OpenSelectedArticle,
     OpenUrlInDefaultBrowser(String),
@@ -129,11 +173,17 @@ pub struct App {
     receiver: RwLock<Option<Receiver<Action>>>,
     news_flash: Arc<RwLock<Option<NewsFlash>>>,
     settings: Arc<RwLock<Settings>>,
-    sync_source_id: RwLock<Option<u32>>,
+    sync_source_id: Arc<RwLock<Option<u32>>>,
     threadpool: ThreadPool,
     icon_threadpool: ThreadPool,
     shutdown_in_progress: Arc<RwLock<bool>>,
     features: Arc<RwLock<Option<PluginCapabilities>>>,
+    dbus: Arc<RwLock<Option<DBusService>>>,
+    sync_scraper: SyncScraper,
+    offline_prep_in_progress: Arc<RwLock<bool>>,
+    // offline mode was entered automatically because the network went away
+    network_offline: Arc<RwLock<bool>>,
+    offline_queue: Arc<RwLock<OfflineQueue>>,
 }
 
 impl App {
@@ -166,12 +216,17 @@ impl App {
 
         let news_flash = Arc::new(RwLock::new(None));
         let features = Arc::new(RwLock::new(None));
+        let offline_queue = Arc::new(RwLock::new(OfflineQueue::open().unwrap_or_else(|error| {
+            error!("Failed to read offline queue: {}", error);
+            OfflineQueue::default()
+        })));
         let settings = Arc::new(RwLock::new(Settings::open().expect("Failed to access settings file")));
         let window = Arc::new(MainWindow::new(
             &settings,
             sender.clone(),
             shutdown_in_progress.clone(),
             &features,
+            &offline_queue,
         ));
 
         let app = Rc::new(Self {
@@ -181,11 +236,16 @@ impl App {
             receiver,
             news_flash,
             settings,
-            sync_source_id: RwLock::new(None),
+            sync_source_id: Arc::new(RwLock::new(None)),
             threadpool,
             icon_threadpool,
             shutdown_in_progress,
             features,
+            dbus: Arc::new(RwLock::new(None)),
+            sync_scraper: SyncScraper::new(),
+            offline_prep_in_progress: Arc::new(RwLock::new(false)),
+            network_offline: Arc::new(RwLock::new(false)),
+            offline_queue,
         });
 
         app.setup_signals();
@@ -207,7 +267,27 @@ impl App {
     }
 
     fn setup_signals(&self) {
-        self.application.connect_startup(|_app| {});
+        self.application.connect_startup(clone!(
+            @strong self.dbus as dbus,
+            @strong self.news_flash as news_flash,
+            @strong self.sender as sender => @default-panic, move |app|
+        {
+            if let (Some(connection), Some(object_path)) = (app.get_dbus_connection(), app.get_dbus_object_path()) {
+                if let Some(service) = DBusService::register(&connection, &object_path, &sender) {
+                    if let Some(news_flash) = news_flash.read().as_ref() {
+                        service.update_unread_count(news_flash);
+                    }
+                    dbus.write().replace(service);
+                }
+            }
+        }));
+
+        self.application
+            .connect_shutdown(clone!(@strong self.dbus as dbus => @default-panic, move |_app| {
+                if let Some(mut service) = dbus.write().take() {
+                    service.unregister();
+                }
+            }));
 
         self.application.connect_activate(clone!(
             @weak self.window.widget as window,
@@ -220,7 +300,19 @@ impl App {
             // Workaround SINGLE selection mode of listbox
             content_page.sidebar.read().feed_list.read().on_window_show();
             content_page.sidebar.read().tag_list.read().on_window_show();
+            content_page.sidebar.read().smart_folder_list.read().on_window_show();
         }));
+
+        if let Some(monitor) = NetworkMonitor::get_default() {
+            monitor.connect_network_changed(
+                clone!(@strong self.sender as sender => @default-panic, move |_monitor, available| {
+                    Util::send(&sender, Action::NetworkChanged(available));
+                }),
+            );
+            if !monitor.get_network_available() {
+                Util::send(&self.sender, Action::NetworkChanged(false));
+            }
+        }
     }
 
     pub fn run(&self, app: Rc<Self>) {
@@ -248,20 +340,52 @@ impl App {
             Action::ShowResetPage => self.window.show_reset_page(),
             Action::ShowDiscoverDialog => self.spawn_discover_dialog(),
             Action::ShowSettingsWindow => self.spawn_settings_window(),
+            Action::ShowFilterRulesDialog => self.spawn_filter_rules_dialog(),
+            Action::SmartFolderDialog(id) => self.spawn_smart_folder_dialog(id),
+            Action::DeleteSmartFolder(id) => self.delete_smart_folder(id),
             Action::ShowShortcutWindow => self.spawn_shortcut_window(),
             Action::ShowAboutWindow => self.spawn_about_window(),
+            Action::ShowMainWindow => self.application.activate(),
             Action::Login(data) => self.login(data),
             Action::RetryLogin => self.retry_login(),
             Action::ResetAccount => self.reset_account(),
             Action::ResetAccountError(error) => self.window.reset_account_failed(error),
             Action::ScheduleSync => self.schedule_sync(),
+            Action::SetFeedSyncInterval(feed_id, interval) => self.set_feed_sync_interval(feed_id, interval),
+            Action::SetCategorySyncInterval(category_id, interval) => {
+                self.set_category_sync_interval(category_id, interval)
+            }
+            Action::SetFeedViewSetting(setting) => self.set_feed_view_setting(setting),
+            Action::ScrapeSyncedArticles => self.scrape_synced_articles(),
+            Action::PrepareOffline => self.prepare_offline(),
+            Action::OfflinePrepProgress(progress) => self
+                .window
+                .content_page
+                .sidebar
+                .read()
+                .footer
+                .set_offline_progress(progress),
             Action::Sync => self.sync(),
             Action::InitSync => self.init_sync(),
             Action::MarkArticleRead(update) => self.mark_article_read(update),
+            Action::MarkArticlesRead(article_ids) => self.mark_articles_read(article_ids),
             Action::MarkArticle(update) => self.mark_article(update),
             Action::ToggleArticleRead => self.toggle_article_read(),
             Action::ToggleArticleMarked => self.toggle_article_marked(),
+            Action::ShowArticleListMenu => self.show_article_list_menu(),
+            Action::Undo => self.undo(),
+            Action::Redo => self.redo(),
+            Action::ApplyUndoAction(action) => self.apply_undo_action(action),
+            Action::ArticleBatch(update) => self.article_batch(update),
+            Action::ExecuteArticleBatch(update) => self.execute_article_batch(update),
             Action::UpdateSidebar => {
+                // articles changed, so smart folders have to be counted again
+                self.window.content_page.invalidate_smart_folder_counts();
+                self.window
+                    .update_sidebar(&self.news_flash, self.threadpool.clone(), &self.features);
+                self.update_dbus_unread_count();
+            }
+            Action::RefreshSidebar => {
                 self.window
                     .update_sidebar(&self.news_flash, self.threadpool.clone(), &self.features)
             }
@@ -275,6 +399,7 @@ impl App {
             Action::SelectNextArticle => self.window.content_page.article_list.read().select_next_article(),
             Action::SelectPrevArticle => self.window.content_page.article_list.read().select_prev_article(),
             Action::HeaderSelection(selection) => self.window.set_headerbar_selection(selection),
+            Action::DateRange(date_range) => self.window.set_date_range(date_range),
             Action::UpdateArticleHeader => self.window.update_article_header(&self.news_flash, &self.features),
             Action::ShowArticle(article_id) => self.window.show_article(article_id, &self.news_flash, &self.features),
             Action::RedrawArticle => self.window.content_page.article_view.redraw_article(),
@@ -289,6 +414,15 @@ impl App {
                 self.window
                     .set_sidebar_read(&self.news_flash, self.threadpool.clone(), self.settings.clone())
             }
+            Action::SetSidebarReadOlderThan(days) => self.set_sidebar_read_older_than(days),
+            Action::MarkOlderRead(selection, days, timestamp) => self.window.mark_older_read(
+                selection,
+                days,
+                timestamp,
+                &self.news_flash,
+                self.threadpool.clone(),
+                self.settings.clone(),
+            ),
             Action::AddDialog => self.add_feed_dialog(),
             Action::AddFeed((url, title, category)) => self.add_feed(url, title, category),
             Action::AddCategory(title) => self.add_category(title),
@@ -305,6 +439,12 @@ impl App {
             Action::UntagArticle(article_id, tag_id) => self.untag_article(article_id, tag_id),
             Action::DragAndDrop(action) => self.drag_and_drop(action),
             Action::ExportArticle => self.export_article(),
+            Action::PrintArticle => self.print_article(None),
+            Action::SaveArticlePdf => self.save_article_pdf(),
+            Action::DownloadEnclosures => self.download_enclosures(),
+            Action::ExportArticles(article_ids) => self.export_articles(article_ids),
+            Action::ExportDigest => self.export_digest(),
+            Action::ExportScheduledDigest(new_article_count) => self.export_scheduled_digest(new_article_count),
             Action::StartGrabArticleContent => self.start_grab_article_content(),
             Action::FinishGrabArticleContent(article) => self.finish_grab_article_content(article),
             Action::ImportOpml => self.import_opml(),
@@ -312,6 +452,9 @@ impl App {
             Action::QueueQuit => self.queue_quit(),
             Action::ForceQuit => self.force_quit(),
             Action::SetOfflineMode(offline) => self.set_offline(offline),
+            Action::NetworkChanged(available) => self.network_changed(available),
+            Action::QueueOfflineChanges(changes) => self.queue_offline_changes(changes),
+            Action::FlushOfflineQueue => self.flush_offline_queue(),
             Action::IgnoreTLSErrors => self.ignore_tls_errors(),
             Action::OpenSelectedArticle => self.open_selected_article_in_browser(),
             Action::OpenUrlInDefaultBrowser(url) => self.open_url_in_default_browser(url),
@@ -320,6 +463,10 @@ impl App {
     }
 
     fn show_notification(&self, counts: NotificationCounts) {
+        if self.settings.read().get_quiet_hours().is_active() {
+            return;
+        }
+
         if counts.new > 0 && counts.unread > 0 {
             let summary = i18n("New Articles");
 
@@ -464,11 +611,26 @@ impl App {
             @weak self.window as main_window,
             @weak self.news_flash as news_flash,
             @weak self.features as features,
+            @weak self.offline_queue as offline_queue,
             @strong self.sender as sender => @default-panic, move |res| match res
         {
             Ok(Ok(())) => {
                 news_flash.write().take();
                 main_window.content_page.clear();
+                for cache in &[OfflineCache::new(), OfflineCache::digest()] {
+                    if let Err(error) = cache.clear() {
+                        error!("Failed to clear offline cache: {}", error);
+                    }
+                }
+                if let Err(error) = EnclosureDownloads::new().clear() {
+                    error!("Failed to clear downloaded enclosures: {}", error);
+                }
+                if let Err(error) = main_window.content_page.media_articles.write().clear() {
+                    error!("Failed to clear articles with media: {}", error);
+                }
+                if let Err(error) = offline_queue.write().clear() {
+                    error!("Failed to clear offline queue: {}", error);
+                }
                 main_window.content_header.show_article(None, &Arc::new(RwLock::new(None)), &features);
                 Util::send(&sender, Action::ShowWelcomePage);
             }
@@ -483,41 +645,177 @@ impl App {
     }
 
     fn schedule_sync(&self) {
-        GtkUtil::remove_source(*self.sync_source_id.read());
-        let sync_interval = self.settings.read().get_sync_interval();
-        if let Some(sync_interval) = sync_interval.to_seconds() {
-            self.sync_source_id.write().replace(
-                gtk::timeout_add_seconds(
-                    sync_interval,
-                    clone!(@strong self.sender as sender => @default-panic, move || {
+        GtkUtil::remove_source(self.sync_source_id.write().take());
+        if self.window.state.read().get_offline() {
+            // restarted once we are back online
+            return;
+        }
+        let global_interval = self.settings.read().get_sync_interval();
+        let schedule = self.settings.read().get_sync_schedule();
+
+        if schedule.is_trivial() {
+            if let Some(sync_interval) = global_interval.to_seconds() {
+                Self::start_sync_timer(&self.sync_source_id, &self.sender, &self.settings, sync_interval);
+            }
+            return;
+        }
+
+        // per feed intervals need to look at the database, don't block the UI while doing so
+        let (sender, receiver) = oneshot::channel::<Option<u32>>();
+
+        let news_flash = self.news_flash.clone();
+        let thread_future = async move {
+            let mut next_sync = None;
+            if let Some(news_flash) = news_flash.read().as_ref() {
+                next_sync = schedule.next_sync_minutes(news_flash, global_interval);
+            }
+            sender.send(next_sync).expect(CHANNEL_ERROR);
+        };
+
+        let glib_future = receiver.map(clone!(
+            @strong self.sync_source_id as sync_source_id,
+            @strong self.settings as settings,
+            @strong self.sender as sender => @default-panic, move |res|
+        {
+            GtkUtil::remove_source(sync_source_id.write().take());
+            if let Ok(Some(minutes)) = res {
+                info!("Next sync scheduled in {} minutes", minutes);
+                Self::start_sync_timer(&sync_source_id, &sender, &settings, minutes * 60);
+            }
+        }));
+
+        self.threadpool.spawn_ok(thread_future);
+        Util::glib_spawn_future(glib_future);
+    }
+
+    fn start_sync_timer(
+        sync_source_id: &Arc<RwLock<Option<u32>>>,
+        sender: &Sender<Action>,
+        settings: &Arc<RwLock<Settings>>,
+        seconds: u32,
+    ) {
+        sync_source_id.write().replace(
+            gtk::timeout_add_seconds(
+                seconds,
+                clone!(@strong sender, @weak settings => @default-panic, move || {
+                    if settings.read().get_quiet_hours().is_active() {
+                        info!("Skipping automatic sync during quiet hours");
+                    } else {
                         Util::send(&sender, Action::Sync);
-                        Continue(true)
-                    }),
-                )
-                .to_glib(),
+                    }
+                    Continue(true)
+                }),
+            )
+            .to_glib(),
+        );
+    }
+
+    fn set_feed_sync_interval(&self, feed_id: FeedID, interval: Option<FeedSyncInterval>) {
+        if self
+            .settings
+            .write()
+            .set_feed_sync_interval(&feed_id, interval)
+            .is_err()
+        {
+            Util::send(
+                &self.sender,
+                Action::ErrorSimpleMessage("Failed to set setting 'feed sync interval'.".to_owned()),
             );
-        } else {
-            self.sync_source_id.write().take();
+            return;
+        }
+        self.schedule_sync();
+    }
+
+    fn set_feed_view_setting(&self, setting: FeedViewSetting) {
+        let article = match self.window.content_page.article_view.get_visible_article() {
+            Some(article) => article,
+            None => {
+                warn!("Set feed view setting: No article visible.");
+                return;
+            }
+        };
+
+        let mut feed_settings = self.settings.read().get_feed_view_settings(&article.feed_id);
+        feed_settings.apply(setting.clone());
+        if self
+            .settings
+            .write()
+            .set_feed_view_settings(&article.feed_id, feed_settings)
+            .is_err()
+        {
+            Util::send(
+                &self.sender,
+                Action::ErrorSimpleMessage("Failed to set setting 'feed article view'.".to_owned()),
+            );
+            return;
+        }
+
+        match setting {
+            FeedViewSetting::ScrapedContent(true) => {
+                if !self.window.state.read().get_offline() {
+                    self.window.state.write().set_prefer_scraped_content(true);
+                    Util::send(&self.sender, Action::StartGrabArticleContent);
+                }
+            }
+            FeedViewSetting::Zoom(_) | FeedViewSetting::Theme(_) => {
+                Util::send(&self.sender, Action::RedrawArticle);
+            }
+            FeedViewSetting::ScrapeOnSync(true) => {
+                Util::send(&self.sender, Action::ScrapeSyncedArticles);
+            }
+            FeedViewSetting::ScrapedContent(false)
+            | FeedViewSetting::OpenInBrowser(_)
+            | FeedViewSetting::ScrapeOnSync(false) => {}
+        }
+    }
+
+    fn set_category_sync_interval(&self, category_id: CategoryID, interval: Option<FeedSyncInterval>) {
+        if self
+            .settings
+            .write()
+            .set_category_sync_interval(&category_id, interval)
+            .is_err()
+        {
+            Util::send(
+                &self.sender,
+                Action::ErrorSimpleMessage("Failed to set setting 'category sync interval'.".to_owned()),
+            );
+            return;
         }
+        self.schedule_sync();
     }
 
     fn sync(&self) {
         let (sender, receiver) = oneshot::channel::<Result<i64, NewsFlashError>>();
         self.window.content_header.start_sync();
+        if let Some(dbus) = self.dbus.read().as_ref() {
+            dbus.set_syncing(true);
+        }
 
         let news_flash = self.news_flash.clone();
         let settings = self.settings.clone();
+        let media_articles = self.window.content_page.media_articles.clone();
         let thread_future = async move {
             if let Some(news_flash) = news_flash.read().as_ref() {
-                let result = Runtime::new()
-                    .expect(RUNTIME_ERROR)
-                    .block_on(news_flash.sync(&Self::build_client(&settings)));
+                let client = Self::build_client(&settings);
+                let known_articles = Self::load_known_articles(news_flash, &settings, &media_articles);
+                let result = Runtime::new().expect(RUNTIME_ERROR).block_on(news_flash.sync(&client));
+                if result.is_ok() {
+                    let new_articles = known_articles
+                        .as_ref()
+                        .and_then(|known_articles| Self::load_new_articles(news_flash, known_articles));
+                    Self::update_media_articles(news_flash, &media_articles, new_articles.as_deref());
+                    if let Some(new_articles) = &new_articles {
+                        Self::apply_filter_rules(news_flash, &settings, new_articles, &client);
+                    }
+                }
                 sender.send(result).expect(CHANNEL_ERROR);
             }
         };
 
         let glib_future = receiver.map(clone!(
             @strong self.news_flash as news_flash,
+            @strong self.dbus as dbus,
             @weak self.window.content_header as content_header,
             @strong self.sender as sender => @default-panic, move |res|
         {
@@ -526,11 +824,20 @@ impl App {
                     Ok(unread_count) => unread_count,
                     Err(_) => 0,
                 };
+                if let Some(dbus) = dbus.read().as_ref() {
+                    dbus.set_syncing(false);
+                    dbus.update_unread_count(news_flash);
+                }
                 match res {
                     Ok(Ok(new_article_count)) => {
                         content_header.finish_sync();
                         Util::send(&sender, Action::UpdateSidebar);
                         Util::send(&sender, Action::UpdateArticleList);
+                        Util::send(&sender, Action::ScheduleSync);
+                        Util::send(&sender, Action::FlushOfflineQueue);
+                        Util::send(&sender, Action::ScrapeSyncedArticles);
+                        Util::send(&sender, Action::PrepareOffline);
+                        Util::send(&sender, Action::ExportScheduledDigest(new_article_count));
                         let counts = NotificationCounts {
                             new: new_article_count,
                             unread: unread_count,
@@ -553,20 +860,36 @@ impl App {
     fn init_sync(&self) {
         let (sender, receiver) = oneshot::channel::<Result<i64, NewsFlashError>>();
         self.window.content_header.start_sync();
+        if let Some(dbus) = self.dbus.read().as_ref() {
+            dbus.set_syncing(true);
+        }
 
         let news_flash = self.news_flash.clone();
         let settings = self.settings.clone();
+        let media_articles = self.window.content_page.media_articles.clone();
         let thread_future = async move {
             if let Some(news_flash) = news_flash.read().as_ref() {
+                let client = Self::build_client(&settings);
+                let known_articles = Self::load_known_articles(news_flash, &settings, &media_articles);
                 let result = Runtime::new()
                     .expect(RUNTIME_ERROR)
-                    .block_on(news_flash.initial_sync(&Self::build_client(&settings)));
+                    .block_on(news_flash.initial_sync(&client));
+                if result.is_ok() {
+                    let new_articles = known_articles
+                        .as_ref()
+                        .and_then(|known_articles| Self::load_new_articles(news_flash, known_articles));
+                    Self::update_media_articles(news_flash, &media_articles, new_articles.as_deref());
+                    if let Some(new_articles) = &new_articles {
+                        Self::apply_filter_rules(news_flash, &settings, new_articles, &client);
+                    }
+                }
                 sender.send(result).expect(CHANNEL_ERROR);
             }
         };
 
         let glib_future = receiver.map(clone!(
             @strong self.news_flash as news_flash,
+            @strong self.dbus as dbus,
             @weak self.window.content_header as content_header,
             @strong self.sender as sender => @default-panic, move |res|
         {
@@ -575,11 +898,20 @@ impl App {
                     Ok(unread_count) => unread_count,
                     Err(_) => 0,
                 };
+                if let Some(dbus) = dbus.read().as_ref() {
+                    dbus.set_syncing(false);
+                    dbus.update_unread_count(news_flash);
+                }
                 match res {
                     Ok(Ok(new_article_count)) => {
                         content_header.finish_sync();
                         Util::send(&sender, Action::UpdateSidebar);
                         Util::send(&sender, Action::UpdateArticleList);
+                        Util::send(&sender, Action::ScheduleSync);
+                        Util::send(&sender, Action::FlushOfflineQueue);
+                        Util::send(&sender, Action::ScrapeSyncedArticles);
+                        Util::send(&sender, Action::PrepareOffline);
+                        Util::send(&sender, Action::ExportScheduledDigest(new_article_count));
                         let counts = NotificationCounts {
                             new: new_article_count,
                             unread: unread_count,
@@ -599,6 +931,107 @@ impl App {
         Util::glib_spawn_future(glib_future);
     }
 
+    fn scrape_synced_articles(&self) {
+        if self.window.state.read().get_offline() {
+            return;
+        }
+        self.sync_scraper
+            .scrape(&self.news_flash, &self.settings, &self.threadpool);
+    }
+
+    fn prepare_offline(&self) {
+        if self.window.state.read().get_offline() || *self.offline_prep_in_progress.read() {
+            return;
+        }
+
+        let offline_settings = self.settings.read().get_offline_settings();
+        if !offline_settings.prepare_after_sync {
+            return;
+        }
+        *self.offline_prep_in_progress.write() = true;
+
+        let news_flash = self.news_flash.clone();
+        let settings = self.settings.clone();
+        let window_state = self.window.state.clone();
+        let offline_prep_in_progress = self.offline_prep_in_progress.clone();
+        let global_sender = self.sender.clone();
+        let thread_future = async move {
+            let client = Self::build_client(&settings);
+            let result = OfflinePreparer::new().fill_cache(
+                &news_flash,
+                &offline_settings,
+                &client,
+                || !window_state.read().get_offline(),
+                |prepared, total| Util::send(&global_sender, Action::OfflinePrepProgress(Some((prepared, total)))),
+            );
+            if let Err(error) = result {
+                error!("Failed to prepare articles for offline reading: {}", error);
+            }
+
+            *offline_prep_in_progress.write() = false;
+            Util::send(&global_sender, Action::OfflinePrepProgress(None));
+        };
+
+        self.threadpool.spawn_ok(thread_future);
+    }
+
+    // remember which of the articles added by a sync have audio or video
+    fn update_media_articles(
+        news_flash: &NewsFlash,
+        media_articles: &RwLock<MediaArticles>,
+        new_articles: Option<&[Article]>,
+    ) {
+        if let Err(error) = MediaArticles::refresh(media_articles, news_flash, new_articles) {
+            error!("Failed to update articles with media: {}", error);
+        }
+    }
+
+    // articles present before a sync, filter rules and the media index only look at the ones added by it
+    fn load_known_articles(
+        news_flash: &NewsFlash,
+        settings: &Arc<RwLock<Settings>>,
+        media_articles: &RwLock<MediaArticles>,
+    ) -> Option<KnownArticles> {
+        // without filter rules only a complete media index needs them, an incomplete one looks at all articles
+        if settings.read().get_filter_rules().is_empty() && !media_articles.read().is_complete() {
+            return None;
+        }
+
+        match KnownArticles::load(news_flash) {
+            Ok(known_articles) => Some(known_articles),
+            Err(error) => {
+                error!("Failed to load articles before sync: {}", error);
+                None
+            }
+        }
+    }
+
+    fn load_new_articles(news_flash: &NewsFlash, known_articles: &KnownArticles) -> Option<Vec<Article>> {
+        match known_articles.new_articles(news_flash) {
+            Ok(articles) => Some(articles),
+            Err(error) => {
+                error!("Failed to load articles added by sync: {}", error);
+                None
+            }
+        }
+    }
+
+    fn apply_filter_rules(
+        news_flash: &NewsFlash,
+        settings: &Arc<RwLock<Settings>>,
+        new_articles: &[Article],
+        client: &Client,
+    ) {
+        let rules = settings.read().get_filter_rules();
+        if rules.is_empty() {
+            return;
+        }
+
+        if let Err(error) = FilterRules::apply(news_flash, &rules, new_articles, client) {
+            error!("Failed to apply filter rules: {}", error);
+        }
+    }
+
     fn load_favicon(&self, feed: Feed, oneshot_sender: OneShotSender<Option<FavIcon>>) {
         let news_flash = self.news_flash.clone();
         let global_sender = self.sender.clone();
@@ -694,6 +1127,82 @@ impl App {
         Util::glib_spawn_future(glib_future);
     }
 
+    fn mark_articles_read(&self, article_ids: Vec<ArticleID>) {
+        if self.window.state.read().get_offline() {
+            let changes = article_ids
+                .into_iter()
+                .map(|article_id| OfflineChange::read(article_id, Read::Read, Read::Unread))
+                .collect();
+            self.queue_offline_changes(changes);
+            return;
+        }
+
+        let (sender, receiver) = oneshot::channel::<Result<(), NewsFlashError>>();
+
+        let news_flash = self.news_flash.clone();
+        let settings = self.settings.clone();
+        let global_sender = self.sender.clone();
+        let thread_article_ids = article_ids.clone();
+        let thread_future = async move {
+            if let Some(news_flash) = news_flash.read().as_ref() {
+                sender
+                    .send(
+                        Runtime::new()
+                            .expect(RUNTIME_ERROR)
+                            .block_on(news_flash.set_article_read(
+                                &thread_article_ids,
+                                Read::Read,
+                                &Self::build_client(&settings),
+                            )),
+                    )
+                    .expect(CHANNEL_ERROR);
+            } else {
+                let message = "Failed to lock NewsFlash.".to_owned();
+                error!("{}", message);
+                Util::send(&global_sender, Action::ErrorSimpleMessage(message));
+            }
+        };
+
+        let glib_future = receiver.map(clone!(
+            @strong self.sender as global_sender,
+            @strong self.news_flash as news_flash,
+            @strong self.features as features,
+            @weak self.window.content_page as content_page,
+            @weak self.window.content_header as content_header => @default-panic, move |res|
+        {
+            match res {
+                Ok(Ok(())) => {}
+                Ok(Err(error)) => {
+                    let message = format!("Failed to mark {} articles read", article_ids.len());
+                    error!("{}", message);
+                    Util::send(&global_sender, Action::Error(message, error));
+                    Util::send(&global_sender, Action::UpdateArticleList);
+                }
+                Err(error) => {
+                    let message = format!("Sender error: {}", error);
+                    error!("{}", message);
+                    Util::send(&global_sender, Action::ErrorSimpleMessage(message));
+                    Util::send(&global_sender, Action::UpdateArticleList);
+                }
+            };
+
+            Util::send(&global_sender, Action::UpdateSidebar);
+            let visible_article = content_page.article_view.get_visible_article();
+            if let Some(mut visible_article) = visible_article {
+                if article_ids.contains(&visible_article.article_id) {
+                    visible_article.unread = Read::Read;
+                    content_header.show_article(Some(&visible_article), &news_flash, &features);
+                    content_page
+                        .article_view
+                        .update_visible_article(Some(visible_article.unread), None);
+                }
+            }
+        }));
+
+        self.threadpool.spawn_ok(thread_future);
+        Util::glib_spawn_future(glib_future);
+    }
+
     fn mark_article(&self, update: MarkUpdate) {
         let (sender, receiver) = oneshot::channel::<Result<(), NewsFlashError>>();
 
@@ -763,6 +1272,36 @@ impl App {
     }
 
     fn toggle_article_read(&self) {
+        let selected_articles = self
+            .window
+            .content_page
+            .article_list
+            .read()
+            .get_selected_article_models();
+        if self.window.state.read().get_offline() {
+            let read = if selected_articles.iter().any(|article| article.read == Read::Unread) {
+                Read::Read
+            } else {
+                Read::Unread
+            };
+            let changes = selected_articles
+                .into_iter()
+                .map(|article| OfflineChange::read(article.id, read, article.read))
+                .collect();
+            self.queue_offline_changes(changes);
+            return;
+        }
+        if selected_articles.len() > 1 {
+            let operation = if selected_articles.iter().any(|article| article.read == Read::Unread) {
+                BatchOperation::MarkRead
+            } else {
+                BatchOperation::MarkUnread
+            };
+            let article_ids = selected_articles.into_iter().map(|article| article.id).collect();
+            self.article_batch(BatchUpdate { article_ids, operation });
+            return;
+        }
+
         let selected_article = self
             .window
             .content_page
@@ -780,10 +1319,50 @@ impl App {
                 None,
             );
             Util::send(&self.sender, Action::MarkArticleRead(update));
+            self.window.show_undo_bar(UndoActionModel::ArticleChange(
+                vec![selected_article.id],
+                BatchOperation::from_read(selected_article.read.invert()),
+            ));
         }
     }
 
     fn toggle_article_marked(&self) {
+        let selected_articles = self
+            .window
+            .content_page
+            .article_list
+            .read()
+            .get_selected_article_models();
+        if self.window.state.read().get_offline() {
+            let marked = if selected_articles
+                .iter()
+                .any(|article| article.marked == Marked::Unmarked)
+            {
+                Marked::Marked
+            } else {
+                Marked::Unmarked
+            };
+            let changes = selected_articles
+                .into_iter()
+                .map(|article| OfflineChange::marked(article.id, marked, article.marked))
+                .collect();
+            self.queue_offline_changes(changes);
+            return;
+        }
+        if selected_articles.len() > 1 {
+            let operation = if selected_articles
+                .iter()
+                .any(|article| article.marked == Marked::Unmarked)
+            {
+                BatchOperation::Star
+            } else {
+                BatchOperation::Unstar
+            };
+            let article_ids = selected_articles.into_iter().map(|article| article.id).collect();
+            self.article_batch(BatchUpdate { article_ids, operation });
+            return;
+        }
+
         let selected_article = self
             .window
             .content_page
@@ -802,39 +1381,270 @@ impl App {
                 Some(selected_article.marked.invert()),
             );
             Util::send(&self.sender, Action::MarkArticle(update));
+            self.window.show_undo_bar(UndoActionModel::ArticleChange(
+                vec![selected_article.id],
+                BatchOperation::from_marked(selected_article.marked.invert()),
+            ));
         }
     }
 
-    fn spawn_shortcut_window(&self) {
-        let dialog = NewsFlashShortcutWindow::new(&self.window.widget, &*self.settings.read());
-        dialog.widget.present();
+    fn show_article_list_menu(&self) {
+        let support_tags = match self.features.read().as_ref() {
+            Some(features) => features.contains(PluginCapabilities::SUPPORT_TAGS),
+            None => false,
+        };
+        let tags = if support_tags {
+            match self.news_flash.read().as_ref() {
+                Some(news_flash) => news_flash.get_tags().unwrap_or_default(),
+                None => Vec::new(),
+            }
+        } else {
+            Vec::new()
+        };
+        self.window.content_page.article_list.read().show_context_menu(&tags);
     }
 
-    fn spawn_about_window(&self) {
-        let dialog = NewsFlashAbout::new(&self.window.widget);
-        dialog.widget.present();
-    }
+    fn article_batch(&self, update: BatchUpdate) {
+        if update.article_ids.is_empty() || self.window.state.read().get_offline() {
+            return;
+        }
 
-    fn spawn_settings_window(&self) {
-        let dialog = SettingsDialog::new(&self.window.widget, &self.sender, &self.settings);
-        dialog.widget.present();
-    }
+        let read = update.operation.read();
+        let marked = update.operation.marked();
+        let article_ids = {
+            let article_list = self.window.content_page.article_list.read();
+            let article_ids = article_list.filter_unchanged(update.article_ids, read, marked);
+            for article_id in &article_ids {
+                article_list.set_article_row_state(article_id, read, marked);
+            }
+            article_ids
+        };
+        if article_ids.is_empty() {
+            return;
+        }
 
-    fn spawn_discover_dialog(&self) {
-        let dialog = DiscoverDialog::new(
-            &self.window.widget,
+        Util::send(
             &self.sender,
-            &self.settings,
-            &self.news_flash,
-            self.threadpool.clone(),
+            Action::UndoableAction(UndoActionModel::ArticleBatch(article_ids, update.operation)),
         );
-        dialog.widget.present();
     }
 
-    fn add_feed_dialog(&self) {
-        if let Some(news_flash) = self.news_flash.read().as_ref() {
-            let error_message = "Failed to add feed".to_owned();
-            let add_button = self.window.content_page.sidebar.read().footer.add_button.clone();
+    fn undo(&self) {
+        if let Some(action) = self.window.undo_bar.undo() {
+            self.apply_undo_action(action);
+        }
+    }
+
+    fn redo(&self) {
+        if let Some(action) = self.window.undo_bar.redo() {
+            self.apply_undo_action(action);
+        }
+    }
+
+    fn apply_undo_action(&self, action: UndoActionModel) {
+        match action {
+            UndoActionModel::ArticleChange(article_ids, operation) => {
+                let read = operation.read();
+                let marked = operation.marked();
+                if read.is_some() || marked.is_some() {
+                    let article_list = self.window.content_page.article_list.read();
+                    for article_id in &article_ids {
+                        article_list.set_article_row_state(article_id, read, marked);
+                    }
+                }
+                self.execute_article_batch(BatchUpdate { article_ids, operation });
+            }
+            UndoActionModel::MarkAllRead(_selection, article_ids) => {
+                self.apply_undo_action(UndoActionModel::ArticleChange(article_ids, BatchOperation::MarkRead))
+            }
+            UndoActionModel::RenameFeed(feed_id, _old_label, new_label) => {
+                let feed = match self.news_flash.read().as_ref() {
+                    Some(news_flash) => match news_flash.get_feeds() {
+                        Ok((feeds, _mappings)) => feeds.into_iter().find(|feed| feed.feed_id == feed_id),
+                        Err(error) => {
+                            Util::send(&self.sender, Action::Error("Failed to load feeds.".to_owned(), error));
+                            return;
+                        }
+                    },
+                    None => None,
+                };
+                if let Some(feed) = feed {
+                    self.rename_feed(feed, new_label);
+                }
+            }
+            UndoActionModel::RenameCategory(category_id, _old_label, new_label) => {
+                let category = match self.news_flash.read().as_ref() {
+                    Some(news_flash) => match news_flash.get_categories() {
+                        Ok(categories) => categories
+                            .into_iter()
+                            .find(|category| category.category_id == category_id),
+                        Err(error) => {
+                            Util::send(
+                                &self.sender,
+                                Action::Error("Failed to load categories.".to_owned(), error),
+                            );
+                            return;
+                        }
+                    },
+                    None => None,
+                };
+                if let Some(category) = category {
+                    self.rename_category(category, new_label);
+                }
+            }
+            UndoActionModel::MoveFeed(feed_id, old_parent, new_parent) => {
+                self.move_feed_list_item(FeedListDndAction::MoveFeed(feed_id, old_parent, new_parent, 0))
+            }
+            UndoActionModel::MoveCategory(category_id, _old_parent, new_parent) => {
+                self.move_feed_list_item(FeedListDndAction::MoveCategory(category_id, new_parent, 0))
+            }
+            UndoActionModel::DeleteFeed(_, _)
+            | UndoActionModel::DeleteCategory(_, _)
+            | UndoActionModel::DeleteTag(_, _)
+            | UndoActionModel::MarkOlderRead(_, _, _)
+            | UndoActionModel::ArticleBatch(_, _) => {
+                warn!("'{}' is executed by the undo bar", action);
+            }
+        }
+    }
+
+    fn execute_article_batch(&self, update: BatchUpdate) {
+        let (sender, receiver) = oneshot::channel::<Result<(), NewsFlashError>>();
+
+        let news_flash = self.news_flash.clone();
+        let settings = self.settings.clone();
+        let global_sender = self.sender.clone();
+        let batch = BatchUpdate {
+            article_ids: update.article_ids.clone(),
+            operation: update.operation.clone(),
+        };
+        let thread_future = async move {
+            if let Some(news_flash) = news_flash.read().as_ref() {
+                let client = Self::build_client(&settings);
+                let mut runtime = Runtime::new().expect(RUNTIME_ERROR);
+                let result = runtime.block_on(batch.execute(news_flash, &client));
+                sender.send(result).expect(CHANNEL_ERROR);
+            } else {
+                let message = "Failed to lock NewsFlash.".to_owned();
+                error!("{}", message);
+                Util::send(&global_sender, Action::ErrorSimpleMessage(message));
+            }
+        };
+
+        let processing_actions = self.window.undo_bar.processing_actions();
+        let glib_future = receiver.map(clone!(
+            @strong self.sender as global_sender,
+            @weak processing_actions => @default-panic, move |res|
+        {
+            match res {
+                Ok(Ok(())) => {}
+                Ok(Err(error)) => {
+                    let message = format!("Failed to apply '{:?}' to {} articles", update.operation, update.article_ids.len());
+                    error!("{}", message);
+                    Util::send(&global_sender, Action::Error(message, error));
+                }
+                Err(error) => {
+                    let message = format!("Sender error: {}", error);
+                    error!("{}", message);
+                    Util::send(&global_sender, Action::ErrorSimpleMessage(message));
+                }
+            };
+
+            let undo_action = UndoActionModel::ArticleBatch(update.article_ids, update.operation);
+            processing_actions.write().remove(&undo_action);
+            Util::send(&global_sender, Action::UpdateSidebar);
+            Util::send(&global_sender, Action::UpdateArticleList);
+            Util::send(&global_sender, Action::UpdateArticleHeader);
+        }));
+
+        self.threadpool.spawn_ok(thread_future);
+        Util::glib_spawn_future(glib_future);
+    }
+
+    fn spawn_shortcut_window(&self) {
+        let dialog = NewsFlashShortcutWindow::new(&self.window.widget, &*self.settings.read());
+        dialog.widget.present();
+    }
+
+    fn spawn_about_window(&self) {
+        let dialog = NewsFlashAbout::new(&self.window.widget);
+        dialog.widget.present();
+    }
+
+    fn spawn_settings_window(&self) {
+        let dialog = SettingsDialog::new(&self.window.widget, &self.sender, &self.settings);
+        dialog.widget.present();
+    }
+
+    fn spawn_filter_rules_dialog(&self) {
+        let dialog = FilterRulesDialog::new(
+            &self.window.widget,
+            &self.sender,
+            &self.settings,
+            &self.news_flash,
+            self.threadpool.clone(),
+        );
+        dialog.widget.present();
+    }
+
+    fn spawn_smart_folder_dialog(&self, id: Option<String>) {
+        let folder = match id {
+            Some(id) => match self.settings.read().get_smart_folder(&id) {
+                Some(folder) => Some(folder),
+                None => {
+                    warn!("Smart folder '{}' doesn't exist", id);
+                    return;
+                }
+            },
+            None => None,
+        };
+        let dialog = SmartFolderDialog::new(
+            &self.window.widget,
+            &self.sender,
+            &self.settings,
+            &self.news_flash,
+            &self.window.state,
+            folder,
+        );
+        dialog.widget.present();
+    }
+
+    fn delete_smart_folder(&self, id: String) {
+        if self.settings.write().delete_smart_folder(&id).is_err() {
+            Util::send(
+                &self.sender,
+                Action::ErrorSimpleMessage("Failed to delete smart folder.".to_owned()),
+            );
+            return;
+        }
+
+        let selected = match self.window.state.read().get_sidebar_selection() {
+            SidebarSelection::SmartFolder(selected_id, _label) => selected_id == &id,
+            _ => false,
+        };
+        if selected {
+            self.window.state.write().set_sidebar_selection(SidebarSelection::All);
+            self.window.content_page.sidebar.read().select_all_button_no_update();
+            Util::send(&self.sender, Action::UpdateArticleList);
+        }
+        Util::send(&self.sender, Action::UpdateSidebar);
+    }
+
+    fn spawn_discover_dialog(&self) {
+        let dialog = DiscoverDialog::new(
+            &self.window.widget,
+            &self.sender,
+            &self.settings,
+            &self.news_flash,
+            self.threadpool.clone(),
+        );
+        dialog.widget.present();
+    }
+
+    fn add_feed_dialog(&self) {
+        if let Some(news_flash) = self.news_flash.read().as_ref() {
+            let error_message = "Failed to add feed".to_owned();
+            let add_button = self.window.content_page.sidebar.read().footer.add_button.clone();
 
             let categories = match news_flash.get_categories() {
                 Ok(categories) => categories,
@@ -1010,7 +1820,9 @@ impl App {
                 }
 
                 let feed = feed.clone();
+                let undo_action = UndoActionModel::RenameFeed(feed.feed_id.clone(), feed.label.clone(), new_label.clone());
                 Util::send(&sender, Action::RenameFeed((feed, new_label)));
+                Util::send(&sender, Action::UndoableAction(undo_action));
                 rename_dialog.emit_close();
             }));
         }
@@ -1079,7 +1891,13 @@ impl App {
                 }
 
                 let category = category.clone();
+                let undo_action = UndoActionModel::RenameCategory(
+                    category.category_id.clone(),
+                    category.label.clone(),
+                    new_label.clone(),
+                );
                 Util::send(&sender, Action::RenameCategory((category, new_label)));
+                Util::send(&sender, Action::UndoableAction(undo_action));
                 rename_dialog.emit_close();
             }));
         }
@@ -1115,12 +1933,32 @@ impl App {
             SidebarSelection::Feed(feed_id, _parent_id, label) => Some(UndoActionModel::DeleteFeed(feed_id, label)),
             SidebarSelection::Category(category_id, label) => Some(UndoActionModel::DeleteCategory(category_id, label)),
             SidebarSelection::Tag(tag_id, label) => Some(UndoActionModel::DeleteTag(tag_id, label)),
+            SidebarSelection::SmartFolder(id, _label) => {
+                self.delete_smart_folder(id);
+                None
+            }
         };
         if let Some(undo_action) = undo_action {
             Util::send(&self.sender, Action::UndoableAction(undo_action));
         }
     }
 
+    fn set_sidebar_read_older_than(&self, days: u32) {
+        if self.settings.write().set_mark_older_than_days(days).is_err() {
+            Util::send(
+                &self.sender,
+                Action::ErrorSimpleMessage("Failed to set setting 'mark older than'.".to_owned()),
+            );
+        }
+
+        let selection = self.window.state.read().get_sidebar_selection().clone();
+        let timestamp = (Utc::now() - Duration::days(i64::from(days))).timestamp();
+        Util::send(
+            &self.sender,
+            Action::UndoableAction(UndoActionModel::MarkOlderRead(selection, days, timestamp)),
+        );
+    }
+
     fn delete_feed(&self, feed_id: FeedID) {
         let news_flash = self.news_flash.clone();
         let settings = self.settings.clone();
@@ -1245,6 +2083,10 @@ impl App {
     }
 
     fn tag_article(&self, article_id: ArticleID, tag_id: TagID) {
+        if self.window.state.read().get_offline() {
+            self.queue_offline_changes(vec![OfflineChange::TagArticle { article_id, tag_id }]);
+            return;
+        }
         let news_flash = self.news_flash.clone();
         let settings = self.settings.clone();
         let sender = self.sender.clone();
@@ -1270,12 +2112,24 @@ impl App {
 
                 if let Some(tag) = tags.iter().find(|t| t.tag_id == tag_id).cloned() {
                     info!("tag article '{}' with '{}'", article_id, tag.tag_id);
-                    if let Err(error) = Runtime::new().expect(RUNTIME_ERROR).block_on(news_flash.tag_article(
+                    match Runtime::new().expect(RUNTIME_ERROR).block_on(news_flash.tag_article(
                         &article,
                         &tag,
                         &Self::build_client(&settings),
                     )) {
-                        Util::send(&sender, Action::Error("Failed to tag article.".to_owned(), error));
+                        Ok(()) => {
+                            // only a change that happened can be undone
+                            Util::send(
+                                &sender,
+                                Action::UndoableAction(UndoActionModel::ArticleChange(
+                                    vec![article_id.clone()],
+                                    BatchOperation::Tag(tag_id.clone()),
+                                )),
+                            );
+                            // smart folders may search for the tag
+                            Util::send(&sender, Action::UpdateSidebar);
+                        }
+                        Err(error) => Util::send(&sender, Action::Error("Failed to tag article.".to_owned(), error)),
                     }
                 } else {
                     let message = format!("Failed to tag article: tag with id '{}' not found.", tag_id);
@@ -1289,6 +2143,10 @@ impl App {
     }
 
     fn untag_article(&self, article_id: ArticleID, tag_id: TagID) {
+        if self.window.state.read().get_offline() {
+            self.queue_offline_changes(vec![OfflineChange::UntagArticle { article_id, tag_id }]);
+            return;
+        }
         let news_flash = self.news_flash.clone();
         let settings = self.settings.clone();
         let sender = self.sender.clone();
@@ -1314,12 +2172,24 @@ impl App {
 
                 if let Some(tag) = tags.iter().find(|t| t.tag_id == tag_id).cloned() {
                     info!("untag article '{}' with '{}'", article_id, tag.tag_id);
-                    if let Err(error) = Runtime::new().expect(RUNTIME_ERROR).block_on(news_flash.untag_article(
+                    match Runtime::new().expect(RUNTIME_ERROR).block_on(news_flash.untag_article(
                         &article,
                         &tag,
                         &Self::build_client(&settings),
                     )) {
-                        Util::send(&sender, Action::Error("Failed to untag article.".to_owned(), error));
+                        Ok(()) => {
+                            // only a change that happened can be undone
+                            Util::send(
+                                &sender,
+                                Action::UndoableAction(UndoActionModel::ArticleChange(
+                                    vec![article_id.clone()],
+                                    BatchOperation::Untag(tag_id.clone()),
+                                )),
+                            );
+                            // smart folders may search for the tag
+                            Util::send(&sender, Action::UpdateSidebar);
+                        }
+                        Err(error) => Util::send(&sender, Action::Error("Failed to untag article.".to_owned(), error)),
                     }
                 } else {
                     let message = format!("Failed to tag article: untag with id '{}' not found.", tag_id);
@@ -1333,6 +2203,36 @@ impl App {
     }
 
     fn drag_and_drop(&self, action: FeedListDndAction) {
+        // moves within the same parent only change the order and aren't recorded
+        let undo_action = match &action {
+            FeedListDndAction::MoveFeed(feed_id, from_id, to_id, _sort_index) => {
+                if from_id != to_id {
+                    Some(UndoActionModel::MoveFeed(
+                        feed_id.clone(),
+                        from_id.clone(),
+                        to_id.clone(),
+                    ))
+                } else {
+                    None
+                }
+            }
+            FeedListDndAction::MoveCategory(category_id, to_id, _sort_index) => self
+                .news_flash
+                .read()
+                .as_ref()
+                .and_then(|news_flash| news_flash.get_categories().ok())
+                .and_then(|categories| categories.into_iter().find(|c| &c.category_id == category_id))
+                .filter(|category| &category.parent_id != to_id)
+                .map(|category| UndoActionModel::MoveCategory(category_id.clone(), category.parent_id, to_id.clone())),
+        };
+        if let Some(undo_action) = undo_action {
+            self.window.show_undo_bar(undo_action);
+        }
+
+        self.move_feed_list_item(action);
+    }
+
+    fn move_feed_list_item(&self, action: FeedListDndAction) {
         let news_flash = self.news_flash.clone();
         let settings = self.settings.clone();
         let sender = self.sender.clone();
@@ -1378,24 +2278,36 @@ impl App {
                 &[("Cancel", ResponseType::Cancel), ("Save", ResponseType::Ok)],
             );
 
-            let filter = FileFilter::new();
-            filter.add_pattern("*.html");
-            filter.add_mime_type("text/html");
-            filter.set_name(Some("HTML"));
-            dialog.add_filter(&filter);
-            dialog.set_filter(&filter);
-            if let Some(title) = &article.title {
-                dialog.set_current_name(&format!("{}.html", title.replace("/", "_")));
-            } else {
-                dialog.set_current_name("Article.html");
+            // the selected filter doubles as format chooser
+            for format in ExportFormat::all() {
+                let filter = FileFilter::new();
+                filter.add_pattern(&format!("*.{}", format.extension()));
+                filter.add_mime_type(format.mime_type());
+                filter.set_name(Some(format.name()));
+                dialog.add_filter(&filter);
+                if *format == ExportFormat::Html {
+                    dialog.set_filter(&filter);
+                }
             }
+            let title = article.title.as_deref().unwrap_or("Article").replace("/", "_");
+            dialog.set_current_name(format!("{}.html", title));
+            dialog.connect_notify(Some("filter"), |dialog, _| {
+                let format = Self::selected_export_format(dialog);
+                if let Some(name) = dialog.get_current_name() {
+                    let name = PathBuf::from(name.as_str());
+                    let name = name.with_extension(format.extension()).to_string_lossy().to_string();
+                    dialog.set_current_name(&name);
+                }
+            });
 
             if let ResponseType::Ok = dialog.run() {
                 self.window.content_header.start_more_actions_spinner();
 
                 let news_flash = self.news_flash.clone();
                 let global_sender = self.sender.clone();
+                let format = Self::selected_export_format(&dialog);
                 let filename = match dialog.get_filename() {
+                    Some(filename) if filename.extension().is_none() => filename.with_extension(format.extension()),
                     Some(filename) => filename,
                     None => {
                         Util::send(&self.sender, Action::ErrorSimpleMessage("No filename set.".to_owned()));
@@ -1446,19 +2358,11 @@ impl App {
                                 return;
                             }
                         };
-                        let html = ArticleView::build_article_static(
-                            "article",
-                            &article,
-                            &feed.label,
-                            &settings,
-                            None,
-                            None,
-                            true,
-                        );
-                        if FileUtil::write_text_file(&filename, &html).is_err() {
+                        if let Err(error) = ArticleExport::write(format, &article, &feed.label, &settings, &filename) {
+                            error!("Failed to export article: {}", error);
                             Util::send(
                                 &global_sender,
-                                Action::ErrorSimpleMessage("Failed to write OPML data to disc.".to_owned()),
+                                Action::ErrorSimpleMessage("Failed to export article.".to_owned()),
                             );
                         }
                     }
@@ -1477,6 +2381,306 @@ impl App {
         }
     }
 
+    fn print_article(&self, pdf_path: Option<PathBuf>) {
+        if let Err(error) = self
+            .window
+            .content_page
+            .article_view
+            .print(&self.window.widget, pdf_path.as_deref())
+        {
+            error!("Failed to print article: {}", error);
+            Util::send(
+                &self.sender,
+                Action::ErrorSimpleMessage("Failed to print article.".to_owned()),
+            );
+        }
+    }
+
+    fn save_article_pdf(&self) {
+        if let Some(article) = self.window.content_page.article_view.get_visible_article() {
+            let dialog = FileChooserDialog::with_buttons(
+                Some(&i18n("Save as PDF")),
+                Some(&self.window.widget),
+                FileChooserAction::Save,
+                &[
+                    (&i18n("Cancel"), ResponseType::Cancel),
+                    (&i18n("Save"), ResponseType::Ok),
+                ],
+            );
+            dialog.set_do_overwrite_confirmation(true);
+
+            let filter = FileFilter::new();
+            filter.add_pattern("*.pdf");
+            filter.add_mime_type("application/pdf");
+            filter.set_name(Some("PDF"));
+            dialog.add_filter(&filter);
+            dialog.set_filter(&filter);
+            let title = article.title.as_deref().unwrap_or("Article").replace("/", "_");
+            dialog.set_current_name(format!("{}.pdf", title));
+
+            if let ResponseType::Ok = dialog.run() {
+                match dialog.get_filename() {
+                    Some(filename) if filename.extension().is_none() => {
+                        self.print_article(Some(filename.with_extension("pdf")))
+                    }
+                    Some(filename) => self.print_article(Some(filename)),
+                    None => Util::send(&self.sender, Action::ErrorSimpleMessage("No filename set.".to_owned())),
+                }
+            }
+            dialog.emit_close();
+        }
+    }
+
+    fn download_enclosures(&self) {
+        if let Some(article) = self.window.content_page.article_view.get_visible_article() {
+            let (sender, receiver) = oneshot::channel::<()>();
+            self.window.content_header.start_more_actions_spinner();
+
+            let enclosures = Enclosure::from_article(&article);
+            let global_sender = self.sender.clone();
+            let settings = self.settings.clone();
+            let thread_future = async move {
+                let client = Self::build_client(&settings);
+                let downloads = EnclosureDownloads::new();
+                let mut runtime = Runtime::new().expect(RUNTIME_ERROR);
+                if let Err(error) = runtime.block_on(downloads.download_all(&enclosures, &client)) {
+                    error!("Failed to download media: {}", error);
+                    Util::send(
+                        &global_sender,
+                        Action::ErrorSimpleMessage("Failed to download media.".to_owned()),
+                    );
+                }
+                sender.send(()).expect(CHANNEL_ERROR);
+            };
+
+            let glib_future = receiver.map(clone!(
+                @weak self.window.content_header as content_header,
+                @strong self.sender as sender => @default-panic, move |_res| {
+                content_header.stop_more_actions_spinner();
+                // offline the players switch over to the downloaded files
+                Util::send(&sender, Action::RedrawArticle);
+            }));
+
+            self.threadpool.spawn_ok(thread_future);
+            Util::glib_spawn_future(glib_future);
+        }
+    }
+
+    fn selected_export_format(dialog: &FileChooserDialog) -> ExportFormat {
+        dialog
+            .get_filter()
+            .and_then(|filter| filter.get_name())
+            .and_then(|name| ExportFormat::from_name(&name))
+            .unwrap_or(ExportFormat::Html)
+    }
+
+    fn export_articles(&self, article_ids: Vec<ArticleID>) {
+        let (sender, receiver) = oneshot::channel::<()>();
+
+        let dialog = FileChooserDialog::with_buttons(
+            Some("Export Articles"),
+            Some(&self.window.widget),
+            FileChooserAction::SelectFolder,
+            &[("Cancel", ResponseType::Cancel), ("Export", ResponseType::Ok)],
+        );
+
+        if let ResponseType::Ok = dialog.run() {
+            let directory = match dialog.get_filename() {
+                Some(directory) => directory,
+                None => {
+                    Util::send(&self.sender, Action::ErrorSimpleMessage("No folder set.".to_owned()));
+                    dialog.emit_close();
+                    return;
+                }
+            };
+            self.window.content_header.start_more_actions_spinner();
+
+            let news_flash = self.news_flash.clone();
+            let global_sender = self.sender.clone();
+            let window_state = self.window.state.clone();
+            let settings = self.settings.clone();
+            let thread_future = async move {
+                if let Some(news_flash) = news_flash.read().as_ref() {
+                    let offline = window_state.read().get_offline();
+                    let client = Self::build_client(&settings);
+                    match ArticleExport::write_html_pages(
+                        news_flash,
+                        &article_ids,
+                        &settings,
+                        &directory,
+                        offline,
+                        &client,
+                    ) {
+                        Ok(0) => {}
+                        Ok(failed) => Util::send(
+                            &global_sender,
+                            Action::ErrorSimpleMessage(format!("Failed to export {} articles.", failed)),
+                        ),
+                        Err(error) => {
+                            error!("Failed to export articles: {}", error);
+                            Util::send(
+                                &global_sender,
+                                Action::ErrorSimpleMessage("Failed to export articles.".to_owned()),
+                            );
+                        }
+                    }
+                }
+                sender.send(()).expect(CHANNEL_ERROR);
+            };
+
+            let glib_future = receiver.map(
+                clone!(@weak self.window.content_header as content_header => @default-panic, move |_res| {
+                    content_header.stop_more_actions_spinner();
+                }),
+            );
+
+            self.threadpool.spawn_ok(thread_future);
+            Util::glib_spawn_future(glib_future);
+        }
+        dialog.emit_close();
+    }
+
+    fn export_digest(&self) {
+        let sidebar_selection = self.window.state.read().get_sidebar_selection().clone();
+        let header_selection = self.window.state.read().get_header_selection().clone();
+
+        let dialog = FileChooserDialog::with_buttons(
+            Some(&i18n("Export Digest")),
+            Some(&self.window.widget),
+            FileChooserAction::SelectFolder,
+            &[
+                (&i18n("Cancel"), ResponseType::Cancel),
+                (&i18n("Export"), ResponseType::Ok),
+            ],
+        );
+
+        let format_combo = ComboBoxText::new();
+        format_combo.append(Some(DigestFormat::Epub.to_id()), "EPUB");
+        format_combo.append(Some(DigestFormat::Html.to_id()), &i18n("HTML Pages"));
+        format_combo.set_active_id(Some(DigestFormat::Epub.to_id()));
+        let repeat_check = CheckButton::with_label(&i18n("Export again after every sync"));
+
+        // the same selection is scheduled already: preset its options
+        let schedule = self.settings.read().get_digest_schedule();
+        let scheduled = schedule
+            .as_ref()
+            .map(|schedule| {
+                schedule.sidebar_selection == sidebar_selection && schedule.header_selection == header_selection
+            })
+            .unwrap_or(false);
+        if let Some(schedule) = schedule.as_ref().filter(|_| scheduled) {
+            format_combo.set_active_id(Some(schedule.format.to_id()));
+            dialog.set_current_folder(&schedule.directory);
+            repeat_check.set_active(true);
+        }
+
+        let options = gtk::Box::new(Orientation::Horizontal, 12);
+        options.pack_start(&Label::new(Some(&i18n("Format"))), false, false, 0);
+        options.pack_start(&format_combo, false, false, 0);
+        options.pack_start(&repeat_check, false, false, 0);
+        options.show_all();
+        dialog.set_extra_widget(&options);
+
+        if let ResponseType::Ok = dialog.run() {
+            match dialog.get_filename() {
+                Some(directory) => {
+                    let format = format_combo
+                        .get_active_id()
+                        .and_then(|id| DigestFormat::from_id(&id))
+                        .unwrap_or(DigestFormat::Epub);
+                    let digest = DigestSchedule {
+                        sidebar_selection,
+                        header_selection,
+                        format,
+                        directory,
+                    };
+
+                    // there is only one schedule: a new one replaces the old
+                    let new_schedule = if repeat_check.get_active() {
+                        Some(digest.clone())
+                    } else if scheduled {
+                        None
+                    } else {
+                        schedule
+                    };
+                    if self.settings.write().set_digest_schedule(new_schedule).is_err() {
+                        Util::send(
+                            &self.sender,
+                            Action::ErrorSimpleMessage("Failed to set setting 'digest schedule'.".to_owned()),
+                        );
+                    }
+
+                    self.write_digest(digest, true);
+                }
+                None => Util::send(&self.sender, Action::ErrorSimpleMessage("No folder set.".to_owned())),
+            }
+        }
+        dialog.emit_close();
+    }
+
+    // only rebuilt on a new day or if the sync brought new articles
+    fn export_scheduled_digest(&self, new_article_count: i64) {
+        if let Some(schedule) = self.settings.read().get_digest_schedule() {
+            let title = DigestExport::title(&schedule.sidebar_selection, &schedule.header_selection);
+            if new_article_count == 0 && DigestExport::path(schedule.format, &title, &schedule.directory).exists() {
+                return;
+            }
+            self.write_digest(schedule, false);
+        }
+    }
+
+    // `show_progress`: spin the header button, only for exports the user started
+    fn write_digest(&self, digest: DigestSchedule, show_progress: bool) {
+        let (sender, receiver) = oneshot::channel::<()>();
+        if show_progress {
+            self.window.content_header.start_more_actions_spinner();
+        }
+
+        let news_flash = self.news_flash.clone();
+        let global_sender = self.sender.clone();
+        let window_state = self.window.state.clone();
+        let settings = self.settings.clone();
+        let media_articles = self.window.content_page.media_articles.clone();
+        let thread_future = async move {
+            if let Some(news_flash) = news_flash.read().as_ref() {
+                let offline = window_state.read().get_offline();
+                let client = Self::build_client(&settings);
+                match DigestExport::build(news_flash, &settings, &media_articles, &digest, offline, &client) {
+                    Ok(Some(digest)) => {
+                        info!("Digest written to '{}'", digest.path.display());
+                        if digest.truncated {
+                            let message = format!(
+                                "The digest only contains the first {} articles of the selection.",
+                                MAX_DIGEST_ARTICLES
+                            );
+                            Util::send(&global_sender, Action::ErrorSimpleMessage(message));
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(error) => {
+                        error!("Failed to export digest: {}", error);
+                        Util::send(
+                            &global_sender,
+                            Action::ErrorSimpleMessage("Failed to export digest.".to_owned()),
+                        );
+                    }
+                }
+            }
+            sender.send(()).expect(CHANNEL_ERROR);
+        };
+
+        let glib_future = receiver.map(
+            clone!(@weak self.window.content_header as content_header => @default-panic, move |_res| {
+                if show_progress {
+                    content_header.stop_more_actions_spinner();
+                }
+            }),
+        );
+
+        self.threadpool.spawn_ok(thread_future);
+        Util::glib_spawn_future(glib_future);
+    }
+
     fn start_grab_article_content(&self) {
         let (sender, receiver) = oneshot::channel::<Result<FatArticle, NewsFlashError>>();
 
@@ -1546,82 +2750,51 @@ impl App {
     }
 
     fn import_opml(&self) {
-        let (sender, receiver) = oneshot::channel::<()>();
-
         let dialog = FileChooserDialog::with_buttons(
             Some(&i18n("Import OPML")),
             Some(&self.window.widget),
             FileChooserAction::Open,
             &[
                 (&i18n("Cancel"), ResponseType::Cancel),
-                (&i18n("Import"), ResponseType::Ok),
+                (&i18n("Open"), ResponseType::Ok),
             ],
         );
 
-        let filter = FileFilter::new();
-        filter.add_pattern("*.OPML");
-        filter.add_pattern("*.opml");
-        filter.add_mime_type("application/xml");
-        filter.add_mime_type("text/xml");
-        filter.add_mime_type("text/x-opml");
-        filter.set_name(Some("OPML"));
+        let filter = OpmlImport::file_filter();
         dialog.add_filter(&filter);
         dialog.set_filter(&filter);
 
-        let glib_future = receiver.map(clone!(
-            @strong self.sender as sender,
-            @weak self.window as window => @default-panic, move |res| match res
-        {
-            Ok(()) => {
-                window.content_header.finish_sync();
-            }
-            Err(error) => {
-                let message = format!("Sender error: {}", error);
-                error!("{}", message);
-                Util::send(&sender, Action::ErrorSimpleMessage(message));
-            }
-        }));
-
-        Util::glib_spawn_future(glib_future);
-
-        if let ResponseType::Ok = dialog.run() {
-            if let Some(filename) = dialog.get_filename() {
-                if let Ok(opml_content) = FileUtil::read_text_file(&filename) {
-                    let news_flash = self.news_flash.clone();
-                    let global_sender = self.sender.clone();
-                    let settings = self.settings.clone();
-                    let thread_future = async move {
-                        if let Some(news_flash) = news_flash.read().as_ref() {
-                            let result = Runtime::new().expect(RUNTIME_ERROR).block_on(news_flash.import_opml(
-                                &opml_content,
-                                false,
-                                &Self::build_client(&settings),
-                            ));
-
-                            if let Err(error) = result {
-                                Util::send(
-                                    &global_sender,
-                                    Action::Error("Failed to import OPML.".to_owned(), error),
-                                );
-                            } else {
-                                Util::send(&global_sender, Action::UpdateSidebar);
-                            }
+        let response = dialog.run();
+        let filename = dialog.get_filename();
+        dialog.emit_close();
 
-                            sender.send(()).expect(CHANNEL_ERROR);
-                        }
-                    };
-                    self.threadpool.spawn_ok(thread_future);
-                    self.window.content_header.start_sync();
-                } else {
+        if let (ResponseType::Ok, Some(filename)) = (response, filename) {
+            let result = match self.news_flash.read().as_ref() {
+                Some(news_flash) => OpmlImport::open(&filename, news_flash),
+                None => return,
+            };
+            match result {
+                Ok((import, categories)) => {
+                    let dialog = OpmlImportDialog::new(
+                        &self.window.widget,
+                        &self.sender,
+                        &self.settings,
+                        &self.news_flash,
+                        self.threadpool.clone(),
+                        import,
+                        categories,
+                    );
+                    dialog.widget.present();
+                }
+                Err(error) => {
+                    error!("Failed to import OPML: {}", error);
                     Util::send(
                         &self.sender,
-                        Action::ErrorSimpleMessage("Failed to read content of OPML file.".to_owned()),
+                        Action::ErrorSimpleMessage(format!("Failed to import OPML: {}", error)),
                     );
                 }
             }
         }
-
-        dialog.emit_close();
     }
 
     fn export_opml(&self) {
@@ -1635,13 +2808,7 @@ impl App {
             ],
         );
 
-        let filter = FileFilter::new();
-        filter.add_pattern("*.OPML");
-        filter.add_pattern("*.opml");
-        filter.add_mime_type("application/xml");
-        filter.add_mime_type("text/xml");
-        filter.add_mime_type("text/x-opml");
-        filter.set_name(Some("OPML"));
+        let filter = OpmlImport::file_filter();
         dialog.add_filter(&filter);
         dialog.set_filter(&filter);
         dialog.set_current_name("NewsFlash.OPML");
@@ -1705,6 +2872,12 @@ impl App {
     }
 
     fn set_offline(&self, offline: bool) {
+        let was_offline = self.window.state.read().get_offline();
+        *self.network_offline.write() = false;
+
+        if let Some(dbus) = self.dbus.read().as_ref() {
+            dbus.set_offline(offline);
+        }
         self.window.state.write().set_offline(offline);
         self.window.content_header.set_offline(offline);
         self.window.content_page.sidebar.read().footer.update();
@@ -1715,6 +2888,118 @@ impl App {
             .feed_list
             .read()
             .update_offline();
+
+        if offline {
+            GtkUtil::remove_source(self.sync_source_id.write().take());
+        } else if was_offline {
+            // catch up with everything that happened while offline
+            Util::send(&self.sender, Action::ScheduleSync);
+            Util::send(&self.sender, Action::Sync);
+        }
+    }
+
+    // offline mode that was entered manually is left alone
+    fn network_changed(&self, available: bool) {
+        if self.news_flash.read().is_none() {
+            return;
+        }
+
+        let offline = self.window.state.read().get_offline();
+        if !available && !offline {
+            info!("Network connection lost: switching into offline mode");
+            self.set_offline(true);
+            *self.network_offline.write() = true;
+        } else if available && offline && *self.network_offline.read() {
+            info!("Network connection restored: switching into online mode");
+            self.set_offline(false);
+        }
+    }
+
+    fn queue_offline_changes(&self, changes: Vec<OfflineChange>) {
+        if changes.is_empty() {
+            return;
+        }
+
+        if let Err(error) = self.offline_queue.write().push(changes.clone()) {
+            error!("Failed to queue offline changes: {}", error);
+            Util::send(
+                &self.sender,
+                Action::ErrorSimpleMessage("Failed to save changes made while offline.".to_owned()),
+            );
+            return;
+        }
+
+        let visible_article_id = self
+            .window
+            .content_page
+            .article_view
+            .get_visible_article()
+            .map(|article| article.article_id);
+        for change in &changes {
+            self.window.content_page.article_list.read().set_article_row_state(
+                change.article_id(),
+                change.read_state(),
+                change.marked_state(),
+            );
+            if visible_article_id.as_ref() == Some(change.article_id()) {
+                self.window
+                    .content_page
+                    .article_view
+                    .update_visible_article(change.read_state(), change.marked_state());
+            }
+        }
+    }
+
+    // runs after a sync so the local database reflects what happened on the server in the meantime
+    fn flush_offline_queue(&self) {
+        if self.window.state.read().get_offline() || self.offline_queue.read().is_empty() {
+            return;
+        }
+
+        let (sender, receiver) = oneshot::channel::<(Vec<OfflineChange>, usize)>();
+
+        let changes = self.offline_queue.read().changes().to_vec();
+        let news_flash = self.news_flash.clone();
+        let settings = self.settings.clone();
+        let thread_future = async move {
+            let mut result = (Vec::new(), 0);
+            if let Some(news_flash) = news_flash.read().as_ref() {
+                result = OfflineQueue::replay(news_flash, &changes, &Self::build_client(&settings));
+            }
+            sender.send(result).expect(CHANNEL_ERROR);
+        };
+
+        let glib_future = receiver.map(clone!(
+            @strong self.offline_queue as offline_queue,
+            @strong self.sender as sender => @default-panic, move |res|
+        {
+            if let Ok((handled, skipped)) = res {
+                info!("Replayed {} changes made while offline", handled.len() - skipped);
+                if let Err(error) = offline_queue.write().remove(&handled) {
+                    error!("Failed to update offline queue: {}", error);
+                }
+                if skipped > 0 {
+                    let message = format!(
+                        "{} changes made while offline were skipped because the articles were changed elsewhere.",
+                        skipped
+                    );
+                    Util::send(&sender, Action::ErrorSimpleMessage(message));
+                }
+            }
+            Util::send(&sender, Action::UpdateArticleList);
+            Util::send(&sender, Action::UpdateSidebar);
+        }));
+
+        self.threadpool.spawn_ok(thread_future);
+        Util::glib_spawn_future(glib_future);
+    }
+
+    fn update_dbus_unread_count(&self) {
+        if let Some(dbus) = self.dbus.read().as_ref() {
+            if let Some(news_flash) = self.news_flash.read().as_ref() {
+                dbus.update_unread_count(news_flash);
+            }
+        }
     }
 
     pub fn build_client(settings: &Arc<RwLock<Settings>>) -> Client {
@@ -1,6 +1,8 @@
-use super::models::{ArticleListArticleModel, ArticleListModel, MarkUpdate, ReadUpdate};
+use super::models::{ArticleListArticleModel, ArticleListModel, BatchOperation, MarkUpdate, ReadUpdate};
 use crate::app::Action;
 use crate::main_window_state::MainWindowState;
+use crate::offline::OfflineChange;
+use crate::undo_bar::UndoActionModel;
 use crate::util::{BuilderHelper, DateUtil, GtkUtil, Util};
 use chrono::NaiveDateTime;
 use futures::channel::oneshot;
@@ -188,14 +190,11 @@ impl ArticleRow {
             eventbox
                 .connect_enter_notify_event(clone!(
                     @weak unread_stack,
-                    @weak state as window_state,
                     @weak read => @default-panic, move |_widget, _event|
                 {
-                    if !window_state.read().get_offline() {
-                        match *read.read() {
-                            Read::Unread => unread_stack.set_visible_child_name("read"),
-                            Read::Read => unread_stack.set_visible_child_name("unread"),
-                        }
+                    match *read.read() {
+                        Read::Unread => unread_stack.set_visible_child_name("read"),
+                        Read::Read => unread_stack.set_visible_child_name("unread"),
                     }
                     Inhibit(false)
                 }))
@@ -206,15 +205,12 @@ impl ArticleRow {
         vec.push((
             eventbox
                 .connect_leave_notify_event(clone!(
-                    @weak state as window_state,
                     @weak unread_stack,
                     @weak read => @default-panic, move |_widget, _event|
                 {
-                    if !window_state.read().get_offline() {
-                        match *read.read() {
-                            Read::Unread => unread_stack.set_visible_child_name("unread"),
-                            Read::Read => unread_stack.set_visible_child_name("read"),
-                        }
+                    match *read.read() {
+                        Read::Unread => unread_stack.set_visible_child_name("unread"),
+                        Read::Read => unread_stack.set_visible_child_name("read"),
                     }
                     Inhibit(false)
                 }))
@@ -242,7 +238,10 @@ impl ArticleRow {
                         _ => {}
                     }
                     if window_state.read().get_offline() {
-                        return Inhibit(false);
+                        let current = *read.read();
+                        let change = OfflineChange::read(article_id.clone(), current.invert(), current);
+                        Util::send(&sender, Action::QueueOfflineChanges(vec![change]));
+                        return Inhibit(true);
                     }
 
                     let new_state = read.read().invert();
@@ -254,6 +253,13 @@ impl ArticleRow {
                         read: new_state,
                     };
                     Util::send(&sender, Action::MarkArticleRead(update));
+                    Util::send(
+                        &sender,
+                        Action::UndoableAction(UndoActionModel::ArticleChange(
+                            vec![article_id.clone()],
+                            BatchOperation::from_read(new_state),
+                        )),
+                    );
                     Inhibit(true)
                 }))
                 .to_glib() as usize,
@@ -278,14 +284,11 @@ impl ArticleRow {
             eventbox
                 .connect_enter_notify_event(clone!(
                     @weak marked_stack,
-                    @weak state as window_state,
                     @weak marked => @default-panic, move |_widget, _event|
                 {
-                    if !window_state.read().get_offline() {
-                        match *marked.read() {
-                            Marked::Marked => marked_stack.set_visible_child_name("unmarked"),
-                            Marked::Unmarked => marked_stack.set_visible_child_name("marked"),
-                        }
+                    match *marked.read() {
+                        Marked::Marked => marked_stack.set_visible_child_name("unmarked"),
+                        Marked::Unmarked => marked_stack.set_visible_child_name("marked"),
                     }
                     Inhibit(false)
                 }))
@@ -297,14 +300,11 @@ impl ArticleRow {
             eventbox
                 .connect_leave_notify_event(clone!(
                     @weak marked_stack,
-                    @weak state as window_state,
                     @weak marked => @default-panic, move |_widget, _event|
                 {
-                    if !window_state.read().get_offline() {
-                        match *marked.read() {
-                            Marked::Marked => marked_stack.set_visible_child_name("marked"),
-                            Marked::Unmarked => marked_stack.set_visible_child_name("unmarked"),
-                        }
+                    match *marked.read() {
+                        Marked::Marked => marked_stack.set_visible_child_name("marked"),
+                        Marked::Unmarked => marked_stack.set_visible_child_name("unmarked"),
                     }
                     Inhibit(false)
                 }))
@@ -331,7 +331,10 @@ impl ArticleRow {
                         _ => {}
                     }
                     if window_state.read().get_offline() {
-                        return Inhibit(false);
+                        let current = *marked.read();
+                        let change = OfflineChange::marked(article_id.clone(), current.invert(), current);
+                        Util::send(&sender, Action::QueueOfflineChanges(vec![change]));
+                        return Inhibit(true);
                     }
                     let new_marked = marked.read().invert();
                     *marked.write() = new_marked;
@@ -342,6 +345,13 @@ impl ArticleRow {
                         marked: new_marked,
                     };
                     Util::send(&sender, Action::MarkArticle(update));
+                    Util::send(
+                        &sender,
+                        Action::UndoableAction(UndoActionModel::ArticleChange(
+                            vec![article_id.clone()],
+                            BatchOperation::from_marked(new_marked),
+                        )),
+                    );
                     Inhibit(true)
                 }))
                 .to_glib() as usize,
@@ -0,0 +1,47 @@
+use super::models::{BatchOperation, BatchUpdate};
+use log::warn;
+use news_flash::models::{Marked, Read};
+use news_flash::{NewsFlash, NewsFlashError};
+use reqwest::Client;
+
+impl BatchUpdate {
+    pub async fn execute(&self, news_flash: &NewsFlash, client: &Client) -> Result<(), NewsFlashError> {
+        match &self.operation {
+            BatchOperation::MarkRead => news_flash.set_article_read(&self.article_ids, Read::Read, client).await,
+            BatchOperation::MarkUnread => {
+                news_flash
+                    .set_article_read(&self.article_ids, Read::Unread, client)
+                    .await
+            }
+            BatchOperation::Star => {
+                news_flash
+                    .set_article_marked(&self.article_ids, Marked::Marked, client)
+                    .await
+            }
+            BatchOperation::Unstar => {
+                news_flash
+                    .set_article_marked(&self.article_ids, Marked::Unmarked, client)
+                    .await
+            }
+            BatchOperation::Tag(tag_id) | BatchOperation::Untag(tag_id) => {
+                let tag = match news_flash.get_tags()?.into_iter().find(|tag| &tag.tag_id == tag_id) {
+                    Some(tag) => tag,
+                    None => {
+                        // the tag was deleted in the meantime, nothing left to (un)tag
+                        warn!("Tag with id '{}' not found", tag_id);
+                        return Ok(());
+                    }
+                };
+                for article_id in &self.article_ids {
+                    let article = news_flash.get_article(article_id)?;
+                    if let BatchOperation::Tag(_tag_id) = &self.operation {
+                        news_flash.tag_article(&article, &tag, client).await?;
+                    } else {
+                        news_flash.untag_article(&article, &tag, client).await?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 mod article_row;
+mod batch;
 mod models;
 mod single;
 
@@ -7,19 +8,27 @@ use crate::content_page::ContentHeader;
 use crate::content_page::HeaderSelection;
 use crate::i18n::{i18n, i18n_f};
 use crate::main_window_state::MainWindowState;
+use crate::offline::OfflineChange;
 use crate::settings::Settings;
 use crate::sidebar::models::SidebarSelection;
 use crate::util::{BuilderHelper, GtkUtil, Util};
 use gdk::RGBA;
+use gio::{ActionMapExt, Menu, MenuItem, SimpleAction, SimpleActionGroup};
 use glib::{clone, source::Continue, translate::ToGlib, Sender};
-use gtk::{Label, LabelExt, ListBoxExt, ListBoxRowExt, ScrolledWindow, Stack, StackExt, StackTransitionType};
+use gtk::{
+    AdjustmentExt, Label, LabelExt, ListBoxExt, ListBoxRowExt, Popover, PopoverExt, PositionType, ScrolledWindow,
+    ScrolledWindowExt, Stack, StackExt, StackTransitionType, WidgetExt,
+};
 use models::ArticleListChangeSet;
-pub use models::{ArticleListArticleModel, ArticleListModel, MarkUpdate, ReadUpdate};
-use news_flash::models::{ArticleID, Marked, Read};
+pub use models::{ArticleListArticleModel, ArticleListModel, BatchOperation, BatchUpdate, MarkUpdate, ReadUpdate};
+use news_flash::models::{ArticleID, Marked, Read, Tag};
 use parking_lot::RwLock;
 use single::SingleArticleList;
 use std::sync::Arc;
 
+// delay after the last scroll event before rows scrolled past the top are marked read
+const MARK_READ_ON_SCROLL_DELAY: u32 = 1000;
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum CurrentList {
     List1,
@@ -39,6 +48,8 @@ pub struct ArticleList {
     current_list: Arc<RwLock<CurrentList>>,
     settings: Arc<RwLock<Settings>>,
     empty_label: Label,
+    mark_read_queue: Arc<RwLock<Vec<ArticleID>>>,
+    mark_read_source: Arc<RwLock<Option<u32>>>,
 }
 
 impl ArticleList {
@@ -77,9 +88,13 @@ impl ArticleList {
             current_list: Arc::new(RwLock::new(CurrentList::List1)),
             settings,
             empty_label,
+            mark_read_queue: Arc::new(RwLock::new(Vec::new())),
+            mark_read_source: Arc::new(RwLock::new(None)),
         };
 
         article_list.setup_list_selected_singal();
+        article_list.setup_mark_read_on_scroll(&article_list.list_1);
+        article_list.setup_mark_read_on_scroll(&article_list.list_2);
 
         article_list
     }
@@ -92,6 +107,10 @@ impl ArticleList {
         self.list_1.read().get_background_color()
     }
 
+    pub fn get_article_count(&self) -> usize {
+        self.list_model.read().len()
+    }
+
     pub fn get_relevant_article_count(&self, header_selection: &HeaderSelection) -> usize {
         self.list_model.read().get_relevant_count(header_selection)
     }
@@ -243,12 +262,20 @@ impl ArticleList {
                 @weak self.global_state as global_state,
                 @weak self.current_list as current_list,
                 @weak self.list_1 as list_1,
-                @weak self.list_2 as list_2 => @default-panic, move |_list, row|
+                @weak self.list_2 as list_2 => @default-panic, move |list, row|
             {
+                // ctrl/shift clicks extend the selection instead of opening an article
+                if list.get_selected_rows().len() > 1 {
+                    return;
+                }
+
                 let selected_index = row.get_index();
                 let selected_article = list_model.write().calculate_selection(selected_index).cloned();
                 if let Some(selected_article) = selected_article {
-                    if selected_article.read == Read::Unread && !global_state.read().get_offline() {
+                    if selected_article.read == Read::Unread && global_state.read().get_offline() {
+                        let change = OfflineChange::read(selected_article.id.clone(), Read::Read, Read::Unread);
+                        Util::send(&sender, Action::QueueOfflineChanges(vec![change]));
+                    } else if selected_article.read == Read::Unread {
                         let update = ReadUpdate {
                             article_id: selected_article.id.clone(),
                             read: Read::Read,
@@ -269,6 +296,71 @@ impl ArticleList {
         self.list_activate_signal = Some(activate_signal_id as usize);
     }
 
+    fn setup_mark_read_on_scroll(&self, single_list: &Arc<RwLock<SingleArticleList>>) {
+        let list_box = single_list.read().list();
+        let vadjustment = match single_list.read().widget().get_vadjustment() {
+            Some(vadjustment) => vadjustment,
+            None => return,
+        };
+
+        vadjustment.connect_value_changed(clone!(
+            @weak list_box,
+            @weak single_list,
+            @weak self.list_model as list_model,
+            @weak self.settings as settings,
+            @weak self.mark_read_queue as mark_read_queue,
+            @weak self.mark_read_source as mark_read_source,
+            @strong self.sender as sender => @default-panic, move |vadjustment|
+        {
+            if !list_box.get_mapped() || !settings.read().get_article_list_mark_read_on_scroll() {
+                return;
+            }
+
+            // all rows above the one at the top edge of the viewport are scrolled past
+            let top_index = match list_box.get_row_at_y(vadjustment.get_value() as i32) {
+                Some(row) if row.get_index() > 0 => row.get_index() as usize,
+                _ => return,
+            };
+            let scrolled_past: Vec<ArticleID> = list_model
+                .read()
+                .models()
+                .iter()
+                .take(top_index)
+                .filter(|article| article.read == Read::Unread)
+                .map(|article| article.id.clone())
+                .collect();
+            if scrolled_past.is_empty() {
+                return;
+            }
+
+            for article_id in &scrolled_past {
+                Self::set_article_state_static(article_id, Some(Read::Read), None, &single_list, &list_model);
+            }
+            mark_read_queue.write().extend(scrolled_past);
+
+            // debounce: only talk to the backend once scrolling came to a rest
+            GtkUtil::remove_source(mark_read_source.write().take());
+            mark_read_source.write().replace(
+                gtk::timeout_add(
+                    MARK_READ_ON_SCROLL_DELAY,
+                    clone!(
+                        @weak mark_read_queue,
+                        @weak mark_read_source,
+                        @strong sender => @default-panic, move ||
+                    {
+                        mark_read_source.write().take();
+                        let article_ids: Vec<ArticleID> = mark_read_queue.write().drain(..).collect();
+                        if !article_ids.is_empty() {
+                            Util::send(&sender, Action::MarkArticlesRead(article_ids));
+                        }
+                        Continue(false)
+                    }),
+                )
+                .to_glib(),
+            );
+        }));
+    }
+
     fn require_new_list(&self, new_state: &RwLock<MainWindowState>) -> bool {
         if self.local_state == *new_state.read()
             && self.settings.read().get_article_list_order() == self.list_model.read().order()
@@ -364,6 +456,26 @@ impl ArticleList {
                     None => i18n_f("No starred articles in tag \"{}\"", &[&title]),
                 },
             },
+            SidebarSelection::SmartFolder(_id, title) => match new_state.read().get_header_selection() {
+                HeaderSelection::All => match new_state.read().get_search_term() {
+                    Some(search) => i18n_f("No articles that fit \"{}\" in smart folder \"{}\"", &[&search, &title]),
+                    None => i18n_f("No articles in smart folder \"{}\"", &[&title]),
+                },
+                HeaderSelection::Unread => match new_state.read().get_search_term() {
+                    Some(search) => i18n_f(
+                        "No unread articles that fit \"{}\" in smart folder \"{}\"",
+                        &[&search, &title],
+                    ),
+                    None => i18n_f("No unread articles in smart folder \"{}\"", &[&title]),
+                },
+                HeaderSelection::Marked => match new_state.read().get_search_term() {
+                    Some(search) => i18n_f(
+                        "No starred articles that fit \"{}\" in smart folder \"{}\"",
+                        &[&search, &title],
+                    ),
+                    None => i18n_f("No starred articles in smart folder \"{}\"", &[&title]),
+                },
+            },
         }
     }
 
@@ -428,6 +540,131 @@ impl ArticleList {
         None
     }
 
+    pub fn get_selected_article_models(&self) -> Vec<ArticleListArticleModel> {
+        let current_list = match self.get_current_list() {
+            Some(current_list) => current_list,
+            None => return Vec::new(),
+        };
+        let selected_indices = current_list.read().get_selected_indices();
+        let mut list_model = self.list_model.write();
+        selected_indices
+            .into_iter()
+            .filter_map(|index| list_model.calculate_selection(index).cloned())
+            .collect()
+    }
+
+    pub fn filter_unchanged(
+        &self,
+        article_ids: Vec<ArticleID>,
+        read: Option<Read>,
+        marked: Option<Marked>,
+    ) -> Vec<ArticleID> {
+        if read.is_none() && marked.is_none() {
+            return article_ids;
+        }
+
+        let list_model = self.list_model.read();
+        article_ids
+            .into_iter()
+            .filter(|id| match list_model.models().iter().find(|model| &model.id == id) {
+                Some(model) => {
+                    read.map(|read| model.read != read).unwrap_or(false)
+                        || marked.map(|marked| model.marked != marked).unwrap_or(false)
+                }
+                None => true,
+            })
+            .collect()
+    }
+
+    pub fn show_context_menu(&self, tags: &[Tag]) {
+        let current_list = match self.get_current_list() {
+            Some(current_list) => current_list,
+            None => return,
+        };
+        let row = match current_list.read().get_context_row() {
+            Some(row) => row,
+            None => return,
+        };
+        let article_ids: Vec<ArticleID> = self
+            .get_selected_article_models()
+            .into_iter()
+            .map(|model| model.id)
+            .collect();
+        if article_ids.is_empty() {
+            return;
+        }
+
+        let model = Menu::new();
+        let state_section = Menu::new();
+        let tag_menu = Menu::new();
+        let untag_menu = Menu::new();
+        let export_section = Menu::new();
+
+        // `None` exports the selection instead of changing its state
+        let mut entries = vec![
+            (&state_section, i18n("Mark as Read"), Some(BatchOperation::MarkRead)),
+            (&state_section, i18n("Mark as Unread"), Some(BatchOperation::MarkUnread)),
+            (&state_section, i18n("Star"), Some(BatchOperation::Star)),
+            (&state_section, i18n("Unstar"), Some(BatchOperation::Unstar)),
+        ];
+        for tag in tags {
+            entries.push((
+                &tag_menu,
+                tag.label.clone(),
+                Some(BatchOperation::Tag(tag.tag_id.clone())),
+            ));
+            entries.push((
+                &untag_menu,
+                tag.label.clone(),
+                Some(BatchOperation::Untag(tag.tag_id.clone())),
+            ));
+        }
+        entries.push((&export_section, i18n("Export…"), None));
+
+        // the actions live as long as the popover, so they don't pile up on the window
+        let action_group = SimpleActionGroup::new();
+        for (index, (section, label, operation)) in entries.into_iter().enumerate() {
+            let action_name = format!("entry-{}", index);
+            let action = SimpleAction::new(&action_name, None);
+            action.connect_activate(clone!(
+                @strong article_ids,
+                @strong self.sender as sender => @default-panic, move |_action, _parameter|
+            {
+                let article_ids = article_ids.clone();
+                match &operation {
+                    Some(operation) => Util::send(
+                        &sender,
+                        Action::ArticleBatch(BatchUpdate {
+                            article_ids,
+                            operation: operation.clone(),
+                        }),
+                    ),
+                    None => Util::send(&sender, Action::ExportArticles(article_ids)),
+                }
+            }));
+            action_group.add_action(&action);
+
+            let item = MenuItem::new(Some(&label), None);
+            item.set_action_and_target_value(Some(&format!("article-batch.{}", action_name)), None);
+            section.append_item(&item);
+        }
+
+        model.append_section(None, &state_section);
+        if !tags.is_empty() {
+            let tag_section = Menu::new();
+            tag_section.append_submenu(Some(&i18n("Add Tag")), &tag_menu);
+            tag_section.append_submenu(Some(&i18n("Remove Tag")), &untag_menu);
+            model.append_section(None, &tag_section);
+        }
+        model.append_section(None, &export_section);
+
+        let popover = Popover::new(Some(&row));
+        popover.insert_action_group("article-batch", Some(&action_group));
+        popover.set_position(PositionType::Bottom);
+        popover.bind_model(Some(&model), None);
+        popover.show();
+    }
+
     pub fn set_article_row_state(&self, article_id: &ArticleID, read: Option<Read>, marked: Option<Marked>) {
         if let Some(current_list) = self.get_current_list() {
             Self::set_article_state_static(article_id, read, marked, &current_list, &self.list_model);
@@ -1,4 +1,4 @@
-use news_flash::models::{ArticleID, Marked, Read};
+use news_flash::models::{ArticleID, Marked, Read, TagID};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -12,3 +12,62 @@ pub struct MarkUpdate {
     pub article_id: ArticleID,
     pub marked: Marked,
 }
+
+#[derive(Hash, Eq, PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub enum BatchOperation {
+    MarkRead,
+    MarkUnread,
+    Star,
+    Unstar,
+    Tag(TagID),
+    Untag(TagID),
+}
+
+impl BatchOperation {
+    pub fn from_read(read: Read) -> Self {
+        match read {
+            Read::Read => BatchOperation::MarkRead,
+            Read::Unread => BatchOperation::MarkUnread,
+        }
+    }
+
+    pub fn from_marked(marked: Marked) -> Self {
+        match marked {
+            Marked::Marked => BatchOperation::Star,
+            Marked::Unmarked => BatchOperation::Unstar,
+        }
+    }
+
+    pub fn invert(&self) -> Self {
+        match self {
+            BatchOperation::MarkRead => BatchOperation::MarkUnread,
+            BatchOperation::MarkUnread => BatchOperation::MarkRead,
+            BatchOperation::Star => BatchOperation::Unstar,
+            BatchOperation::Unstar => BatchOperation::Star,
+            BatchOperation::Tag(tag_id) => BatchOperation::Untag(tag_id.clone()),
+            BatchOperation::Untag(tag_id) => BatchOperation::Tag(tag_id.clone()),
+        }
+    }
+
+    pub fn read(&self) -> Option<Read> {
+        match self {
+            BatchOperation::MarkRead => Some(Read::Read),
+            BatchOperation::MarkUnread => Some(Read::Unread),
+            _ => None,
+        }
+    }
+
+    pub fn marked(&self) -> Option<Marked> {
+        match self {
+            BatchOperation::Star => Some(Marked::Marked),
+            BatchOperation::Unstar => Some(Marked::Unmarked),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchUpdate {
+    pub article_ids: Vec<ArticleID>,
+    pub operation: BatchOperation,
+}
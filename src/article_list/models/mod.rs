@@ -5,7 +5,7 @@ mod error;
 
 use crate::content_page::HeaderSelection;
 pub use article::ArticleListArticleModel;
-pub use article_update_msg::{MarkUpdate, ReadUpdate};
+pub use article_update_msg::{BatchOperation, BatchUpdate, MarkUpdate, ReadUpdate};
 pub use change_set::ArticleListChangeSet;
 use chrono::{Duration, NaiveDate, Utc};
 use error::{ArticleListModelError, ArticleListModelErrorKind};
@@ -7,11 +7,11 @@ use crate::content_page::ContentHeader;
 use crate::main_window_state::MainWindowState;
 use crate::util::{BuilderHelper, GtkUtil, Util};
 use chrono::NaiveDateTime;
-use gdk::RGBA;
+use gdk::{EventType, RGBA};
 use glib::{clone, object::Cast, source::Continue, translate::ToGlib, Sender};
 use gtk::{
-    prelude::WidgetExtManual, AdjustmentExt, ContainerExt, ListBox, ListBoxExt, ListBoxRowExt, ScrolledWindow,
-    ScrolledWindowExt, SettingsExt, StateFlags, StyleContextExt, TickCallbackId, WidgetExt,
+    prelude::WidgetExtManual, AdjustmentExt, ContainerExt, Inhibit, ListBox, ListBoxExt, ListBoxRow, ListBoxRowExt,
+    ScrolledWindow, ScrolledWindowExt, SettingsExt, StateFlags, StyleContextExt, TickCallbackId, WidgetExt,
 };
 use news_flash::models::{
     article::{Marked, Read},
@@ -39,6 +39,7 @@ pub struct SingleArticleList {
     articles: HashMap<ArticleID, Arc<RwLock<ArticleRow>>>,
     list: ListBox,
     select_after_signal: Arc<RwLock<Option<u32>>>,
+    context_row: Arc<RwLock<Option<ListBoxRow>>>,
     scroll_cooldown: Arc<RwLock<bool>>,
     scroll_animation_data: ScrollAnimationProperties,
     content_header: Arc<ContentHeader>,
@@ -85,6 +86,7 @@ impl SingleArticleList {
             articles: HashMap::new(),
             list,
             select_after_signal: Arc::new(RwLock::new(None)),
+            context_row: Arc::new(RwLock::new(None)),
             scroll_cooldown,
             scroll_animation_data: ScrollAnimationProperties {
                 start_time: Arc::new(RwLock::new(None)),
@@ -123,6 +125,22 @@ impl SingleArticleList {
         state: &Arc<RwLock<MainWindowState>>,
     ) {
         let article_row = ArticleRow::new(&article, model, state, self.sender.clone());
+        article_row.widget().connect_button_press_event(clone!(
+            @weak self.list as list,
+            @weak self.context_row as context_row,
+            @strong self.sender as sender => @default-panic, move |row, event|
+        {
+            if event.get_button() != 3 || event.get_event_type() != EventType::ButtonPress {
+                return Inhibit(false);
+            }
+            if !row.is_selected() {
+                list.unselect_all();
+                list.select_row(Some(row));
+            }
+            context_row.write().replace(row.clone());
+            Util::send(&sender, Action::ShowArticleListMenu);
+            Inhibit(true)
+        }));
         self.list.insert(&article_row.widget(), pos);
         article_row.widget().show();
         self.articles
@@ -145,6 +163,7 @@ impl SingleArticleList {
             }));
         }
         self.articles.clear();
+        self.context_row.write().take();
         if let Some(vadjustment) = self.scroll.get_vadjustment() {
             vadjustment.set_value(0.0);
         }
@@ -177,6 +196,7 @@ impl SingleArticleList {
 
     pub fn select_after(&self, id: &ArticleID, time: u32) {
         if let Some(article_handle) = self.articles.get(id) {
+            self.list.unselect_all();
             self.list.select_row(Some(&article_handle.read().widget()));
             Util::send(
                 &self.sender,
@@ -357,6 +377,18 @@ impl SingleArticleList {
         self.list.get_selected_row().map(|row| row.get_index())
     }
 
+    pub fn get_selected_indices(&self) -> Vec<i32> {
+        self.list
+            .get_selected_rows()
+            .iter()
+            .map(|row| row.get_index())
+            .collect()
+    }
+
+    pub fn get_context_row(&self) -> Option<ListBoxRow> {
+        self.context_row.read().clone()
+    }
+
     pub fn set_article_row_state(&self, article_id: &ArticleID, read: Option<Read>, marked: Option<Marked>) {
         if let Some(article_row) = self.articles.get(article_id) {
             if let Some(read) = read {
@@ -14,6 +14,8 @@ pub enum ArticleViewErrorKind {
     NoActiveWebView,
     #[fail(display = "Executed JS didn't return any value")]
     NoValueFromJS,
+    #[fail(display = "Failed to set up print operation")]
+    Print,
     #[fail(display = "Unknown Error")]
     Unknown,
 }
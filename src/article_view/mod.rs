@@ -2,13 +2,16 @@ mod error;
 mod models;
 mod progress_overlay;
 mod url_overlay;
+mod user_theme;
 
 use self::error::{ArticleViewError, ArticleViewErrorKind};
 pub use self::models::ArticleTheme;
 use self::models::InternalState;
 use self::progress_overlay::ProgressOverlay;
 use self::url_overlay::UrlOverlay;
+pub use self::user_theme::UserTheme;
 use crate::app::Action;
+use crate::enclosures::{self, Enclosure, EnclosureDownloads, EnclosureKind, PlaybackPositions};
 use crate::main_window_state::MainWindowState;
 use crate::settings::Settings;
 use crate::util::{BuilderHelper, DateUtil, FileUtil, GtkUtil, Util, GTK_RESOURCE_FILE_ERROR};
@@ -17,23 +20,26 @@ use gdk::{
     keys::constants::KP_Add as KP_ADD, keys::constants::KP_Subtract as KP_SUBTRACT, keys::constants::KP_0, Cursor,
     CursorType, Display, EventMask, ModifierType, ScrollDirection, RGBA,
 };
-use gio::{Cancellable, Settings as GSettings, SettingsExt as GSettingsExt};
-use glib::{clone, object::Cast, source::Continue, translate::ToGlib, MainLoop, Sender};
+use gio::{Cancellable, File as GioFile, FileExt, Settings as GSettings, SettingsExt as GSettingsExt};
+use glib::{clone, object::Cast, object::IsA, source::Continue, translate::ToGlib, MainLoop, Sender};
 use gtk::{
-    prelude::WidgetExtManual, Button, ButtonExt, Inhibit, Overlay, OverlayExt, SettingsExt as GtkSettingsExt, Stack,
-    StackExt, TickCallbackId, WidgetExt,
+    prelude::WidgetExtManual, Button, ButtonExt, Inhibit, Overlay, OverlayExt, PrintSettings,
+    SettingsExt as GtkSettingsExt, Stack, StackExt, TickCallbackId, WidgetExt, Window,
 };
 use log::{error, warn};
 use news_flash::models::{FatArticle, Marked, Read};
 use pango::FontDescription;
 use parking_lot::RwLock;
+use std::fs;
+use std::path::Path;
 use std::str;
 use std::sync::Arc;
 use url::{Host, Origin};
 use webkit2gtk::{
     ContextMenuAction, ContextMenuExt, ContextMenuItemExt, HitTestResultExt, NavigationPolicyDecision,
-    NavigationPolicyDecisionExt, PolicyDecisionExt, PolicyDecisionType, Settings as WebkitSettings, SettingsExt,
-    URIRequestExt, WebContext, WebView, WebViewExt,
+    NavigationPolicyDecisionExt, PolicyDecisionExt, PolicyDecisionType, PrintOperation, PrintOperationExt,
+    Settings as WebkitSettings, SettingsExt, URIRequestExt, URISchemeRequest, URISchemeRequestExt, WebContext,
+    WebContextExt, WebView, WebViewExt,
 };
 
 const MIDDLE_MOUSE_BUTTON: u32 = 2;
@@ -57,6 +63,8 @@ pub struct ArticleView {
     view_html_button: Button,
     visible_article: Arc<RwLock<Option<FatArticle>>>,
     visible_feed_name: Arc<RwLock<Option<String>>>,
+    visible_enclosures: Arc<RwLock<Vec<Enclosure>>>,
+    playback_positions: Arc<RwLock<PlaybackPositions>>,
     widnow_state: Arc<RwLock<MainWindowState>>,
     internal_state: Arc<RwLock<InternalState>>,
     load_changed_signal: Arc<RwLock<Option<usize>>>,
@@ -119,6 +127,8 @@ impl ArticleView {
 
         let web_context = WebContext::new();
         // FIXME: apply appliction wide proxy settings
+        // downloaded enclosures are served through their own scheme, pages never get access to local files
+        web_context.register_uri_scheme(enclosures::URI_SCHEME, Self::serve_enclosure);
 
         let stack = builder.get::<Stack>("article_view_stack");
         stack.set_visible_child_name("empty");
@@ -131,6 +141,10 @@ impl ArticleView {
 
         let internal_state = InternalState::Empty;
         let settings = settings.clone();
+        let playback_positions = PlaybackPositions::open().unwrap_or_else(|error| {
+            error!("Failed to read playback positions: {}", error);
+            PlaybackPositions::default()
+        });
 
         let article_view = ArticleView {
             settings,
@@ -140,6 +154,8 @@ impl ArticleView {
             view_html_button,
             visible_article,
             visible_feed_name,
+            visible_enclosures: Arc::new(RwLock::new(Vec::new())),
+            playback_positions: Arc::new(RwLock::new(playback_positions)),
             internal_state: Arc::new(RwLock::new(internal_state)),
             widnow_state: state.clone(),
             load_changed_signal: Arc::new(RwLock::new(None)),
@@ -181,20 +197,41 @@ impl ArticleView {
     }
 
     pub fn show_article(&self, article: FatArticle, feed_name: String) {
+        let feed_settings = self.settings.read().get_feed_view_settings(&article.feed_id);
+        let newly_shown = self
+            .visible_article
+            .read()
+            .as_ref()
+            .map(|visible| visible.article_id != article.article_id)
+            .unwrap_or(true);
+
         let webview = self.switch_view().expect("Failed to switch webview");
         let html = self.build_article(&article, &feed_name);
-        webview.load_html(&html, Self::get_base_url(&article).as_deref());
+        webview.set_zoom_level(feed_settings.zoom.unwrap_or(1.0));
+        webview.load_html(&html, self.get_base_url(&article).as_deref());
+        let scrape = feed_settings.scraped_content
+            && newly_shown
+            && article.scraped_content.is_none()
+            && !self.widnow_state.read().get_offline();
         self.visible_article.write().replace(article);
         self.visible_feed_name.write().replace(feed_name);
+
+        // only scrape articles that are newly shown, so a failed attempt doesn't repeat endlessly
+        if scrape {
+            Util::send(&self.sender, Action::StartGrabArticleContent);
+        }
     }
 
     pub fn redraw_article(&self) {
+        self.save_playback_position();
         if let Some(article) = &*self.visible_article.read() {
             if let Some(feed_name) = &*self.visible_feed_name.read() {
                 let html = self.build_article(&article, feed_name);
+                let zoom = self.settings.read().get_feed_view_settings(&article.feed_id).zoom;
 
                 let webview = self.switch_view().expect("Failed to switch webview");
-                webview.load_html(&html, Self::get_base_url(&article).as_deref());
+                webview.set_zoom_level(zoom.unwrap_or(1.0));
+                webview.load_html(&html, self.get_base_url(&article).as_deref());
                 return;
             }
         }
@@ -202,7 +239,7 @@ impl ArticleView {
         warn!("Can't redraw article view. No article is on display.");
     }
 
-    fn get_base_url(article: &FatArticle) -> Option<String> {
+    fn get_base_url(&self, article: &FatArticle) -> Option<String> {
         if let Some(url) = &article.url {
             match url.get().origin() {
                 Origin::Opaque(_op) => None,
@@ -235,14 +272,126 @@ impl ArticleView {
         }
     }
 
+    // must be set before `show_article`
+    pub fn set_enclosures(&self, enclosures: Vec<Enclosure>) {
+        self.save_playback_position();
+        *self.visible_enclosures.write() = enclosures;
+    }
+
+    fn serve_enclosure(request: &URISchemeRequest) {
+        let path = request
+            .get_path()
+            .and_then(|path| EnclosureDownloads::new().resolve_uri_path(&path));
+        let stream = path.as_ref().and_then(|path| {
+            let length = fs::metadata(path).ok()?.len();
+            let stream = GioFile::new_for_path(path).read(None::<&Cancellable>).ok()?;
+            Some((stream, length))
+        });
+        match (path, stream) {
+            (Some(path), Some((stream, length))) => {
+                let (content_type, _uncertain) = gio::content_type_guess(path.to_str(), &[]);
+                let mime_type = gio::content_type_get_mime_type(&content_type);
+                request.finish(&stream, length as i64, mime_type.as_deref());
+            }
+            _ => {
+                let uri = request.get_uri().map(|uri| uri.to_string()).unwrap_or_default();
+                warn!("Enclosure '{}' is not downloaded", uri);
+                let mut error = glib::Error::new(gio::IOErrorEnum::NotFound, "Enclosure is not downloaded");
+                request.finish_error(&mut error);
+            }
+        }
+    }
+
+    fn use_downloaded_enclosures(&self) -> bool {
+        self.widnow_state.read().get_offline()
+    }
+
+    fn save_playback_position(&self) {
+        let article_id = match &*self.visible_article.read() {
+            Some(article) => article.article_id.clone(),
+            None => return,
+        };
+        let has_media = self
+            .visible_enclosures
+            .read()
+            .iter()
+            .any(|enclosure| enclosure.article_id == article_id && EnclosureKind::detect(enclosure).is_media());
+        if !has_media {
+            return;
+        }
+
+        let view_name = (*self.internal_state.read()).to_str().map(|s| s.to_owned());
+        let view = view_name
+            .and_then(|view_name| self.stack.get_child_by_name(&view_name))
+            .and_then(|view| view.downcast::<WebView>().ok());
+        if let Some(view) = view {
+            let position = Self::webview_js_get_f64(
+                &view,
+                "(function() {
+                    var media = document.querySelector('.enclosure audio, .enclosure video');
+                    return media ? media.currentTime : -1;
+                })()",
+            );
+            if let Ok(position) = position {
+                if position >= 0.0 {
+                    if let Err(error) = self.playback_positions.write().set(&article_id, position) {
+                        error!("Failed to save playback position: {}", error);
+                    }
+                }
+            }
+        }
+    }
+
     pub fn close_article(&self) {
+        self.save_playback_position();
         self.disconnect_old_view();
         self.visible_article.write().take();
         self.visible_feed_name.write().take();
+        self.visible_enclosures.write().clear();
         *self.internal_state.write() = InternalState::Empty;
         self.stack.set_visible_child_name("empty");
     }
 
+    // with `pdf_path` the article is saved as PDF to that file instead of showing the print dialog
+    pub fn print<W: IsA<Window>>(&self, window: &W, pdf_path: Option<&Path>) -> Result<(), ArticleViewError> {
+        let view_name = (*self.internal_state.read()).to_str().map(|s| s.to_owned());
+        let view_name = view_name.ok_or(ArticleViewErrorKind::NoActiveWebView)?;
+        let webview = self
+            .stack
+            .get_child_by_name(&view_name)
+            .and_then(|view| view.downcast::<WebView>().ok())
+            .ok_or(ArticleViewErrorKind::InvalidActiveWebView)?;
+
+        let operation = PrintOperation::new(&webview);
+        operation.connect_failed(|_operation, error| {
+            error!("Failed to print article: {}", error);
+        });
+
+        match pdf_path {
+            Some(path) => {
+                let uri = glib::filename_to_uri(path, None).map_err(|_| ArticleViewErrorKind::Print)?;
+                let printer = Self::file_printer();
+                // keys of the file print backend
+                let print_settings = PrintSettings::new();
+                print_settings.set_printer(&printer);
+                print_settings.set("output-file-format", Some("pdf"));
+                print_settings.set("output-uri", Some(uri.as_str()));
+                operation.set_print_settings(&print_settings);
+                operation.print();
+            }
+            None => {
+                operation.run_dialog(Some(window));
+            }
+        }
+        Ok(())
+    }
+
+    // the printer that writes to a file is named by the print backend in GTK's own
+    // translation domain, so look the name up there instead of hardcoding the English one
+    fn file_printer() -> String {
+        gettextrs::dgettext("gtk30", "Print to File")
+    }
+
     pub fn update_background_color(&self, color: &RGBA) {
         if (color.alpha - 1.0).abs() == std::f64::EPSILON {
             let webview_1 = self
@@ -782,14 +931,34 @@ impl ArticleView {
     }
 
     fn build_article(&self, article: &FatArticle, feed_name: &str) -> String {
-        Self::build_article_static(
+        let theme_override = self.settings.read().get_feed_view_settings(&article.feed_id).theme;
+        let article_enclosures: Vec<Enclosure> = self
+            .visible_enclosures
+            .read()
+            .iter()
+            .filter(|enclosure| enclosure.article_id == article.article_id)
+            .cloned()
+            .collect();
+        let downloads = EnclosureDownloads::new();
+        let enclosures = enclosures::enclosures_html(
+            &article_enclosures,
+            if self.use_downloaded_enclosures() {
+                Some(&downloads)
+            } else {
+                None
+            },
+            self.playback_positions.read().get(&article.article_id),
+        );
+
+        Self::build_article_template(
             "article",
             article,
             feed_name,
             &self.settings,
-            None,
+            theme_override,
             None,
             self.widnow_state.read().get_prefer_scraped_content(),
+            &enclosures,
         )
     }
 
@@ -801,6 +970,29 @@ impl ArticleView {
         theme_override: Option<ArticleTheme>,
         font_size_override: Option<i32>,
         prefer_scraped_content: bool,
+    ) -> String {
+        Self::build_article_template(
+            file_name,
+            article,
+            feed_name,
+            settings,
+            theme_override,
+            font_size_override,
+            prefer_scraped_content,
+            "",
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn build_article_template(
+        file_name: &str,
+        article: &FatArticle,
+        feed_name: &str,
+        settings: &Arc<RwLock<Settings>>,
+        theme_override: Option<ArticleTheme>,
+        font_size_override: Option<i32>,
+        prefer_scraped_content: bool,
+        enclosures: &str,
     ) -> String {
         let template_data = Resources::get(&format!("article_view/{}.html", file_name)).expect(GTK_RESOURCE_FILE_ERROR);
         let template_str = str::from_utf8(template_data.as_ref()).expect(GTK_RESOURCE_FILE_ERROR);
@@ -900,17 +1092,18 @@ impl ArticleView {
         // $FEED
         template_string = template_string.replacen("$FEED", feed_name, 1);
 
+        // $ENCLOSURES
+        template_string = template_string.replacen("$ENCLOSURES", enclosures, 1);
+
         // $THEME
-        let theme = if let Some(theme_override) = &theme_override {
-            theme_override
-                .to_str(settings.read().get_prefer_dark_theme())
-                .to_owned()
-        } else {
-            settings
-                .read()
-                .get_article_view_theme()
-                .to_str(settings.read().get_prefer_dark_theme())
-                .to_owned()
+        let theme = theme_override.unwrap_or_else(|| settings.read().get_article_view_theme());
+        let user_theme = match &theme {
+            ArticleTheme::User(id) => UserTheme::load(id),
+            _ => None,
+        };
+        let theme = match &user_theme {
+            Some(user_theme) => user_theme.to_str().to_owned(),
+            None => theme.to_str(settings.read().get_prefer_dark_theme()).to_owned(),
         };
         template_string = template_string.replacen("$THEME", &theme, 1);
 
@@ -920,12 +1113,47 @@ impl ArticleView {
         // $FONTSIZE
         template_string = template_string.replacen("$FONTSIZE", &format!("{}", font_size), 1);
 
+        // $TYPOGRAPHY
+        template_string = template_string.replacen("$TYPOGRAPHY", &Self::typography_style(settings), 1);
+
         // $CSS
+        let mut css_string = css_string.to_owned();
+        if let Some(user_theme) = &user_theme {
+            css_string.push('\n');
+            css_string.push_str(&user_theme.css);
+        }
+        if let Some(custom_css) = settings.read().get_article_view_custom_css() {
+            css_string.push('\n');
+            css_string.push_str(&custom_css);
+        }
         template_string = template_string.replacen("$CSS", &css_string, 1);
 
         template_string
     }
 
+    fn typography_style(settings: &Arc<RwLock<Settings>>) -> String {
+        let settings = settings.read();
+        let mut style = format!(
+            "--line-width:{}em;--line-height:{:.2}em;--paragraph-spacing:{:.2}rem;--text-align:{};",
+            settings.get_article_view_line_width(),
+            settings.get_article_view_line_height(),
+            settings.get_article_view_paragraph_spacing(),
+            if settings.get_article_view_justify() {
+                "justify"
+            } else {
+                "start"
+            },
+        );
+
+        if let Some(font) = settings.get_article_view_monospace_font() {
+            if let Some(family) = FontDescription::from_string(&font).get_family() {
+                style.push_str(&format!("--monospace-font:{};", family));
+            }
+        }
+
+        style
+    }
+
     fn set_scroll_pos_static(view: &WebView, pos: f64) {
         let cancellable: Option<&Cancellable> = None;
         view.run_javascript(&format!("window.scrollTo(0,{});", pos), cancellable, |res| match res {
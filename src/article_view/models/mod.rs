@@ -1,3 +1,4 @@
+use super::user_theme::UserTheme;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug)]
@@ -28,19 +29,22 @@ impl InternalState {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ArticleTheme {
     Default,
     Spring,
     Midnight,
     Parchment,
     Gruvbox,
+    // id of a `UserTheme`
+    User(String),
 }
 
 impl ArticleTheme {
     pub fn to_str(&self, prefer_dark_theme: bool) -> &str {
         match self {
-            ArticleTheme::Default => {
+            // the classes of a user theme are resolved when loading its css, fall back to default
+            ArticleTheme::Default | ArticleTheme::User(_) => {
                 if prefer_dark_theme {
                     "theme dark"
                 } else {
@@ -54,13 +58,53 @@ impl ArticleTheme {
         }
     }
 
-    pub fn name(&self) -> &str {
+    pub fn name(&self) -> String {
         match self {
-            ArticleTheme::Default => "Default",
-            ArticleTheme::Spring => "Spring",
-            ArticleTheme::Midnight => "Midnight",
-            ArticleTheme::Parchment => "Parchment",
-            ArticleTheme::Gruvbox => "Gruvbox",
+            ArticleTheme::Default => "Default".to_owned(),
+            ArticleTheme::Spring => "Spring".to_owned(),
+            ArticleTheme::Midnight => "Midnight".to_owned(),
+            ArticleTheme::Parchment => "Parchment".to_owned(),
+            ArticleTheme::Gruvbox => "Gruvbox".to_owned(),
+            ArticleTheme::User(id) => UserTheme::name_of(id).unwrap_or_else(|| id.clone()),
+        }
+    }
+
+    pub fn all() -> Vec<Self> {
+        let mut themes = vec![
+            ArticleTheme::Default,
+            ArticleTheme::Spring,
+            ArticleTheme::Midnight,
+            ArticleTheme::Parchment,
+            ArticleTheme::Gruvbox,
+        ];
+        themes.extend(
+            UserTheme::load_all()
+                .into_iter()
+                .map(|theme| ArticleTheme::User(theme.id)),
+        );
+        themes
+    }
+
+    pub fn to_action_target(&self) -> String {
+        match self {
+            ArticleTheme::Default => "default".to_owned(),
+            ArticleTheme::Spring => "spring".to_owned(),
+            ArticleTheme::Midnight => "midnight".to_owned(),
+            ArticleTheme::Parchment => "parchment".to_owned(),
+            ArticleTheme::Gruvbox => "gruvbox".to_owned(),
+            ArticleTheme::User(id) => format!("user:{}", id),
+        }
+    }
+
+    pub fn from_action_target(target: &str) -> Option<Self> {
+        match target {
+            "default" => Some(ArticleTheme::Default),
+            "spring" => Some(ArticleTheme::Spring),
+            "midnight" => Some(ArticleTheme::Midnight),
+            "parchment" => Some(ArticleTheme::Parchment),
+            "gruvbox" => Some(ArticleTheme::Gruvbox),
+            target if target.starts_with("user:") => Some(ArticleTheme::User(target["user:".len()..].to_owned())),
+            _ => None,
         }
     }
 }
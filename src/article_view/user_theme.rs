@@ -0,0 +1,136 @@
+use crate::app::CONFIG_DIR;
+use lazy_static::lazy_static;
+use log::warn;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+const THEME_DIR: &str = "themes";
+const THEME_EXTENSION: &str = "css";
+
+lazy_static! {
+    // names by id of the themes found by the last `load_all`
+    static ref THEME_NAMES: RwLock<Option<HashMap<String, String>>> = RwLock::new(None);
+}
+
+// Article theme loaded from `CONFIG_DIR/themes/<id>.css`. Name and dark flag are read from
+// `key: value` lines of the leading comment block, e.g. ` * dark: true`.
+// The css is appended after the embedded `style.css` and the body is rendered with the classes
+// of the default or dark theme plus `user`, so a theme only needs to override what it changes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserTheme {
+    pub id: String,
+    pub name: String,
+    pub dark: bool,
+    pub css: String,
+}
+
+impl UserTheme {
+    pub fn dir() -> PathBuf {
+        CONFIG_DIR.join(THEME_DIR)
+    }
+
+    pub fn load(id: &str) -> Option<Self> {
+        let path = Self::dir().join(format!("{}.{}", id, THEME_EXTENSION));
+        match fs::read_to_string(&path) {
+            Ok(css) => Some(Self::parse(id, css)),
+            Err(error) => {
+                warn!("Failed to read user theme '{}': {}", path.display(), error);
+                None
+            }
+        }
+    }
+
+    pub fn load_all() -> Vec<Self> {
+        let entries = match fs::read_dir(Self::dir()) {
+            Ok(entries) => entries,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut themes: Vec<Self> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().map(|ext| ext == THEME_EXTENSION).unwrap_or(false))
+            .filter_map(|path| {
+                let id = path.file_stem()?.to_str()?.to_owned();
+                Self::load(&id)
+            })
+            .collect();
+        themes.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        *THEME_NAMES.write() = Some(
+            themes
+                .iter()
+                .map(|theme| (theme.id.clone(), theme.name.clone()))
+                .collect(),
+        );
+        themes
+    }
+
+    // without reading the theme files again once they were loaded
+    pub fn name_of(id: &str) -> Option<String> {
+        if THEME_NAMES.read().is_none() {
+            Self::load_all();
+        }
+        THEME_NAMES.read().as_ref()?.get(id).cloned()
+    }
+
+    pub fn parse(id: &str, css: String) -> Self {
+        let mut name = None;
+        let mut dark = false;
+
+        let trimmed = css.trim_start();
+        if trimmed.starts_with("/*") {
+            if let Some(end) = trimmed.find("*/") {
+                for line in trimmed[2..end].lines() {
+                    let line = line.trim().trim_start_matches('*').trim();
+                    let mut split = line.splitn(2, ':');
+                    let key = split.next().map(|key| key.trim().to_lowercase());
+                    let value = split.next().map(|value| value.trim());
+                    match (key.as_deref(), value) {
+                        (Some("name"), Some(value)) if !value.is_empty() => name = Some(value.to_owned()),
+                        (Some("dark"), Some(value)) => dark = value.eq_ignore_ascii_case("true"),
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        UserTheme {
+            id: id.to_owned(),
+            name: name.unwrap_or_else(|| id.to_owned()),
+            dark,
+            css,
+        }
+    }
+
+    pub fn to_str(&self) -> &str {
+        if self.dark {
+            "theme dark user"
+        } else {
+            "theme default user"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UserTheme;
+
+    #[test]
+    fn parse_header() {
+        let css = "/*\n * name: Corporate Dark\n * dark: true\n */\nbody.theme.user { color: red; }".to_owned();
+        let theme = UserTheme::parse("corporate", css);
+        assert_eq!(theme.name, "Corporate Dark");
+        assert!(theme.dark);
+        assert_eq!(theme.to_str(), "theme dark user");
+    }
+
+    #[test]
+    fn parse_without_header() {
+        let css = "body.theme.user { color: red; }\n/* name: ignored */".to_owned();
+        let theme = UserTheme::parse("plain", css);
+        assert_eq!(theme.name, "plain");
+        assert!(!theme.dark);
+    }
+}
@@ -0,0 +1,78 @@
+use failure::{Backtrace, Context, Error, Fail};
+use std::fmt;
+
+#[derive(Debug)]
+pub struct CliError {
+    inner: Context<CliErrorKind>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Fail)]
+pub enum CliErrorKind {
+    #[fail(display = "Invalid arguments: {}", _0)]
+    InvalidArguments(String),
+    #[fail(display = "No account configured")]
+    NoAccount,
+    #[fail(display = "Failed to access settings file")]
+    Settings,
+    #[fail(display = "Failed to load data from the database")]
+    DataBase,
+    #[fail(display = "Failed to sync")]
+    Sync,
+    #[fail(display = "Backend operation failed")]
+    Backend,
+    #[fail(display = "Failed to find article '{}'", _0)]
+    MissingArticle(String),
+    #[fail(display = "Failed to find feed for article")]
+    MissingFeed,
+    #[fail(display = "Error writing file to disc")]
+    WriteFile,
+    #[fail(display = "Error serializing output")]
+    Serialize,
+    #[fail(display = "Unknown Error")]
+    Unknown,
+}
+
+impl Fail for CliError {
+    fn cause(&self) -> Option<&dyn Fail> {
+        self.inner.cause()
+    }
+
+    fn backtrace(&self) -> Option<&Backtrace> {
+        self.inner.backtrace()
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, f)
+    }
+}
+
+impl CliError {
+    #[allow(dead_code)]
+    pub fn kind(&self) -> CliErrorKind {
+        self.inner.get_context().clone()
+    }
+}
+
+impl From<CliErrorKind> for CliError {
+    fn from(kind: CliErrorKind) -> CliError {
+        CliError {
+            inner: Context::new(kind),
+        }
+    }
+}
+
+impl From<Context<CliErrorKind>> for CliError {
+    fn from(inner: Context<CliErrorKind>) -> CliError {
+        CliError { inner }
+    }
+}
+
+impl From<Error> for CliError {
+    fn from(_: Error) -> CliError {
+        CliError {
+            inner: Context::new(CliErrorKind::Unknown),
+        }
+    }
+}
@@ -0,0 +1,336 @@
+mod error;
+
+pub use self::error::{CliError, CliErrorKind};
+
+use crate::app::{App, CONFIG_DIR, DATA_DIR};
+use crate::article_view::ArticleView;
+use crate::settings::Settings;
+use crate::util::{FileUtil, Util, RUNTIME_ERROR};
+use failure::ResultExt;
+use news_flash::models::{Article, ArticleFilter, ArticleID, Feed, Marked, Read};
+use news_flash::NewsFlash;
+use parking_lot::RwLock;
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+static USAGE: &str = "Usage: com.gitlab.newsflash <COMMAND> [--json]
+
+Commands:
+  sync                          Sync all feeds of the configured account
+  list-unread                   List all unread articles
+  mark-read <ARTICLE_ID>        Mark a single article as read
+  export-opml <FILE>            Write all subscriptions to an OPML file
+  export-article <ARTICLE_ID> [FILE]
+                                Write an article as HTML to FILE or stdout
+  help                          Print this message
+
+Options:
+  --json                        Print the result as JSON instead of plain text";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Sync,
+    ListUnread,
+    MarkRead(ArticleID),
+    ExportOpml(PathBuf),
+    ExportArticle(ArticleID, Option<PathBuf>),
+    Help,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandLine {
+    pub command: Command,
+    pub format: OutputFormat,
+}
+
+impl CommandLine {
+    // `None` if the arguments don't start with a known subcommand, they are meant for `gtk::Application` then
+    pub fn parse(args: &[String]) -> Option<Result<Self, CliError>> {
+        let subcommand = args.get(1)?;
+        match subcommand.as_str() {
+            "sync" | "list-unread" | "mark-read" | "export-opml" | "export-article" | "help" => {}
+            _ => return None,
+        }
+
+        let format = if args.iter().skip(2).any(|a| a == "--json") {
+            OutputFormat::Json
+        } else {
+            OutputFormat::Text
+        };
+        let positional: Vec<&String> = args.iter().skip(2).filter(|a| *a != "--json").collect();
+
+        let command = match subcommand.as_str() {
+            "sync" => Command::Sync,
+            "list-unread" => Command::ListUnread,
+            "mark-read" => match positional.first() {
+                Some(id) => Command::MarkRead(ArticleID::new(id)),
+                None => return Some(Err(Self::missing_argument("mark-read", "ARTICLE_ID"))),
+            },
+            "export-opml" => match positional.first() {
+                Some(path) => Command::ExportOpml(PathBuf::from(path)),
+                None => return Some(Err(Self::missing_argument("export-opml", "FILE"))),
+            },
+            "export-article" => match positional.first() {
+                Some(id) => Command::ExportArticle(ArticleID::new(id), positional.get(1).map(PathBuf::from)),
+                None => return Some(Err(Self::missing_argument("export-article", "ARTICLE_ID"))),
+            },
+            _ => Command::Help,
+        };
+
+        Some(Ok(CommandLine { command, format }))
+    }
+
+    fn missing_argument(command: &str, argument: &str) -> CliError {
+        CliErrorKind::InvalidArguments(format!("'{}' expects <{}>", command, argument)).into()
+    }
+
+    pub fn usage() -> &'static str {
+        USAGE
+    }
+
+    pub fn run(&self) -> i32 {
+        if let Command::Help = self.command {
+            println!("{}", USAGE);
+            return 0;
+        }
+
+        match self.execute() {
+            Ok(output) => {
+                if !output.is_empty() {
+                    println!("{}", output);
+                }
+                0
+            }
+            Err(error) => {
+                match self.format {
+                    OutputFormat::Text => eprintln!("Error: {}", error),
+                    OutputFormat::Json => println!("{}", json!({ "error": error.to_string() })),
+                }
+                1
+            }
+        }
+    }
+
+    fn execute(&self) -> Result<String, CliError> {
+        let settings = Arc::new(RwLock::new(Settings::open().context(CliErrorKind::Settings)?));
+        let news_flash = NewsFlash::try_load(&DATA_DIR, &CONFIG_DIR).context(CliErrorKind::NoAccount)?;
+
+        match &self.command {
+            Command::Sync => self.sync(&news_flash, &settings),
+            Command::ListUnread => self.list_unread(&news_flash),
+            Command::MarkRead(article_id) => self.mark_read(&news_flash, &settings, article_id),
+            Command::ExportOpml(path) => self.export_opml(&news_flash, path),
+            Command::ExportArticle(article_id, path) => {
+                self.export_article(&news_flash, &settings, article_id, path.as_ref())
+            }
+            Command::Help => Ok(USAGE.to_owned()),
+        }
+    }
+
+    fn sync(&self, news_flash: &NewsFlash, settings: &Arc<RwLock<Settings>>) -> Result<String, CliError> {
+        let new_articles = Runtime::new()
+            .expect(RUNTIME_ERROR)
+            .block_on(news_flash.sync(&App::build_client(settings)))
+            .context(CliErrorKind::Sync)?;
+        let unread = news_flash.unread_count_all().context(CliErrorKind::DataBase)?;
+
+        Ok(match self.format {
+            OutputFormat::Text => format!("{} new articles ({} unread)", new_articles, unread),
+            OutputFormat::Json => json!({ "new": new_articles, "unread": unread }).to_string(),
+        })
+    }
+
+    fn list_unread(&self, news_flash: &NewsFlash) -> Result<String, CliError> {
+        let (feeds, _mappings) = news_flash.get_feeds().context(CliErrorKind::DataBase)?;
+        let articles = Util::load_all_articles(
+            news_flash,
+            ArticleFilter {
+                limit: None,
+                offset: None,
+                order: None,
+                unread: Some(Read::Unread),
+                marked: None,
+                feed: None,
+                feed_blacklist: None,
+                category: None,
+                category_blacklist: None,
+                tag: None,
+                ids: None,
+                newer_than: None,
+                older_than: None,
+                search_term: None,
+            },
+        )
+        .context(CliErrorKind::DataBase)?;
+
+        Ok(match self.format {
+            OutputFormat::Text => articles
+                .iter()
+                .map(|article| {
+                    format!(
+                        "{}\t{}\t{}\t{}",
+                        article.article_id,
+                        article.date.format("%Y-%m-%d %H:%M"),
+                        Self::feed_label(&feeds, article),
+                        article.title.as_deref().unwrap_or("No Title")
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join("\n"),
+            OutputFormat::Json => {
+                let articles: Vec<serde_json::Value> = articles
+                    .iter()
+                    .map(|article| {
+                        json!({
+                            "id": article.article_id.to_string(),
+                            "title": article.title,
+                            "author": article.author,
+                            "feed_id": article.feed_id.to_string(),
+                            "feed": Self::feed_label(&feeds, article),
+                            "url": article.url.as_ref().map(|url| url.get().to_string()),
+                            "date": article.date.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                            "marked": article.marked == Marked::Marked,
+                        })
+                    })
+                    .collect();
+                serde_json::to_string_pretty(&articles).context(CliErrorKind::Serialize)?
+            }
+        })
+    }
+
+    fn mark_read(
+        &self,
+        news_flash: &NewsFlash,
+        settings: &Arc<RwLock<Settings>>,
+        article_id: &ArticleID,
+    ) -> Result<String, CliError> {
+        news_flash
+            .get_article(article_id)
+            .context(CliErrorKind::MissingArticle(article_id.to_string()))?;
+        Runtime::new()
+            .expect(RUNTIME_ERROR)
+            .block_on(news_flash.set_article_read(
+                std::slice::from_ref(article_id),
+                Read::Read,
+                &App::build_client(settings),
+            ))
+            .context(CliErrorKind::Backend)?;
+
+        Ok(match self.format {
+            OutputFormat::Text => String::new(),
+            OutputFormat::Json => json!({ "id": article_id.to_string(), "read": true }).to_string(),
+        })
+    }
+
+    fn export_opml(&self, news_flash: &NewsFlash, path: &PathBuf) -> Result<String, CliError> {
+        let opml = news_flash.export_opml().context(CliErrorKind::DataBase)?;
+        FileUtil::write_text_file(path, &opml).context(CliErrorKind::WriteFile)?;
+
+        Ok(match self.format {
+            OutputFormat::Text => String::new(),
+            OutputFormat::Json => json!({ "file": path.to_string_lossy() }).to_string(),
+        })
+    }
+
+    fn export_article(
+        &self,
+        news_flash: &NewsFlash,
+        settings: &Arc<RwLock<Settings>>,
+        article_id: &ArticleID,
+        path: Option<&PathBuf>,
+    ) -> Result<String, CliError> {
+        let article = Runtime::new()
+            .expect(RUNTIME_ERROR)
+            .block_on(news_flash.article_download_images(article_id, &App::build_client(settings)))
+            .context(CliErrorKind::MissingArticle(article_id.to_string()))?;
+        let (feeds, _mappings) = news_flash.get_feeds().context(CliErrorKind::DataBase)?;
+        let feed = feeds
+            .iter()
+            .find(|f| f.feed_id == article.feed_id)
+            .ok_or(CliErrorKind::MissingFeed)?;
+        let html = ArticleView::build_article_static("article", &article, &feed.label, settings, None, None, true);
+
+        match path {
+            Some(path) => {
+                FileUtil::write_text_file(path, &html).context(CliErrorKind::WriteFile)?;
+                Ok(match self.format {
+                    OutputFormat::Text => String::new(),
+                    OutputFormat::Json => json!({
+                        "id": article_id.to_string(),
+                        "file": path.to_string_lossy(),
+                    })
+                    .to_string(),
+                })
+            }
+            None => Ok(match self.format {
+                OutputFormat::Text => html,
+                OutputFormat::Json => json!({
+                    "id": article_id.to_string(),
+                    "title": article.title,
+                    "html": html,
+                })
+                .to_string(),
+            }),
+        }
+    }
+
+    fn feed_label<'a>(feeds: &'a [Feed], article: &Article) -> &'a str {
+        feeds
+            .iter()
+            .find(|f| f.feed_id == article.feed_id)
+            .map(|f| f.label.as_str())
+            .unwrap_or("")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Command, CommandLine, OutputFormat};
+    use news_flash::models::ArticleID;
+    use std::path::PathBuf;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        let mut vec = vec!["com.gitlab.newsflash".to_owned()];
+        vec.extend(args.iter().map(|a| (*a).to_owned()));
+        vec
+    }
+
+    #[test]
+    fn gui_arguments_are_ignored() {
+        assert!(CommandLine::parse(&args(&[])).is_none());
+        assert!(CommandLine::parse(&args(&["--gapplication-service"])).is_none());
+    }
+
+    #[test]
+    fn parse_subcommands() {
+        let cli = CommandLine::parse(&args(&["sync"])).unwrap().unwrap();
+        assert_eq!(cli.command, Command::Sync);
+        assert_eq!(cli.format, OutputFormat::Text);
+
+        let cli = CommandLine::parse(&args(&["list-unread", "--json"])).unwrap().unwrap();
+        assert_eq!(cli.command, Command::ListUnread);
+        assert_eq!(cli.format, OutputFormat::Json);
+
+        let cli = CommandLine::parse(&args(&["export-article", "--json", "abc", "out.html"]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            cli.command,
+            Command::ExportArticle(ArticleID::new("abc"), Some(PathBuf::from("out.html")))
+        );
+    }
+
+    #[test]
+    fn missing_argument() {
+        assert!(CommandLine::parse(&args(&["mark-read"])).unwrap().is_err());
+        assert!(CommandLine::parse(&args(&["export-opml", "--json"])).unwrap().is_err());
+    }
+}
@@ -1,14 +1,19 @@
+use super::date_range::{DateRange, DATE_FORMAT};
 use super::header_selection::HeaderSelection;
 use crate::app::Action;
-use crate::i18n::i18n;
+use crate::article_view::ArticleTheme;
+use crate::i18n::{i18n, i18n_f};
 use crate::main_window_state::MainWindowState;
+use crate::settings::{FeedViewSetting, FeedViewSettings, Settings};
 use crate::tag_popover::TagPopover;
 use crate::util::{BuilderHelper, GtkUtil, Util};
-use gio::{ActionMapExt, Menu, MenuItem, SimpleAction};
-use glib::{clone, object::Cast, source::Continue, translate::ToGlib, Sender};
+use chrono::NaiveDate;
+use gio::{ActionExt, ActionMapExt, Menu, MenuItem, SimpleAction};
+use glib::{clone, object::Cast, source::Continue, translate::ToGlib, Sender, ToVariant, VariantTy};
 use gtk::{
-    Button, ButtonExt, EntryExt, Inhibit, MenuButton, MenuButtonExt, Popover, PopoverExt, SearchEntry, SearchEntryExt,
-    Stack, StackExt, ToggleButton, ToggleButtonExt, Widget, WidgetExt,
+    BoxExt, Button, ButtonExt, ContainerExt, EditableSignals, Entry, EntryExt, Inhibit, Label, MenuButton,
+    MenuButtonExt, Orientation, Popover, PopoverExt, RadioButton, SearchEntry, SearchEntryExt, SpinButton,
+    SpinButtonExt, Stack, StackExt, StyleContextExt, ToggleButton, ToggleButtonExt, Widget, WidgetExt,
 };
 use libhandy::{SearchBar, SearchBarExt};
 use news_flash::models::{FatArticle, Marked, PluginCapabilities, Read};
@@ -16,9 +21,46 @@ use news_flash::NewsFlash;
 use parking_lot::RwLock;
 use std::sync::Arc;
 
+const FEED_ZOOM_LEVELS: [f64; 5] = [0.75, 1.0, 1.25, 1.5, 2.0];
+
+struct FeedViewActions {
+    scraped_content: SimpleAction,
+    open_in_browser: SimpleAction,
+    scrape_on_sync: SimpleAction,
+    zoom: SimpleAction,
+    theme: SimpleAction,
+}
+
+impl FeedViewActions {
+    fn set_enabled(&self, enabled: bool) {
+        self.scraped_content.set_enabled(enabled);
+        self.open_in_browser.set_enabled(enabled);
+        self.scrape_on_sync.set_enabled(enabled);
+        self.zoom.set_enabled(enabled);
+        self.theme.set_enabled(enabled);
+    }
+
+    fn update(&self, feed_settings: &FeedViewSettings) {
+        self.scraped_content
+            .set_state(&feed_settings.scraped_content.to_variant());
+        self.open_in_browser
+            .set_state(&feed_settings.open_in_browser.to_variant());
+        self.scrape_on_sync
+            .set_state(&feed_settings.scrape_on_sync.to_variant());
+        self.zoom
+            .set_state(&ContentHeader::zoom_action_target(feed_settings.zoom).to_variant());
+        let theme = match &feed_settings.theme {
+            Some(theme) => theme.to_action_target(),
+            None => "global".to_owned(),
+        };
+        self.theme.set_state(&theme.to_variant());
+    }
+}
+
 pub struct ContentHeader {
     sender: Sender<Action>,
     state: Arc<RwLock<MainWindowState>>,
+    settings: Arc<RwLock<Settings>>,
     update_stack: Stack,
     update_button: Button,
     offline_button: Button,
@@ -28,9 +70,12 @@ pub struct ContentHeader {
     search_entry: SearchEntry,
     mark_all_read_button: Button,
     mark_all_read_stack: Stack,
+    mark_older_popover: Popover,
+    mark_older_spin: SpinButton,
     all_button: ToggleButton,
     unread_button: ToggleButton,
     marked_button: ToggleButton,
+    date_range_button: MenuButton,
     scrap_content_button: ToggleButton,
     scrap_content_stack: Stack,
     scrap_content_event: RwLock<Option<usize>>,
@@ -38,6 +83,7 @@ pub struct ContentHeader {
     tag_popover: RwLock<Option<TagPopover>>,
     more_actions_button: MenuButton,
     more_actions_stack: Stack,
+    feed_view_actions: FeedViewActions,
     mode_switch_stack: Stack,
     mark_article_button: ToggleButton,
     mark_article_read_button: ToggleButton,
@@ -51,12 +97,14 @@ impl ContentHeader {
     pub fn new(
         builder: &BuilderHelper,
         state: &Arc<RwLock<MainWindowState>>,
+        settings: &Arc<RwLock<Settings>>,
         sender: Sender<Action>,
         features: &Arc<RwLock<Option<PluginCapabilities>>>,
     ) -> Self {
         let all_button = builder.get::<ToggleButton>("all_button");
         let unread_button = builder.get::<ToggleButton>("unread_button");
         let marked_button = builder.get::<ToggleButton>("marked_button");
+        let date_range_button = builder.get::<MenuButton>("date_range_button");
         let update_button = builder.get::<Button>("update_button");
         let update_stack = builder.get::<Stack>("update_stack");
         let offline_button = builder.get::<Button>("offline_status_button");
@@ -89,6 +137,8 @@ impl ContentHeader {
             Util::send(&sender, Action::SetSidebarRead);
         }));
 
+        let (mark_older_popover, mark_older_spin) = Self::setup_mark_older_popover(&mark_all_read_button, &sender);
+
         offline_button.connect_clicked(clone!(@strong sender => @default-panic, move |_button| {
             Util::send(&sender, Action::SetOfflineMode(false));
         }));
@@ -132,11 +182,13 @@ impl ContentHeader {
 
         Self::setup_menu_button(&menu_button, &sender, features);
         Self::setup_mode_button(&mode_button, &sender);
-        Self::setup_more_actions_button(&more_actions_button, &sender);
+        let feed_view_actions = Self::setup_more_actions_button(&more_actions_button, &sender);
+        Self::setup_date_range_button(&date_range_button, state, &sender);
 
         let header = ContentHeader {
             sender,
             state: state.clone(),
+            settings: settings.clone(),
             update_stack,
             update_button,
             offline_button,
@@ -146,9 +198,12 @@ impl ContentHeader {
             search_entry,
             mark_all_read_button,
             mark_all_read_stack,
+            mark_older_popover,
+            mark_older_spin,
             all_button,
             unread_button,
             marked_button,
+            date_range_button,
             scrap_content_button,
             scrap_content_stack,
             scrap_content_event: RwLock::new(None),
@@ -156,6 +211,7 @@ impl ContentHeader {
             tag_popover,
             more_actions_button,
             more_actions_stack,
+            feed_view_actions,
             mode_switch_stack,
             mark_article_button,
             mark_article_read_button,
@@ -212,6 +268,18 @@ impl ContentHeader {
         self.mode_switch_stack.set_visible_child_name("marked");
     }
 
+    pub fn set_date_range(&self, date_range: &DateRange) {
+        self.date_range_button
+            .set_tooltip_text(Some(&i18n_f("Published: {}", &[&date_range.label()])));
+
+        let style = self.date_range_button.get_style_context();
+        if date_range == &DateRange::All {
+            style.remove_class("suggested-action");
+        } else {
+            style.add_class("suggested-action");
+        }
+    }
+
     fn setup_linked_button(
         sender: &Sender<Action>,
         button: &ToggleButton,
@@ -325,6 +393,9 @@ impl ContentHeader {
     }
 
     fn setup_search_entry(search_entry: &SearchEntry, sender: &Sender<Action>) {
+        search_entry.set_tooltip_text(Some(&i18n(
+            "Filter with feed:, category:, tag:, author:, is:unread, is:starred, has:media, before:YYYY-MM-DD and after:YYYY-MM-DD. Use \"quotes\" for phrases and a leading - to exclude.",
+        )));
         search_entry.connect_search_changed(clone!(@strong sender => @default-panic, move |search_entry| {
             Util::send(&sender, Action::SearchTerm(search_entry.get_text().as_str().into()));
         }));
@@ -354,6 +425,16 @@ impl ContentHeader {
             Util::send(&sender, Action::ShowSettingsWindow);
         }));
 
+        let filter_rules_action = SimpleAction::new("filter-rules", None);
+        filter_rules_action.connect_activate(clone!(@strong sender => @default-panic, move |_action, _parameter| {
+            Util::send(&sender, Action::ShowFilterRulesDialog);
+        }));
+
+        let smart_folder_action = SimpleAction::new("new-smart-folder", None);
+        smart_folder_action.connect_activate(clone!(@strong sender => @default-panic, move |_action, _parameter| {
+            Util::send(&sender, Action::SmartFolderDialog(None));
+        }));
+
         let discover_dialog_action = SimpleAction::new("discover", None);
         discover_dialog_action.connect_activate(clone!(@strong sender => @default-panic, move |_action, _parameter| {
             Util::send(&sender, Action::ShowDiscoverDialog);
@@ -374,6 +455,11 @@ impl ContentHeader {
             Util::send(&sender, Action::ExportOpml);
         }));
 
+        let export_digest_action = SimpleAction::new("export-digest", None);
+        export_digest_action.connect_activate(clone!(@strong sender => @default-panic, move |_action, _parameter| {
+            Util::send(&sender, Action::ExportDigest);
+        }));
+
         let relogin_action = SimpleAction::new("relogin", None);
         relogin_action.connect_activate(clone!(@strong sender => @default-panic, move |_action, _parameter| {
             Util::send(&sender, Action::RetryLogin);
@@ -388,10 +474,13 @@ impl ContentHeader {
             main_window.add_action(&show_shortcut_window_action);
             main_window.add_action(&show_about_window_action);
             main_window.add_action(&settings_window_action);
+            main_window.add_action(&filter_rules_action);
+            main_window.add_action(&smart_folder_action);
             main_window.add_action(&discover_dialog_action);
             main_window.add_action(&quit_action);
             main_window.add_action(&import_opml_action);
             main_window.add_action(&export_opml_action);
+            main_window.add_action(&export_digest_action);
             main_window.add_action(&relogin_action);
             main_window.add_action(&reset_account_action);
         }
@@ -408,12 +497,15 @@ impl ContentHeader {
         let im_export_model = Menu::new();
         im_export_model.append(Some(&i18n("Import OPML")), Some("win.import-opml"));
         im_export_model.append(Some(&i18n("Export OPML")), Some("win.export-opml"));
+        im_export_model.append(Some(&i18n("Export Digest")), Some("win.export-digest"));
 
         let account_model = Menu::new();
         account_model.append(Some(&i18n("Reset Account")), Some("win.reset-account"));
 
         let main_model = Menu::new();
         main_model.append(Some(&i18n("Settings")), Some("win.settings"));
+        main_model.append(Some(&i18n("Filter Rules")), Some("win.filter-rules"));
+        main_model.append(Some(&i18n("Save as Smart Folder")), Some("win.new-smart-folder"));
         main_model.append(Some(&i18n("Discover Feeds")), Some("win.discover"));
         main_model.append_section(Some(""), &account_model);
         main_model.append_section(Some(""), &im_export_model);
@@ -464,7 +556,170 @@ impl ContentHeader {
         button.set_menu_model(Some(&model));
     }
 
-    fn setup_more_actions_button(button: &MenuButton, sender: &Sender<Action>) {
+    fn setup_mark_older_popover(button: &Button, sender: &Sender<Action>) -> (Popover, SpinButton) {
+        let popover = Popover::new(Some(button));
+        let content = gtk::Box::new(Orientation::Vertical, 6);
+        content.set_property_margin(12);
+
+        let spin_box = gtk::Box::new(Orientation::Horizontal, 6);
+        let spin = SpinButton::with_range(1.0, 3650.0, 1.0);
+        spin.set_value(7.0);
+        spin.set_activates_default(true);
+        spin_box.pack_start(&Label::new(Some(&i18n("Older than"))), false, false, 0);
+        spin_box.pack_start(&spin, false, false, 0);
+        spin_box.pack_start(&Label::new(Some(&i18n("days"))), false, false, 0);
+
+        let mark_button = Button::with_label(&i18n("Mark as Read"));
+        mark_button.get_style_context().add_class("suggested-action");
+        mark_button.set_can_default(true);
+
+        content.pack_start(&spin_box, false, false, 0);
+        content.pack_start(&mark_button, false, false, 0);
+        content.show_all();
+        popover.add(&content);
+        popover.set_default_widget(Some(&mark_button));
+
+        mark_button.connect_clicked(clone!(
+            @weak popover,
+            @weak spin,
+            @strong sender => @default-panic, move |_button|
+        {
+            spin.update();
+            Util::send(&sender, Action::SetSidebarReadOlderThan(spin.get_value_as_int() as u32));
+            popover.popdown();
+        }));
+
+        button.connect_button_press_event(clone!(@weak popover => @default-panic, move |button, event| {
+            if event.get_button() == 3 && button.is_sensitive() {
+                popover.popup();
+                return Inhibit(true);
+            }
+            Inhibit(false)
+        }));
+
+        (popover, spin)
+    }
+
+    fn setup_date_range_button(button: &MenuButton, state: &Arc<RwLock<MainWindowState>>, sender: &Sender<Action>) {
+        let popover = Popover::new(Some(button));
+        let content = gtk::Box::new(Orientation::Vertical, 6);
+        content.set_property_margin(12);
+
+        let all_radio = RadioButton::with_label(&DateRange::All.label());
+        let mut radios = vec![(all_radio.clone(), DateRange::All)];
+        for range in DateRange::presets()
+            .into_iter()
+            .filter(|range| range != &DateRange::All)
+        {
+            radios.push((RadioButton::with_label_from_widget(&all_radio, &range.label()), range));
+        }
+        for (radio, range) in &radios {
+            content.pack_start(radio, false, false, 0);
+            radio.connect_toggled(clone!(
+                @weak popover,
+                @strong range,
+                @strong state,
+                @strong sender => @default-panic, move |radio|
+            {
+                if radio.get_active() && state.read().get_date_range() != &range {
+                    Util::send(&sender, Action::DateRange(range.clone()));
+                    popover.popdown();
+                }
+            }));
+        }
+
+        let custom_radio = RadioButton::with_label_from_widget(&all_radio, &i18n("Custom range"));
+        content.pack_start(&custom_radio, false, false, 0);
+
+        let from_entry = Entry::new();
+        from_entry.set_placeholder_text(Some(&i18n("From (YYYY-MM-DD)")));
+        let to_entry = Entry::new();
+        to_entry.set_placeholder_text(Some(&i18n("To (YYYY-MM-DD)")));
+        let apply_button = Button::with_label(&i18n("Apply"));
+        for widget in &[
+            from_entry.clone().upcast::<Widget>(),
+            to_entry.clone().upcast::<Widget>(),
+        ] {
+            widget.set_margin_start(24);
+            content.pack_start(widget, false, false, 0);
+        }
+        apply_button.set_margin_start(24);
+        content.pack_start(&apply_button, false, false, 0);
+
+        for entry in &[&from_entry, &to_entry] {
+            entry.connect_changed(clone!(@weak custom_radio => @default-panic, move |entry| {
+                entry.get_style_context().remove_class("error");
+                custom_radio.set_active(true);
+            }));
+            entry.connect_activate(clone!(@weak apply_button => @default-panic, move |_entry| {
+                apply_button.clicked();
+            }));
+        }
+
+        apply_button.connect_clicked(clone!(
+            @weak popover,
+            @weak custom_radio,
+            @weak from_entry,
+            @weak to_entry,
+            @strong sender => @default-panic, move |_button|
+        {
+            let from = Self::parse_date_entry(&from_entry);
+            let to = Self::parse_date_entry(&to_entry);
+            if let (Ok(from), Ok(to)) = (from, to) {
+                if from.is_some() || to.is_some() {
+                    custom_radio.set_active(true);
+                    Util::send(&sender, Action::DateRange(DateRange::Custom(from, to)));
+                    popover.popdown();
+                }
+            }
+        }));
+
+        // reflect the current state whenever the popover is opened
+        button.connect_toggled(clone!(
+            @weak custom_radio,
+            @weak from_entry,
+            @weak to_entry,
+            @strong state => @default-panic, move |button|
+        {
+            if !button.get_active() {
+                return;
+            }
+            let current = state.read().get_date_range().clone();
+            match &current {
+                DateRange::Custom(from, to) => {
+                    let format = |date: &Option<NaiveDate>| date.map(|date| date.format(DATE_FORMAT).to_string());
+                    from_entry.set_text(&format(from).unwrap_or_default());
+                    to_entry.set_text(&format(to).unwrap_or_default());
+                    custom_radio.set_active(true);
+                }
+                current => {
+                    if let Some((radio, _range)) = radios.iter().find(|(_radio, range)| range == current) {
+                        radio.set_active(true);
+                    }
+                }
+            }
+        }));
+
+        content.show_all();
+        popover.add(&content);
+        button.set_popover(Some(&popover));
+    }
+
+    fn parse_date_entry(entry: &Entry) -> Result<Option<NaiveDate>, ()> {
+        let text = entry.get_text().as_str().trim().to_owned();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        match NaiveDate::parse_from_str(&text, DATE_FORMAT) {
+            Ok(date) => Ok(Some(date)),
+            Err(_) => {
+                entry.get_style_context().add_class("error");
+                Err(())
+            }
+        }
+    }
+
+    fn setup_more_actions_button(button: &MenuButton, sender: &Sender<Action>) -> FeedViewActions {
         let close_article_action = SimpleAction::new("close-article", None);
         close_article_action.connect_activate(clone!(@strong sender => @default-panic, move |_action, _parameter| {
             Util::send(&sender, Action::CloseArticle);
@@ -475,23 +730,163 @@ impl ContentHeader {
             Util::send(&sender, Action::ExportArticle);
         }));
 
+        let print_article_action = SimpleAction::new("print-article", None);
+        print_article_action.connect_activate(clone!(@strong sender => @default-panic, move |_action, _parameter| {
+            Util::send(&sender, Action::PrintArticle);
+        }));
+
+        let save_article_pdf_action = SimpleAction::new("save-article-pdf", None);
+        save_article_pdf_action.connect_activate(clone!(@strong sender => @default-panic, move |_action, _parameter| {
+            Util::send(&sender, Action::SaveArticlePdf);
+        }));
+
+        let download_enclosures_action = SimpleAction::new("download-enclosures", None);
+        download_enclosures_action.connect_activate(
+            clone!(@strong sender => @default-panic, move |_action, _parameter| {
+                Util::send(&sender, Action::DownloadEnclosures);
+            }),
+        );
+
         let open_article_action = SimpleAction::new("open-article-in-browser", None);
         open_article_action.connect_activate(clone!(@strong sender => @default-panic, move |_action, _parameter| {
             Util::send(&sender, Action::OpenSelectedArticle);
         }));
 
+        let feed_view_actions = Self::setup_feed_view_actions(sender);
+
         if let Ok(main_window) = GtkUtil::get_main_window(button) {
             main_window.add_action(&close_article_action);
             main_window.add_action(&open_article_action);
             main_window.add_action(&export_article_action);
+            main_window.add_action(&print_article_action);
+            main_window.add_action(&save_article_pdf_action);
+            main_window.add_action(&download_enclosures_action);
+            main_window.add_action(&feed_view_actions.scraped_content);
+            main_window.add_action(&feed_view_actions.open_in_browser);
+            main_window.add_action(&feed_view_actions.scrape_on_sync);
+            main_window.add_action(&feed_view_actions.zoom);
+            main_window.add_action(&feed_view_actions.theme);
+        }
+
+        let zoom_model = Menu::new();
+        for zoom in FEED_ZOOM_LEVELS.iter() {
+            let zoom = if (zoom - 1.0).abs() < std::f64::EPSILON {
+                None
+            } else {
+                Some(*zoom)
+            };
+            let label = format!("{}%", (zoom.unwrap_or(1.0) * 100.0).round());
+            let item = MenuItem::new(Some(&label), None);
+            item.set_action_and_target_value(
+                Some("win.feed-zoom"),
+                Some(&Self::zoom_action_target(zoom).to_variant()),
+            );
+            zoom_model.append_item(&item);
+        }
+
+        let theme_model = Menu::new();
+        let global_theme_item = MenuItem::new(Some(&i18n("Default")), None);
+        global_theme_item.set_action_and_target_value(Some("win.feed-theme"), Some(&"global".to_variant()));
+        theme_model.append_item(&global_theme_item);
+        for theme in ArticleTheme::all() {
+            let item = MenuItem::new(Some(&theme.name()), None);
+            item.set_action_and_target_value(Some("win.feed-theme"), Some(&theme.to_action_target().to_variant()));
+            theme_model.append_item(&item);
         }
 
+        let feed_model = Menu::new();
+        feed_model.append(
+            Some(&i18n("Always show scraped content")),
+            Some("win.feed-scraped-content"),
+        );
+        feed_model.append(
+            Some(&i18n("Fetch full content after sync")),
+            Some("win.feed-scrape-on-sync"),
+        );
+        feed_model.append(Some(&i18n("Open in browser instead")), Some("win.feed-open-in-browser"));
+        feed_model.append_submenu(Some(&i18n("Zoom")), &zoom_model);
+        feed_model.append_submenu(Some(&i18n("Theme")), &theme_model);
+
         let model = Menu::new();
         model.append(Some(&i18n("Export Article")), Some("win.export-article"));
+        model.append(Some(&i18n("Print…")), Some("win.print-article"));
+        model.append(Some(&i18n("Save as PDF…")), Some("win.save-article-pdf"));
+        model.append(Some(&i18n("Download Media")), Some("win.download-enclosures"));
         model.append(Some(&i18n("Open in browser")), Some("win.open-article-in-browser"));
         model.append(Some(&i18n("Close Article")), Some("win.close-article"));
+        model.append_submenu(Some(&i18n("Feed Preferences")), &feed_model);
         button.set_menu_model(Some(&model));
         button.set_sensitive(false);
+
+        feed_view_actions
+    }
+
+    fn setup_feed_view_actions(sender: &Sender<Action>) -> FeedViewActions {
+        let scraped_content = SimpleAction::new_stateful("feed-scraped-content", None, &false.to_variant());
+        scraped_content.connect_activate(clone!(@strong sender => @default-panic, move |action, _parameter| {
+            let active = !action.get_state().and_then(|state| state.get::<bool>()).unwrap_or(false);
+            action.set_state(&active.to_variant());
+            Util::send(&sender, Action::SetFeedViewSetting(FeedViewSetting::ScrapedContent(active)));
+        }));
+
+        let open_in_browser = SimpleAction::new_stateful("feed-open-in-browser", None, &false.to_variant());
+        open_in_browser.connect_activate(clone!(@strong sender => @default-panic, move |action, _parameter| {
+            let active = !action.get_state().and_then(|state| state.get::<bool>()).unwrap_or(false);
+            action.set_state(&active.to_variant());
+            Util::send(&sender, Action::SetFeedViewSetting(FeedViewSetting::OpenInBrowser(active)));
+        }));
+
+        let scrape_on_sync = SimpleAction::new_stateful("feed-scrape-on-sync", None, &false.to_variant());
+        scrape_on_sync.connect_activate(clone!(@strong sender => @default-panic, move |action, _parameter| {
+            let active = !action.get_state().and_then(|state| state.get::<bool>()).unwrap_or(false);
+            action.set_state(&active.to_variant());
+            Util::send(&sender, Action::SetFeedViewSetting(FeedViewSetting::ScrapeOnSync(active)));
+        }));
+
+        let zoom = SimpleAction::new_stateful(
+            "feed-zoom",
+            Some(VariantTy::new("s").expect("valid type string")),
+            &Self::zoom_action_target(None).to_variant(),
+        );
+        zoom.connect_activate(clone!(@strong sender => @default-panic, move |action, parameter| {
+            if let Some(parameter) = parameter {
+                if let Some(target) = parameter.get::<String>() {
+                    action.set_state(parameter);
+                    let zoom = target.parse::<f64>().ok();
+                    Util::send(&sender, Action::SetFeedViewSetting(FeedViewSetting::Zoom(zoom)));
+                }
+            }
+        }));
+
+        let theme = SimpleAction::new_stateful(
+            "feed-theme",
+            Some(VariantTy::new("s").expect("valid type string")),
+            &"global".to_variant(),
+        );
+        theme.connect_activate(clone!(@strong sender => @default-panic, move |action, parameter| {
+            if let Some(parameter) = parameter {
+                if let Some(target) = parameter.get::<String>() {
+                    action.set_state(parameter);
+                    let theme = ArticleTheme::from_action_target(&target);
+                    Util::send(&sender, Action::SetFeedViewSetting(FeedViewSetting::Theme(theme)));
+                }
+            }
+        }));
+
+        FeedViewActions {
+            scraped_content,
+            open_in_browser,
+            scrape_on_sync,
+            zoom,
+            theme,
+        }
+    }
+
+    fn zoom_action_target(zoom: Option<f64>) -> String {
+        match zoom {
+            Some(zoom) => zoom.to_string(),
+            None => "default".to_owned(),
+        }
     }
 
     fn unread_button_state(article: Option<&FatArticle>) -> (&str, bool) {
@@ -592,6 +987,11 @@ impl ContentHeader {
         );
 
         self.more_actions_button.set_sensitive(sensitive);
+        self.feed_view_actions.set_enabled(sensitive);
+        if let Some(article) = article {
+            let feed_settings = self.settings.read().get_feed_view_settings(&article.feed_id);
+            self.feed_view_actions.update(&feed_settings);
+        }
 
         if !self.state.read().get_offline() {
             let mut tag_support = false;
@@ -646,6 +1046,17 @@ impl ContentHeader {
         self.more_actions_stack.set_visible_child_name("image");
     }
 
+    pub fn set_mark_older_than_days(&self, days: u32) {
+        self.mark_older_spin.set_value(f64::from(days));
+    }
+
+    pub fn show_mark_older_popover(&self) {
+        if self.mark_all_read_button.is_sensitive() {
+            self.mark_older_popover.popup();
+            self.mark_older_spin.grab_focus();
+        }
+    }
+
     pub fn finish_mark_all_read(&self) {
         self.mark_all_read_button.set_sensitive(true);
         self.mark_all_read_stack.set_visible_child_name("image");
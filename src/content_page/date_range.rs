@@ -0,0 +1,210 @@
+use crate::i18n::{i18n, i18n_f};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Clone, Debug, Serialize, PartialEq, Deserialize)]
+pub enum DateRange {
+    All,
+    Today,
+    ThisWeek,
+    LastDays(u32),
+    // first and last day (inclusive), open ended if `None`
+    Custom(
+        #[serde(with = "optional_date")] Option<NaiveDate>,
+        #[serde(with = "optional_date")] Option<NaiveDate>,
+    ),
+}
+
+impl Default for DateRange {
+    fn default() -> Self {
+        DateRange::All
+    }
+}
+
+impl DateRange {
+    pub fn presets() -> Vec<Self> {
+        vec![
+            DateRange::All,
+            DateRange::Today,
+            DateRange::ThisWeek,
+            DateRange::LastDays(7),
+            DateRange::LastDays(30),
+        ]
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            DateRange::All => i18n("All time"),
+            DateRange::Today => i18n("Today"),
+            DateRange::ThisWeek => i18n("This week"),
+            DateRange::LastDays(days) => i18n_f("Last {} days", &[&days.to_string()]),
+            DateRange::Custom(Some(from), Some(to)) => i18n_f(
+                "{} to {}",
+                &[
+                    &from.format(DATE_FORMAT).to_string(),
+                    &to.format(DATE_FORMAT).to_string(),
+                ],
+            ),
+            DateRange::Custom(Some(from), None) => i18n_f("Since {}", &[&from.format(DATE_FORMAT).to_string()]),
+            DateRange::Custom(None, Some(to)) => i18n_f("Until {}", &[&to.format(DATE_FORMAT).to_string()]),
+            DateRange::Custom(None, None) => i18n("All time"),
+        }
+    }
+
+    pub fn to_id(&self) -> String {
+        match self {
+            DateRange::All => "all".to_owned(),
+            DateRange::Today => "today".to_owned(),
+            DateRange::ThisWeek => "week".to_owned(),
+            DateRange::LastDays(days) => format!("days-{}", days),
+            DateRange::Custom(from, to) => format!(
+                "custom-{}-{}",
+                from.map(|date| date.format("%Y%m%d").to_string()).unwrap_or_default(),
+                to.map(|date| date.format("%Y%m%d").to_string()).unwrap_or_default(),
+            ),
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "all" => Some(DateRange::All),
+            "today" => Some(DateRange::Today),
+            "week" => Some(DateRange::ThisWeek),
+            _ if id.starts_with("days-") => id["days-".len()..].parse::<u32>().ok().map(DateRange::LastDays),
+            _ if id.starts_with("custom-") => {
+                let mut dates = id["custom-".len()..].split('-').map(|date| {
+                    if date.is_empty() {
+                        Ok(None)
+                    } else {
+                        NaiveDate::parse_from_str(date, "%Y%m%d").map(Some)
+                    }
+                });
+                match (dates.next(), dates.next(), dates.next()) {
+                    (Some(Ok(from)), Some(Ok(to)), None) => Some(DateRange::Custom(from, to)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    pub fn start_of_day(date: NaiveDate) -> Option<DateTime<Utc>> {
+        Local
+            .from_local_datetime(&date.and_hms(0, 0, 0))
+            .earliest()
+            .map(|date_time| date_time.with_timezone(&Utc))
+    }
+
+    pub fn intersect(
+        a: (Option<DateTime<Utc>>, Option<DateTime<Utc>>),
+        b: (Option<DateTime<Utc>>, Option<DateTime<Utc>>),
+    ) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+        let newer_than = match (a.0, b.0) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        let older_than = match (a.1, b.1) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        (newer_than, older_than)
+    }
+
+    pub fn bounds(&self) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+        self.bounds_at(Local::now())
+    }
+
+    fn bounds_at(&self, now: DateTime<Local>) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+        let start_of_day = |days_back: i64| {
+            (now.date() - Duration::days(days_back))
+                .and_hms_opt(0, 0, 0)
+                .map(|midnight| midnight.with_timezone(&Utc))
+        };
+
+        match self {
+            DateRange::All => (None, None),
+            DateRange::Today => (start_of_day(0), None),
+            DateRange::ThisWeek => (start_of_day(i64::from(now.weekday().num_days_from_monday())), None),
+            DateRange::LastDays(days) => (Some((now - Duration::days(i64::from(*days))).with_timezone(&Utc)), None),
+            DateRange::Custom(from, to) => (
+                from.and_then(Self::start_of_day),
+                to.and_then(|to| Self::start_of_day(to + Duration::days(1))),
+            ),
+        }
+    }
+}
+
+mod optional_date {
+    use super::DATE_FORMAT;
+    use chrono::NaiveDate;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(date: &Option<NaiveDate>, serializer: S) -> Result<S::Ok, S::Error> {
+        match date {
+            Some(date) => serializer.serialize_some(&date.format(DATE_FORMAT).to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<NaiveDate>, D::Error> {
+        let date: Option<String> = Option::deserialize(deserializer)?;
+        match date {
+            Some(date) => NaiveDate::parse_from_str(&date, DATE_FORMAT)
+                .map(Some)
+                .map_err(serde::de::Error::custom),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DateRange;
+    use chrono::{Duration, Local, NaiveDate, TimeZone, Utc};
+
+    #[test]
+    fn bounds() {
+        // Wednesday
+        let now = Local.ymd(2020, 6, 17).and_hms(15, 30, 0);
+
+        assert_eq!(DateRange::All.bounds_at(now), (None, None));
+
+        let (today, _) = DateRange::Today.bounds_at(now);
+        assert_eq!(today, Some(Local.ymd(2020, 6, 17).and_hms(0, 0, 0).with_timezone(&Utc)));
+
+        let (week, _) = DateRange::ThisWeek.bounds_at(now);
+        assert_eq!(week, Some(Local.ymd(2020, 6, 15).and_hms(0, 0, 0).with_timezone(&Utc)));
+
+        let (last_days, older_than) = DateRange::LastDays(3).bounds_at(now);
+        assert_eq!(last_days, Some((now - Duration::days(3)).with_timezone(&Utc)));
+        assert_eq!(older_than, None);
+
+        let custom = DateRange::Custom(None, Some(NaiveDate::from_ymd(2020, 6, 1)));
+        assert_eq!(
+            custom.bounds_at(now),
+            (None, Some(Local.ymd(2020, 6, 2).and_hms(0, 0, 0).with_timezone(&Utc)))
+        );
+    }
+
+    #[test]
+    fn id_round_trip() {
+        for range in DateRange::presets() {
+            assert_eq!(DateRange::from_id(&range.to_id()), Some(range));
+        }
+        assert_eq!(DateRange::from_id("days-x"), None);
+
+        let custom = DateRange::Custom(Some(NaiveDate::from_ymd(2020, 1, 1)), None);
+        assert_eq!(DateRange::from_id(&custom.to_id()), Some(custom));
+        assert_eq!(DateRange::from_id("custom-2020-"), None);
+    }
+
+    #[test]
+    fn serialize_custom() {
+        let custom = DateRange::Custom(Some(NaiveDate::from_ymd(2020, 1, 1)), None);
+        let json = serde_json::to_string(&custom).unwrap();
+        assert_eq!(json, r#"{"Custom":["2020-01-01",null]}"#);
+        assert_eq!(serde_json::from_str::<DateRange>(&json).unwrap(), custom);
+    }
+}
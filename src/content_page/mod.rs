@@ -1,20 +1,29 @@
 mod content_header;
+mod date_range;
 mod error;
 mod header_selection;
+mod search_query;
 
 pub use self::content_header::ContentHeader;
+pub use self::date_range::DateRange;
 pub use self::header_selection::HeaderSelection;
+pub use self::search_query::SearchQuery;
 
 use self::error::{ContentPageError, ContentPageErrorKind};
 use crate::app::Action;
 use crate::article_list::{ArticleList, ArticleListModel};
 use crate::article_view::ArticleView;
+use crate::enclosures::MediaArticles;
+use crate::filter_rules::{FilterContext, FilterRule, FilterRules};
 use crate::main_window_state::MainWindowState;
+use crate::offline::OfflineQueue;
 use crate::settings::Settings;
 use crate::sidebar::models::SidebarSelection;
 use crate::sidebar::{FeedListTree, SideBar, TagListModel};
+use crate::smart_folders::SmartFolder;
 use crate::undo_bar::{UndoActionModel, UndoBar};
 use crate::util::{BuilderHelper, Util, CHANNEL_ERROR};
+use chrono::Local;
 use failure::ResultExt;
 use futures::channel::oneshot;
 use futures::executor::ThreadPool;
@@ -22,22 +31,29 @@ use futures_util::future::FutureExt;
 use glib::{clone, Sender};
 use gtk::{Box, BoxExt, WidgetExt};
 use libhandy::Leaflet;
-use log::warn;
+use log::{error, warn};
 use news_flash::models::{
-    Article, ArticleFilter, Category, CategoryType, Marked, PluginCapabilities, PluginID, Read, NEWSFLASH_TOPLEVEL,
+    Article, ArticleFilter, Category, CategoryType, FeedID, Marked, PluginCapabilities, PluginID, Read,
+    NEWSFLASH_TOPLEVEL,
 };
 use news_flash::NewsFlash;
 use parking_lot::RwLock;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 pub struct ContentPage {
     pub sidebar: Arc<RwLock<SideBar>>,
     pub article_list: Arc<RwLock<ArticleList>>,
     pub article_view: ArticleView,
+    pub media_articles: Arc<RwLock<MediaArticles>>,
     settings: Arc<RwLock<Settings>>,
     state: Arc<RwLock<MainWindowState>>,
     sender: Sender<Action>,
+    // Article counts of smart folders that need a full query, cleared whenever articles change.
+    // Keyed by the day, the counted header selection and the folder definition,
+    // so relative date ranges and edited folders are counted again.
+    smart_folder_counts: Arc<RwLock<HashMap<String, i64>>>,
+    offline_queue: Arc<RwLock<OfflineQueue>>,
 }
 
 impl ContentPage {
@@ -48,6 +64,7 @@ impl ContentPage {
         content_header: &Arc<ContentHeader>,
         sender: Sender<Action>,
         features: &Arc<RwLock<Option<PluginCapabilities>>>,
+        offline_queue: &Arc<RwLock<OfflineQueue>>,
     ) -> Self {
         let feed_list_box = builder.get::<Box>("feedlist_box");
         let article_list_box = builder.get::<Box>("articlelist_box");
@@ -71,19 +88,31 @@ impl ContentPage {
         articleview_box.pack_start(&article_view.widget(), false, true, 0);
 
         let settings = settings.clone();
+        let media_articles = MediaArticles::open().unwrap_or_else(|error| {
+            error!("Failed to read media articles: {}", error);
+            MediaArticles::default()
+        });
 
         ContentPage {
             sidebar,
             article_list,
             article_view,
+            media_articles: Arc::new(RwLock::new(media_articles)),
             settings,
             state: state.clone(),
             sender,
+            smart_folder_counts: Arc::new(RwLock::new(HashMap::new())),
+            offline_queue: offline_queue.clone(),
         }
     }
 
+    pub fn invalidate_smart_folder_counts(&self) {
+        self.smart_folder_counts.write().clear();
+    }
+
     pub fn clear(&self) {
         self.article_view.close_article();
+        self.invalidate_smart_folder_counts();
         self.state.write().set_prefer_scraped_content(false);
 
         let list_model = ArticleListModel::new(&self.settings.read().get_article_list_order());
@@ -123,7 +152,7 @@ impl ContentPage {
         undo_bar: &UndoBar,
         threadpool: ThreadPool,
     ) {
-        let (sender, receiver) = oneshot::channel::<Result<ArticleListModel, ContentPageErrorKind>>();
+        let (sender, receiver) = oneshot::channel::<Result<(ArticleListModel, i64), ContentPageErrorKind>>();
 
         let relevant_articles_loaded = self
             .article_list
@@ -135,6 +164,8 @@ impl ContentPage {
         let current_undo_action = undo_bar.get_current_action();
         let processing_undo_actions = undo_bar.processing_actions();
         let settings = self.settings.clone();
+        let offline_queue = self.offline_queue.clone();
+        let media_articles = self.media_articles.clone();
         let thread_future = async move {
             if let Some(news_flash) = news_flash.read().as_ref() {
                 let limit = if window_state.write().reset_article_list() {
@@ -145,16 +176,18 @@ impl ContentPage {
                     MainWindowState::page_size()
                 };
                 let mut list_model = ArticleListModel::new(&settings.read().get_article_list_order());
-                let mut articles = match Self::load_articles(
+                let (mut articles, offset) = match Self::load_articles(
                     news_flash,
                     &window_state,
                     &settings,
+                    &offline_queue,
+                    &media_articles,
                     &current_undo_action,
                     &processing_undo_actions,
                     limit,
                     None,
                 ) {
-                    Ok(articles) => articles,
+                    Ok(result) => result,
                     Err(error) => {
                         sender.send(Err(error.kind())).expect(CHANNEL_ERROR);
                         return;
@@ -182,7 +215,7 @@ impl ContentPage {
                     })
                     .collect();
 
-                sender.send(Ok(list_model)).expect(CHANNEL_ERROR);
+                sender.send(Ok((list_model, offset))).expect(CHANNEL_ERROR);
             }
         };
 
@@ -191,7 +224,8 @@ impl ContentPage {
             @weak self.article_list as article_list => @default-panic, move |res|
         {
             if let Ok(res) = res {
-                if let Ok(article_list_model) = res {
+                if let Ok((article_list_model, offset)) = res {
+                    window_state.write().set_article_list_offset(offset);
                     article_list.write().update(article_list_model, &window_state);
                 }
             }
@@ -208,32 +242,42 @@ impl ContentPage {
         undo_bar: &UndoBar,
         threadpool: ThreadPool,
     ) {
-        let (sender, receiver) = oneshot::channel::<Result<ArticleListModel, ContentPageErrorKind>>();
-
-        let relevant_articles_loaded = self
-            .article_list
-            .read()
-            .get_relevant_article_count(window_state.read().get_header_selection());
+        let (sender, receiver) = oneshot::channel::<Result<(ArticleListModel, i64), ContentPageErrorKind>>();
+
+        // Articles that no longer match the header selection (e.g. read while showing unread articles)
+        // have moved out of the database result, so the offset shifts back by their number.
+        let irrelevant_articles = {
+            let article_list = self.article_list.read();
+            (article_list.get_article_count()
+                - article_list.get_relevant_article_count(window_state.read().get_header_selection()))
+                as i64
+        };
+        let offset = (window_state.read().get_article_list_offset() - irrelevant_articles).max(0);
 
         let current_undo_action = undo_bar.get_current_action();
         let processing_undo_actions = undo_bar.processing_actions();
         let settings = self.settings.clone();
+        let offline_queue = self.offline_queue.clone();
+        let media_articles = self.media_articles.clone();
         let news_flash = news_flash_handle.clone();
+        let state = window_state.clone();
         let window_state = window_state.clone();
         let thread_future = async move {
             let mut list_model = ArticleListModel::new(&settings.read().get_article_list_order());
 
             if let Some(news_flash) = news_flash.read().as_ref() {
-                let mut articles = match Self::load_articles(
+                let (mut articles, offset) = match Self::load_articles(
                     news_flash,
                     &window_state,
                     &settings,
+                    &offline_queue,
+                    &media_articles,
                     &current_undo_action,
                     &processing_undo_actions,
                     MainWindowState::page_size(),
-                    Some(relevant_articles_loaded as i64),
+                    Some(offset),
                 ) {
-                    Ok(articles) => articles,
+                    Ok(result) => result,
                     Err(error) => {
                         sender.send(Err(error.kind())).expect(CHANNEL_ERROR);
                         return;
@@ -260,58 +304,47 @@ impl ContentPage {
                     })
                     .collect();
 
-                sender.send(Ok(list_model)).expect(CHANNEL_ERROR);
+                sender.send(Ok((list_model, offset))).expect(CHANNEL_ERROR);
             }
         };
 
-        let glib_future = receiver.map(
-            clone!(@weak self.article_list as article_list => @default-panic, move |res| {
-                if let Ok(res) = res {
-                    if let Ok(article_list_model) = res {
-                        article_list.write().add_more_articles(article_list_model);
-                    }
+        let glib_future = receiver.map(clone!(
+            @weak state,
+            @weak self.article_list as article_list => @default-panic, move |res|
+        {
+            if let Ok(res) = res {
+                if let Ok((article_list_model, offset)) = res {
+                    state.write().set_article_list_offset(offset + irrelevant_articles);
+                    article_list.write().add_more_articles(article_list_model);
                 }
-            }),
-        );
+            }
+        }));
 
         threadpool.spawn_ok(thread_future);
         Util::glib_spawn_future(glib_future);
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn load_articles(
         news_flash: &NewsFlash,
         window_state: &RwLock<MainWindowState>,
         settings: &Arc<RwLock<Settings>>,
+        offline_queue: &RwLock<OfflineQueue>,
+        media_articles: &RwLock<MediaArticles>,
         current_undo_action: &Option<UndoActionModel>,
         processing_undo_actions: &Arc<RwLock<HashSet<UndoActionModel>>>,
         limit: i64,
         offset: Option<i64>,
-    ) -> Result<Vec<Article>, ContentPageError> {
-        let unread = match window_state.read().get_header_selection() {
-            HeaderSelection::All | HeaderSelection::Marked => None,
-            HeaderSelection::Unread => Some(Read::Unread),
-        };
-        let marked = match window_state.read().get_header_selection() {
-            HeaderSelection::All | HeaderSelection::Unread => None,
-            HeaderSelection::Marked => Some(Marked::Marked),
-        };
-        let feed = match &window_state.read().get_sidebar_selection() {
-            SidebarSelection::All | SidebarSelection::Category(_, _) | SidebarSelection::Tag(_, _) => None,
-            SidebarSelection::Feed(id, _parent_id, _title) => Some(id.clone()),
-        };
-        let category = match &window_state.read().get_sidebar_selection() {
-            SidebarSelection::All | SidebarSelection::Feed(_, _, _) | SidebarSelection::Tag(_, _) => None,
-            SidebarSelection::Category(id, _title) => Some(id.clone()),
-        };
-        let tag = match &window_state.read().get_sidebar_selection() {
-            SidebarSelection::All | SidebarSelection::Feed(_, _, _) | SidebarSelection::Category(_, _) => None,
-            SidebarSelection::Tag(id, _title) => Some(id.clone()),
-        };
+    ) -> Result<(Vec<Article>, i64), ContentPageError> {
+        let sidebar_selection = window_state.read().get_sidebar_selection().clone();
+        let header_selection = window_state.read().get_header_selection().clone();
         let search_term = window_state.read().get_search_term().clone();
-        let (feed_blacklist, category_blacklist) = {
+        let date_bounds = window_state.read().get_date_range().bounds();
+        let (feed_blacklist, category_blacklist, pending_batches) = {
             let mut undo_actions = Vec::new();
             let mut feed_blacklist = Vec::new();
             let mut category_blacklist = Vec::new();
+            let mut pending_batches = Vec::new();
             if let Some(current_undo_action) = current_undo_action {
                 undo_actions.push(current_undo_action);
             }
@@ -326,6 +359,16 @@ impl ContentPage {
                         category_blacklist.push(category_id.clone())
                     }
                     UndoActionModel::DeleteTag(_tag_id, _label) => {}
+                    UndoActionModel::MarkOlderRead(_selection, _days, _timestamp) => {}
+                    UndoActionModel::ArticleBatch(article_ids, operation) => {
+                        pending_batches.push((article_ids.clone(), operation.clone()))
+                    }
+                    UndoActionModel::ArticleChange(_, _)
+                    | UndoActionModel::RenameFeed(_, _, _)
+                    | UndoActionModel::RenameCategory(_, _, _)
+                    | UndoActionModel::MoveFeed(_, _, _)
+                    | UndoActionModel::MoveCategory(_, _, _)
+                    | UndoActionModel::MarkAllRead(_, _) => {}
                 }
             }
 
@@ -340,29 +383,139 @@ impl ContentPage {
                 Some(category_blacklist)
             };
 
-            (feed_blacklist, category_blacklist)
+            (feed_blacklist, category_blacklist, pending_batches)
         };
 
-        let articles = news_flash
-            .get_articles(ArticleFilter {
-                limit: Some(limit),
-                offset,
-                order: Some(settings.read().get_article_list_order()),
-                unread,
-                marked,
-                feed,
-                feed_blacklist,
-                category,
-                category_blacklist,
-                tag,
-                ids: None,
-                newer_than: None,
-                older_than: None,
-                search_term,
-            })
+        let filter = match Self::selection_filter(
+            news_flash,
+            settings,
+            &sidebar_selection,
+            &header_selection,
+            search_term,
+            feed_blacklist,
+        )? {
+            Some(filter) => filter,
+            None => return Ok((Vec::new(), offset.unwrap_or(0))),
+        };
+        let (newer_than, older_than) = DateRange::intersect((filter.newer_than, filter.older_than), date_bounds);
+        let filter = ArticleFilter {
+            limit: Some(limit),
+            offset,
+            order: Some(settings.read().get_article_list_order()),
+            category_blacklist,
+            newer_than,
+            older_than,
+            ..filter
+        };
+
+        let not_hidden = Self::not_hidden(news_flash, settings)?;
+        let (mut articles, next_offset) = SearchQuery::load_page(news_flash, filter, media_articles, not_hidden)
             .context(ContentPageErrorKind::DataBase)?;
 
-        Ok(articles)
+        // show batch operations that are still pending in the undo bar as already applied
+        for (article_ids, operation) in &pending_batches {
+            for article in articles
+                .iter_mut()
+                .filter(|article| article_ids.contains(&article.article_id))
+            {
+                if let Some(read) = operation.read() {
+                    article.unread = read;
+                }
+                if let Some(marked) = operation.marked() {
+                    article.marked = marked;
+                }
+            }
+        }
+
+        // same for changes made while offline that were not sent to the backend yet
+        offline_queue.read().apply(&mut articles);
+
+        Ok((articles, next_offset))
+    }
+
+    // `None` if the selected smart folder doesn't exist anymore
+    pub fn selection_filter(
+        news_flash: &NewsFlash,
+        settings: &Arc<RwLock<Settings>>,
+        sidebar_selection: &SidebarSelection,
+        header_selection: &HeaderSelection,
+        search_term: Option<String>,
+        feed_blacklist: Option<Vec<FeedID>>,
+    ) -> Result<Option<ArticleFilter<'static>>, ContentPageError> {
+        if let SidebarSelection::SmartFolder(id, _title) = sidebar_selection {
+            let folder = match settings.read().get_smart_folder(id) {
+                Some(folder) => folder,
+                None => return Ok(None),
+            };
+            let (feeds, mappings) = news_flash.get_feeds().context(ContentPageErrorKind::DataBase)?;
+            let categories = news_flash.get_categories().context(ContentPageErrorKind::DataBase)?;
+            let feed_ids: Vec<FeedID> = feeds.into_iter().map(|feed| feed.feed_id).collect();
+            let mut blacklist = folder
+                .feed_blacklist(&feed_ids, &mappings, &categories)
+                .unwrap_or_default();
+            blacklist.append(&mut feed_blacklist.unwrap_or_default());
+            let feed_blacklist = if blacklist.is_empty() { None } else { Some(blacklist) };
+            return Ok(Some(folder.article_filter(
+                header_selection,
+                search_term,
+                feed_blacklist,
+            )));
+        }
+
+        let unread = match header_selection {
+            HeaderSelection::All | HeaderSelection::Marked => None,
+            HeaderSelection::Unread => Some(Read::Unread),
+        };
+        let marked = match header_selection {
+            HeaderSelection::All | HeaderSelection::Unread => None,
+            HeaderSelection::Marked => Some(Marked::Marked),
+        };
+        let (feed, category, tag) = match sidebar_selection {
+            SidebarSelection::All | SidebarSelection::SmartFolder(_, _) => (None, None, None),
+            SidebarSelection::Feed(id, _parent_id, _title) => (Some(id.clone()), None, None),
+            SidebarSelection::Category(id, _title) => (None, Some(id.clone()), None),
+            SidebarSelection::Tag(id, _title) => (None, None, Some(id.clone())),
+        };
+
+        Ok(Some(ArticleFilter {
+            limit: None,
+            offset: None,
+            order: None,
+            unread,
+            marked,
+            feed,
+            feed_blacklist,
+            category,
+            category_blacklist: None,
+            tag,
+            ids: None,
+            newer_than: None,
+            older_than: None,
+            search_term,
+        }))
+    }
+
+    // meant for `SearchQuery::load_page`, so hidden articles don't count against the limit
+    pub fn not_hidden(
+        news_flash: &NewsFlash,
+        settings: &Arc<RwLock<Settings>>,
+    ) -> Result<impl Fn(&Article) -> bool, ContentPageError> {
+        let hide_rules: Vec<FilterRule> = settings
+            .read()
+            .get_filter_rules()
+            .into_iter()
+            .filter(|rule| rule.hides())
+            .collect();
+        let hide_rules = FilterRules::compile(&hide_rules);
+        let context = if hide_rules.is_empty() {
+            None
+        } else {
+            Some(FilterContext::load(news_flash).context(ContentPageErrorKind::DataBase)?)
+        };
+        Ok(move |article: &Article| match &context {
+            Some(context) => !FilterRules::is_hidden(&hide_rules, article, context),
+            None => true,
+        })
     }
 
     pub fn update_sidebar(
@@ -372,14 +525,18 @@ impl ContentPage {
         threadpool: ThreadPool,
         features: &Arc<RwLock<Option<PluginCapabilities>>>,
     ) {
-        let (sender, receiver) =
-            oneshot::channel::<Result<(i64, FeedListTree, Option<TagListModel>), ContentPageErrorKind>>();
+        let (sender, receiver) = oneshot::channel::<
+            Result<(i64, FeedListTree, Option<TagListModel>, Vec<(SmartFolder, i64)>), ContentPageErrorKind>,
+        >();
 
         let news_flash = news_flash.clone();
         let state = self.state.clone();
         let current_undo_action = undo_bar.get_current_action();
         let processing_undo_actions = undo_bar.processing_actions();
         let app_features = features.clone();
+        let smart_folders = self.settings.read().get_smart_folders();
+        let smart_folder_counts = self.smart_folder_counts.clone();
+        let media_articles = self.media_articles.clone();
         let global_sender = self.sender.clone();
         let thread_future = async move {
             if let Some(news_flash) = news_flash.read().as_ref() {
@@ -427,6 +584,14 @@ impl ContentPage {
                         UndoActionModel::DeleteFeed(id, _label) => pending_delte_feeds.insert(id),
                         UndoActionModel::DeleteCategory(id, _label) => pending_delete_categories.insert(id),
                         UndoActionModel::DeleteTag(id, _label) => pending_delete_tags.insert(id),
+                        UndoActionModel::MarkOlderRead(_selection, _days, _timestamp) => false,
+                        UndoActionModel::ArticleBatch(_article_ids, _operation) => false,
+                        UndoActionModel::ArticleChange(_, _)
+                        | UndoActionModel::RenameFeed(_, _, _)
+                        | UndoActionModel::RenameCategory(_, _, _)
+                        | UndoActionModel::MoveFeed(_, _, _)
+                        | UndoActionModel::MoveCategory(_, _, _)
+                        | UndoActionModel::MarkAllRead(_, _) => false,
                     };
                 }
                 let processing_undo_actions = &*processing_undo_actions.read();
@@ -435,6 +600,14 @@ impl ContentPage {
                         UndoActionModel::DeleteFeed(id, _label) => pending_delte_feeds.insert(id),
                         UndoActionModel::DeleteCategory(id, _label) => pending_delete_categories.insert(id),
                         UndoActionModel::DeleteTag(id, _label) => pending_delete_tags.insert(id),
+                        UndoActionModel::MarkOlderRead(_selection, _days, _timestamp) => false,
+                        UndoActionModel::ArticleBatch(_article_ids, _operation) => false,
+                        UndoActionModel::ArticleChange(_, _)
+                        | UndoActionModel::RenameFeed(_, _, _)
+                        | UndoActionModel::RenameCategory(_, _, _)
+                        | UndoActionModel::MoveFeed(_, _, _)
+                        | UndoActionModel::MoveCategory(_, _, _)
+                        | UndoActionModel::MarkAllRead(_, _) => false,
                     };
                 }
 
@@ -554,8 +727,68 @@ impl ContentPage {
                     &pending_delete_categories,
                 );
 
+                // smart folders: count articles the same way as feeds
+                let count_header = match state.read().get_header_selection() {
+                    HeaderSelection::All | HeaderSelection::Unread => HeaderSelection::Unread,
+                    HeaderSelection::Marked => HeaderSelection::Marked,
+                };
+                let feed_ids: Vec<FeedID> = feeds.iter().map(|feed| feed.feed_id.clone()).collect();
+                let mut folder_counts = Vec::new();
+                for folder in smart_folders {
+                    let mut feed_blacklist = folder
+                        .feed_blacklist(&feed_ids, &mappings, &categories)
+                        .unwrap_or_default();
+                    feed_blacklist.extend(pending_delte_feeds.iter().map(|id| (*id).clone()));
+                    let feed_blacklist = if feed_blacklist.is_empty() {
+                        None
+                    } else {
+                        Some(feed_blacklist)
+                    };
+
+                    // folders restricted to feeds only add up the feed counts from above
+                    if folder.search_term.is_none()
+                        && folder.date_range == DateRange::All
+                        && (folder.header == HeaderSelection::All || folder.header == count_header)
+                    {
+                        let count = feed_ids
+                            .iter()
+                            .filter(|feed_id| {
+                                feed_blacklist
+                                    .as_ref()
+                                    .map(|blacklist| !blacklist.contains(*feed_id))
+                                    .unwrap_or(true)
+                            })
+                            .filter_map(|feed_id| feed_count_map.get(feed_id))
+                            .sum();
+                        folder_counts.push((folder, count));
+                        continue;
+                    }
+
+                    let key = format!(
+                        "{} {:?} {}",
+                        Local::today().naive_local(),
+                        count_header,
+                        serde_json::to_string(&folder).unwrap_or_else(|_| folder.id.clone())
+                    );
+                    if let Some(count) = smart_folder_counts.read().get(&key) {
+                        folder_counts.push((folder, *count));
+                        continue;
+                    }
+
+                    let filter = folder.article_filter(&count_header, None, feed_blacklist);
+                    let count = match SearchQuery::count_articles(news_flash, filter, &media_articles) {
+                        Ok(count) => count,
+                        Err(_error) => {
+                            sender.send(Err(ContentPageErrorKind::DataBase)).expect(CHANNEL_ERROR);
+                            return;
+                        }
+                    };
+                    smart_folder_counts.write().insert(key, count);
+                    folder_counts.push((folder, count));
+                }
+
                 sender
-                    .send(Ok((total_item_count, tree, tag_list_model)))
+                    .send(Ok((total_item_count, tree, tag_list_model, folder_counts)))
                     .expect(CHANNEL_ERROR);
             }
         };
@@ -567,9 +800,10 @@ impl ContentPage {
                 match res {
                     Ok(res) => {
                         match res {
-                            Ok((total_count, feed_list_model, tag_list_model)) => {
+                            Ok((total_count, feed_list_model, tag_list_model, smart_folders)) => {
                                 sidebar.write().update_feedlist(feed_list_model, &features);
                                 sidebar.write().update_all(total_count);
+                                sidebar.read().update_smart_folders(smart_folders);
                                 if let Some(tag_list_model) = tag_list_model {
                                     if tag_list_model.is_empty() {
                                         sidebar.read().hide_taglist();
@@ -0,0 +1,730 @@
+use super::date_range::{DateRange, DATE_FORMAT};
+use crate::enclosures::MediaArticles;
+use crate::util::Util;
+use chrono::NaiveDate;
+use news_flash::models::{
+    Article, ArticleFilter, ArticleID, ArticleOrder, Category, Feed, FeedID, FeedMapping, Marked, Read, Tag,
+};
+use news_flash::{NewsFlash, NewsFlashError};
+use parking_lot::RwLock;
+use std::collections::{HashMap, HashSet};
+
+const PAGE_SIZE: i64 = 500;
+
+// Search bar query with support for
+// `feed:`, `category:`, `tag:`, `author:`, `is:unread`, `is:read`, `is:starred`, `has:media`,
+// `before:YYYY-MM-DD`, `after:YYYY-MM-DD`, "quoted phrases" and negation with a leading `-`.
+// Everything else is passed on as free text.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchQuery {
+    pub text: Vec<String>,
+    pub excluded_text: Vec<String>,
+    pub feeds: Vec<String>,
+    pub excluded_feeds: Vec<String>,
+    pub categories: Vec<String>,
+    pub excluded_categories: Vec<String>,
+    pub tags: Vec<String>,
+    pub excluded_tags: Vec<String>,
+    pub authors: Vec<String>,
+    pub excluded_authors: Vec<String>,
+    pub unread: Option<bool>,
+    pub starred: Option<bool>,
+    pub media: Option<bool>,
+    pub before: Option<NaiveDate>,
+    pub after: Option<NaiveDate>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Token {
+    negated: bool,
+    key: Option<String>,
+    value: String,
+    quoted: bool,
+}
+
+pub struct SearchContext {
+    feeds: Vec<Feed>,
+    mappings: Vec<FeedMapping>,
+    categories: Vec<Category>,
+    tags: Vec<Tag>,
+}
+
+impl SearchContext {
+    pub fn load(news_flash: &NewsFlash) -> Result<Self, NewsFlashError> {
+        let (feeds, mappings) = news_flash.get_feeds()?;
+        Ok(SearchContext {
+            feeds,
+            mappings,
+            categories: news_flash.get_categories()?,
+            tags: news_flash.get_tags()?,
+        })
+    }
+}
+
+// loaded with one query per tag instead of looking up the tags of every single article
+#[derive(Debug, Default)]
+pub struct TaggedArticles {
+    by_name: HashMap<String, HashSet<ArticleID>>,
+}
+
+impl TaggedArticles {
+    fn load(query: &SearchQuery, context: &SearchContext, news_flash: &NewsFlash) -> Result<Self, NewsFlashError> {
+        let mut by_name: HashMap<String, HashSet<ArticleID>> = HashMap::new();
+        for name in query.tags.iter().chain(query.excluded_tags.iter()) {
+            if by_name.contains_key(name) {
+                continue;
+            }
+            let mut article_ids = HashSet::new();
+            for tag in SearchQuery::matching(&context.tags, name, |tag| &tag.label) {
+                let articles = Util::load_all_articles(
+                    news_flash,
+                    ArticleFilter {
+                        limit: None,
+                        offset: None,
+                        order: None,
+                        unread: None,
+                        marked: None,
+                        feed: None,
+                        feed_blacklist: None,
+                        category: None,
+                        category_blacklist: None,
+                        tag: Some(tag.tag_id.clone()),
+                        ids: None,
+                        newer_than: None,
+                        older_than: None,
+                        search_term: None,
+                    },
+                )?;
+                article_ids.extend(articles.into_iter().map(|article| article.article_id));
+            }
+            by_name.insert(name.clone(), article_ids);
+        }
+        Ok(TaggedArticles { by_name })
+    }
+
+    fn contains(&self, name: &str, article_id: &ArticleID) -> bool {
+        self.by_name
+            .get(name)
+            .map(|article_ids| article_ids.contains(article_id))
+            .unwrap_or(false)
+    }
+}
+
+impl SearchQuery {
+    pub fn parse(query: &str) -> Self {
+        let mut result = SearchQuery::default();
+
+        for token in Self::tokenize(query) {
+            if token.value.is_empty() {
+                continue;
+            }
+
+            let key = token.key.as_ref().map(|key| key.to_lowercase());
+            let handled = match key.as_deref() {
+                Some("feed") => result.push(token.negated, token.value.clone(), FieldKind::Feed),
+                Some("category") => result.push(token.negated, token.value.clone(), FieldKind::Category),
+                Some("tag") => result.push(token.negated, token.value.clone(), FieldKind::Tag),
+                Some("author") => result.push(token.negated, token.value.clone(), FieldKind::Author),
+                Some("is") => match token.value.to_lowercase().as_str() {
+                    "unread" => {
+                        result.unread = Some(!token.negated);
+                        true
+                    }
+                    "read" => {
+                        result.unread = Some(token.negated);
+                        true
+                    }
+                    "starred" => {
+                        result.starred = Some(!token.negated);
+                        true
+                    }
+                    _ => false,
+                },
+                Some("has") if token.value.to_lowercase() == "media" => {
+                    result.media = Some(!token.negated);
+                    true
+                }
+                Some("before") if !token.negated => match NaiveDate::parse_from_str(&token.value, DATE_FORMAT) {
+                    Ok(date) => {
+                        result.before = Some(date);
+                        true
+                    }
+                    Err(_) => false,
+                },
+                Some("after") if !token.negated => match NaiveDate::parse_from_str(&token.value, DATE_FORMAT) {
+                    Ok(date) => {
+                        result.after = Some(date);
+                        true
+                    }
+                    Err(_) => false,
+                },
+                _ => false,
+            };
+
+            if !handled {
+                let mut text = match &token.key {
+                    Some(key) => format!("{}:{}", key, token.value),
+                    None => token.value.clone(),
+                };
+                if token.quoted && token.key.is_none() {
+                    text = format!("\"{}\"", text);
+                }
+                if token.negated {
+                    result.excluded_text.push(text.trim_matches('"').to_owned());
+                } else {
+                    result.text.push(text);
+                }
+            }
+        }
+
+        result
+    }
+
+    fn push(&mut self, negated: bool, value: String, kind: FieldKind) -> bool {
+        let list = match (kind, negated) {
+            (FieldKind::Feed, false) => &mut self.feeds,
+            (FieldKind::Feed, true) => &mut self.excluded_feeds,
+            (FieldKind::Category, false) => &mut self.categories,
+            (FieldKind::Category, true) => &mut self.excluded_categories,
+            (FieldKind::Tag, false) => &mut self.tags,
+            (FieldKind::Tag, true) => &mut self.excluded_tags,
+            (FieldKind::Author, false) => &mut self.authors,
+            (FieldKind::Author, true) => &mut self.excluded_authors,
+        };
+        list.push(value);
+        true
+    }
+
+    fn tokenize(query: &str) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut chars = query.chars().peekable();
+
+        loop {
+            while chars.peek().map(|c| c.is_whitespace()).unwrap_or(false) {
+                chars.next();
+            }
+            if chars.peek().is_none() {
+                break;
+            }
+
+            let mut token = Token {
+                negated: false,
+                key: None,
+                value: String::new(),
+                quoted: false,
+            };
+            if chars.peek() == Some(&'-') {
+                chars.next();
+                token.negated = true;
+            }
+
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => {
+                        token.quoted = true;
+                        for c in &mut chars {
+                            if c == '"' {
+                                break;
+                            }
+                            token.value.push(c);
+                        }
+                    }
+                    ':' if token.key.is_none() && !token.quoted && !token.value.is_empty() => {
+                        token.key = Some(std::mem::take(&mut token.value));
+                    }
+                    c if c.is_whitespace() => break,
+                    c => token.value.push(c),
+                }
+            }
+
+            tokens.push(token);
+        }
+
+        tokens
+    }
+
+    pub fn search_term(&self) -> Option<String> {
+        if self.text.is_empty() {
+            None
+        } else {
+            Some(self.text.join(" "))
+        }
+    }
+
+    // `false` if the query can't match any article
+    pub fn apply(&self, filter: &mut ArticleFilter, context: &SearchContext) -> bool {
+        filter.search_term = self.search_term();
+
+        if let Some(unread) = self.unread {
+            let read = if unread { Read::Unread } else { Read::Read };
+            match &filter.unread {
+                Some(current) if current != &read => return false,
+                _ => filter.unread = Some(read),
+            }
+        }
+        if let Some(starred) = self.starred {
+            let marked = if starred { Marked::Marked } else { Marked::Unmarked };
+            match &filter.marked {
+                Some(current) if current != &marked => return false,
+                _ => filter.marked = Some(marked),
+            }
+        }
+
+        if let Some(after) = self.after.and_then(DateRange::start_of_day) {
+            filter.newer_than = Some(filter.newer_than.map(|current| current.max(after)).unwrap_or(after));
+        }
+        if let Some(before) = self.before.and_then(DateRange::start_of_day) {
+            filter.older_than = Some(filter.older_than.map(|current| current.min(before)).unwrap_or(before));
+        }
+
+        let mut blacklist: HashSet<FeedID> = HashSet::new();
+        for name in &self.feeds {
+            let matching: HashSet<FeedID> = Self::matching(&context.feeds, name, |feed| &feed.label)
+                .map(|feed| feed.feed_id.clone())
+                .collect();
+            if matching.is_empty() {
+                return false;
+            }
+            // blacklisting every other feed is only the fallback: each blacklisted feed adds to the SQL expression
+            match &filter.feed {
+                Some(feed_id) if matching.contains(feed_id) => {}
+                Some(_) => return false,
+                None if matching.len() == 1 => filter.feed = matching.into_iter().next(),
+                None => blacklist.extend(Self::all_feeds_except(context, &matching)),
+            }
+        }
+        for name in &self.excluded_feeds {
+            blacklist.extend(Self::matching(&context.feeds, name, |feed| &feed.label).map(|feed| feed.feed_id.clone()));
+        }
+        for name in &self.categories {
+            let mut category_ids: Vec<_> = Self::matching(&context.categories, name, |category| &category.label)
+                .map(|category| category.category_id.clone())
+                .collect();
+            if category_ids.is_empty() {
+                return false;
+            }
+            let matching = Util::feeds_of_categories(&category_ids, &context.categories, &context.mappings);
+            // the database only matches feeds mapped directly to `filter.category`, not those of subcategories
+            let has_subcategories = context
+                .categories
+                .iter()
+                .any(|category| category_ids.contains(&category.parent_id));
+            match &filter.feed {
+                Some(feed_id) if matching.contains(feed_id) => {}
+                Some(_) => return false,
+                None if category_ids.len() == 1 && !has_subcategories && filter.category.is_none() => {
+                    filter.category = category_ids.pop()
+                }
+                None => blacklist.extend(Self::all_feeds_except(context, &matching)),
+            }
+        }
+        for name in &self.excluded_categories {
+            let category_ids: Vec<_> = Self::matching(&context.categories, name, |category| &category.label)
+                .map(|category| category.category_id.clone())
+                .collect();
+            blacklist.extend(Util::feeds_of_categories(
+                &category_ids,
+                &context.categories,
+                &context.mappings,
+            ));
+        }
+        if let Some(feed_id) = &filter.feed {
+            if blacklist.contains(feed_id) {
+                return false;
+            }
+        } else if !blacklist.is_empty() {
+            let mut feed_blacklist = filter.feed_blacklist.take().unwrap_or_default();
+            feed_blacklist.extend(blacklist);
+            filter.feed_blacklist = Some(feed_blacklist);
+        }
+
+        for name in &self.tags {
+            let mut matching = Self::matching(&context.tags, name, |tag| &tag.label);
+            match (matching.next(), matching.next()) {
+                (None, _) => return false,
+                // a single tag can be handled by the database, everything else is checked by `matches`
+                (Some(tag), None) if filter.tag.is_none() => filter.tag = Some(tag.tag_id.clone()),
+                _ => {}
+            }
+        }
+
+        true
+    }
+
+    pub fn needs_post_filter(&self) -> bool {
+        !self.excluded_text.is_empty()
+            || !self.tags.is_empty()
+            || !self.excluded_tags.is_empty()
+            || !self.authors.is_empty()
+            || !self.excluded_authors.is_empty()
+            || self.media.is_some()
+    }
+
+    pub fn needs_tags(&self) -> bool {
+        !self.tags.is_empty() || !self.excluded_tags.is_empty()
+    }
+
+    // `tagged` is only required if `needs_tags` is true
+    pub fn matches(&self, article: &Article, tagged: &TaggedArticles, media_articles: &MediaArticles) -> bool {
+        let author = article.author.as_deref().unwrap_or("");
+        let contains = |haystack: &str, needle: &str| haystack.to_lowercase().contains(&needle.to_lowercase());
+
+        if !self.authors.iter().all(|name| contains(author, name)) {
+            return false;
+        }
+        if self.excluded_authors.iter().any(|name| contains(author, name)) {
+            return false;
+        }
+
+        let has_tag = |name: &String| tagged.contains(name, &article.article_id);
+        if !self.tags.iter().all(has_tag) || self.excluded_tags.iter().any(has_tag) {
+            return false;
+        }
+
+        if let Some(media) = self.media {
+            if media_articles.contains(&article.article_id) != media {
+                return false;
+            }
+        }
+
+        let title = article.title.as_deref().unwrap_or("");
+        let summary = article.summary.as_deref().unwrap_or("");
+        !self
+            .excluded_text
+            .iter()
+            .any(|text| contains(title, text) || contains(summary, text))
+    }
+
+    pub fn load_articles(
+        news_flash: &NewsFlash,
+        filter: ArticleFilter,
+        media_articles: &RwLock<MediaArticles>,
+    ) -> Result<Vec<Article>, NewsFlashError> {
+        Self::load_page(news_flash, filter, media_articles, |_article| true).map(|(articles, _offset)| articles)
+    }
+
+    // count page by page, so the matching articles are never all in memory at once
+    pub fn count_articles(
+        news_flash: &NewsFlash,
+        mut filter: ArticleFilter,
+        media_articles: &RwLock<MediaArticles>,
+    ) -> Result<i64, NewsFlashError> {
+        filter.limit = Some(PAGE_SIZE);
+        filter.offset = None;
+        filter.order = Some(ArticleOrder::NewestFirst);
+        let mut count = 0;
+        Self::load_page(news_flash, filter, media_articles, |_article| {
+            count += 1;
+            false
+        })?;
+        Ok(count)
+    }
+
+    // conditions checked after the database query don't count against the limit: further pages are loaded
+    // until enough articles are collected or the database runs out of articles
+    // without a limit all matching articles are loaded, `PAGE_SIZE` at a time
+    // returns the articles and the offset in the database to continue loading from
+    pub fn load_page<F>(
+        news_flash: &NewsFlash,
+        mut filter: ArticleFilter,
+        media_articles: &RwLock<MediaArticles>,
+        mut keep: F,
+    ) -> Result<(Vec<Article>, i64), NewsFlashError>
+    where
+        F: FnMut(&Article) -> bool,
+    {
+        let limit = filter.limit.take();
+        let page_size = limit.unwrap_or(PAGE_SIZE);
+        let mut offset = filter.offset.take().unwrap_or(0);
+
+        let query = filter.search_term.take().map(|term| Self::parse(&term));
+        let mut tagged = TaggedArticles::default();
+        if let Some(query) = &query {
+            let context = SearchContext::load(news_flash)?;
+            if !query.apply(&mut filter, &context) {
+                return Ok((Vec::new(), offset));
+            }
+            if query.needs_tags() {
+                tagged = TaggedArticles::load(query, &context, news_flash)?;
+            }
+        }
+
+        let mut articles = Vec::new();
+        loop {
+            let page = news_flash.get_articles(Self::page_filter(&filter, page_size, offset))?;
+            let exhausted = (page.len() as i64) < page_size;
+
+            for article in page {
+                offset += 1;
+                let matches = match &query {
+                    Some(query) if query.needs_post_filter() => {
+                        query.matches(&article, &tagged, &media_articles.read())
+                    }
+                    _ => true,
+                };
+                if matches && keep(&article) {
+                    articles.push(article);
+                    if Some(articles.len() as i64) == limit {
+                        return Ok((articles, offset));
+                    }
+                }
+            }
+
+            if exhausted {
+                return Ok((articles, offset));
+            }
+        }
+    }
+
+    fn page_filter<'a>(filter: &ArticleFilter<'a>, limit: i64, offset: i64) -> ArticleFilter<'a> {
+        ArticleFilter {
+            limit: Some(limit),
+            offset: Some(offset),
+            order: filter.order.clone(),
+            unread: filter.unread,
+            marked: filter.marked,
+            feed: filter.feed.clone(),
+            feed_blacklist: filter.feed_blacklist.clone(),
+            category: filter.category.clone(),
+            category_blacklist: filter.category_blacklist.clone(),
+            tag: filter.tag.clone(),
+            ids: filter.ids,
+            newer_than: filter.newer_than,
+            older_than: filter.older_than,
+            search_term: None,
+        }
+    }
+
+    // items whose label equals `name` or, if there is no such item, contain it (case insensitive)
+    fn matching<'a, T, F>(items: &'a [T], name: &str, label: F) -> std::vec::IntoIter<&'a T>
+    where
+        F: Fn(&T) -> &String,
+    {
+        let name = name.to_lowercase();
+        let exact: Vec<&T> = items.iter().filter(|item| label(item).to_lowercase() == name).collect();
+        if !exact.is_empty() {
+            return exact.into_iter();
+        }
+        items
+            .iter()
+            .filter(|item| label(item).to_lowercase().contains(&name))
+            .collect::<Vec<&T>>()
+            .into_iter()
+    }
+
+    fn all_feeds_except(context: &SearchContext, feeds: &HashSet<FeedID>) -> Vec<FeedID> {
+        context
+            .feeds
+            .iter()
+            .filter(|feed| !feeds.contains(&feed.feed_id))
+            .map(|feed| feed.feed_id.clone())
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy)]
+enum FieldKind {
+    Feed,
+    Category,
+    Tag,
+    Author,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SearchContext, SearchQuery};
+    use crate::content_page::DateRange;
+    use chrono::NaiveDate;
+    use news_flash::models::{
+        ArticleFilter, Category, CategoryID, CategoryType, Feed, FeedID, FeedMapping, NEWSFLASH_TOPLEVEL,
+    };
+
+    fn context() -> SearchContext {
+        let feed = |id: &str, label: &str| Feed {
+            feed_id: FeedID::new(id),
+            label: label.to_owned(),
+            website: None,
+            feed_url: None,
+            icon_url: None,
+            sort_index: None,
+        };
+        let category = |id: &str, parent: &CategoryID| Category {
+            category_id: CategoryID::new(id),
+            label: id.to_owned(),
+            parent_id: parent.clone(),
+            sort_index: None,
+            category_type: CategoryType::Default,
+        };
+        let mapping = |feed: &str, category: &str| FeedMapping {
+            feed_id: FeedID::new(feed),
+            category_id: CategoryID::new(category),
+        };
+
+        SearchContext {
+            feeds: vec![
+                feed("gnome", "Planet GNOME"),
+                feed("kde", "Planet KDE"),
+                feed("lwn", "LWN"),
+            ],
+            mappings: vec![
+                mapping("gnome", "desktop"),
+                mapping("kde", "kde"),
+                mapping("lwn", "news"),
+            ],
+            categories: vec![
+                category("desktop", &NEWSFLASH_TOPLEVEL),
+                category("kde", &CategoryID::new("desktop")),
+                category("news", &NEWSFLASH_TOPLEVEL),
+            ],
+            tags: Vec::new(),
+        }
+    }
+
+    fn empty_filter() -> ArticleFilter<'static> {
+        ArticleFilter {
+            limit: None,
+            offset: None,
+            order: None,
+            unread: None,
+            marked: None,
+            feed: None,
+            feed_blacklist: None,
+            category: None,
+            category_blacklist: None,
+            tag: None,
+            ids: None,
+            newer_than: None,
+            older_than: None,
+            search_term: None,
+        }
+    }
+
+    #[test]
+    fn free_text() {
+        let query = SearchQuery::parse("  rust  \"gtk bindings\" ");
+        assert_eq!(query.text, vec!["rust".to_owned(), "\"gtk bindings\"".to_owned()]);
+        assert_eq!(query.search_term(), Some("rust \"gtk bindings\"".to_owned()));
+        assert!(!query.needs_post_filter());
+        assert_eq!(SearchQuery::parse("").search_term(), None);
+    }
+
+    #[test]
+    fn fields() {
+        let query = SearchQuery::parse(
+            "feed:\"Planet GNOME\" category:news tag:later author:jane is:unread is:starred after:2020-01-31 linux",
+        );
+        assert_eq!(query.feeds, vec!["Planet GNOME".to_owned()]);
+        assert_eq!(query.categories, vec!["news".to_owned()]);
+        assert_eq!(query.tags, vec!["later".to_owned()]);
+        assert_eq!(query.authors, vec!["jane".to_owned()]);
+        assert_eq!(query.unread, Some(true));
+        assert_eq!(query.starred, Some(true));
+        assert_eq!(query.after, Some(NaiveDate::from_ymd(2020, 1, 31)));
+        assert_eq!(query.before, None);
+        assert_eq!(query.text, vec!["linux".to_owned()]);
+    }
+
+    #[test]
+    fn negation() {
+        let query = SearchQuery::parse("-feed:reddit -is:unread -\"press release\" -is:starred -tag:done");
+        assert_eq!(query.excluded_feeds, vec!["reddit".to_owned()]);
+        assert_eq!(query.unread, Some(false));
+        assert_eq!(query.starred, Some(false));
+        assert_eq!(query.excluded_text, vec!["press release".to_owned()]);
+        assert_eq!(query.excluded_tags, vec!["done".to_owned()]);
+        assert!(query.text.is_empty());
+        assert!(query.needs_post_filter());
+        assert!(query.needs_tags());
+    }
+
+    #[test]
+    fn unknown_keys_are_text() {
+        let query = SearchQuery::parse("https://example.com before:yesterday is:great feed:");
+        assert_eq!(
+            query.text,
+            vec![
+                "https://example.com".to_owned(),
+                "before:yesterday".to_owned(),
+                "is:great".to_owned()
+            ]
+        );
+        assert_eq!(query.before, None);
+        assert!(query.feeds.is_empty());
+    }
+
+    #[test]
+    fn media() {
+        let query = SearchQuery::parse("has:media");
+        assert_eq!(query.media, Some(true));
+        assert!(query.needs_post_filter());
+        assert!(query.text.is_empty());
+
+        assert_eq!(SearchQuery::parse("-has:Media").media, Some(false));
+        assert_eq!(SearchQuery::parse("has:images").text, vec!["has:images".to_owned()]);
+    }
+
+    #[test]
+    fn apply_dates() {
+        let context = context();
+        let mut filter = empty_filter();
+        assert!(SearchQuery::parse("after:2020-01-31 before:2020-03-01").apply(&mut filter, &context));
+        assert_eq!(
+            filter.newer_than,
+            DateRange::start_of_day(NaiveDate::from_ymd(2020, 1, 31))
+        );
+        assert_eq!(
+            filter.older_than,
+            DateRange::start_of_day(NaiveDate::from_ymd(2020, 3, 1))
+        );
+
+        // bounds already set by the selection are only ever narrowed
+        assert!(SearchQuery::parse("after:2020-01-01 before:2020-02-15").apply(&mut filter, &context));
+        assert_eq!(
+            filter.newer_than,
+            DateRange::start_of_day(NaiveDate::from_ymd(2020, 1, 31))
+        );
+        assert_eq!(
+            filter.older_than,
+            DateRange::start_of_day(NaiveDate::from_ymd(2020, 2, 15))
+        );
+    }
+
+    #[test]
+    fn apply_feeds() {
+        let context = context();
+
+        let mut filter = empty_filter();
+        assert!(SearchQuery::parse("feed:\"planet gnome\"").apply(&mut filter, &context));
+        assert_eq!(filter.feed, Some(FeedID::new("gnome")));
+        assert!(filter.feed_blacklist.is_none());
+
+        let mut filter = empty_filter();
+        assert!(SearchQuery::parse("feed:planet").apply(&mut filter, &context));
+        assert_eq!(filter.feed, None);
+        assert_eq!(filter.feed_blacklist, Some(vec![FeedID::new("lwn")]));
+
+        assert!(!SearchQuery::parse("feed:lwn -feed:lwn").apply(&mut empty_filter(), &context));
+        assert!(!SearchQuery::parse("feed:nothing").apply(&mut empty_filter(), &context));
+    }
+
+    #[test]
+    fn apply_categories() {
+        let context = context();
+
+        let mut filter = empty_filter();
+        assert!(SearchQuery::parse("category:news").apply(&mut filter, &context));
+        assert_eq!(filter.category, Some(CategoryID::new("news")));
+        assert!(filter.feed_blacklist.is_none());
+
+        // feeds of subcategories aren't matched by the database
+        let mut filter = empty_filter();
+        assert!(SearchQuery::parse("category:desktop").apply(&mut filter, &context));
+        assert_eq!(filter.category, None);
+        assert_eq!(filter.feed_blacklist, Some(vec![FeedID::new("lwn")]));
+
+        let mut filter = empty_filter();
+        assert!(SearchQuery::parse("feed:gnome category:desktop").apply(&mut filter, &context));
+        assert_eq!(filter.feed, Some(FeedID::new("gnome")));
+        assert!(!SearchQuery::parse("feed:lwn category:desktop").apply(&mut empty_filter(), &context));
+    }
+}
@@ -0,0 +1,238 @@
+use crate::add_dialog::AddCategory;
+use crate::app::Action;
+use crate::article_list::ReadUpdate;
+use crate::util::{Util, GTK_RESOURCE_FILE_ERROR};
+use crate::Resources;
+use gio::{DBusConnection, DBusMethodInvocation, DBusNodeInfo, RegistrationId};
+use glib::translate::{from_glib_full, from_glib_none, ToGlibPtr};
+use glib::{glib_sys, Sender, ToVariant, Variant, VariantTy};
+use log::{error, warn};
+use news_flash::models::{ArticleID, CategoryID, Read, Url};
+use news_flash::NewsFlash;
+use parking_lot::RwLock;
+use std::str;
+use std::sync::Arc;
+
+pub const DBUS_INTERFACE: &str = "com.gitlab.newsflash.Control";
+const PROPERTIES_INTERFACE: &str = "org.freedesktop.DBus.Properties";
+const INVALID_ARGS_ERROR: &str = "org.freedesktop.DBus.Error.InvalidArgs";
+const UNKNOWN_METHOD_ERROR: &str = "org.freedesktop.DBus.Error.UnknownMethod";
+
+#[derive(Clone, Debug, Default)]
+pub struct DBusState {
+    pub unread_count: i64,
+    pub syncing: bool,
+    pub offline: bool,
+}
+
+// remote control object next to the `org.gtk.Application` interface of the running instance
+pub struct DBusService {
+    connection: DBusConnection,
+    object_path: String,
+    registration_id: Option<RegistrationId>,
+    state: Arc<RwLock<DBusState>>,
+}
+
+impl DBusService {
+    pub fn register(connection: &DBusConnection, object_path: &str, sender: &Sender<Action>) -> Option<Self> {
+        let xml_data = Resources::get("dbus/control.xml").expect(GTK_RESOURCE_FILE_ERROR);
+        let xml = str::from_utf8(xml_data.as_ref()).expect(GTK_RESOURCE_FILE_ERROR);
+        let interface_info = match DBusNodeInfo::new_for_xml(xml) {
+            Ok(node_info) => match node_info.lookup_interface(DBUS_INTERFACE) {
+                Some(interface_info) => interface_info,
+                None => {
+                    warn!("D-Bus interface '{}' is missing from its description", DBUS_INTERFACE);
+                    return None;
+                }
+            },
+            Err(error) => {
+                error!("Failed to parse D-Bus interface description: {}", error);
+                return None;
+            }
+        };
+
+        let state = Arc::new(RwLock::new(DBusState::default()));
+        let method_sender = sender.clone();
+        let property_state = state.clone();
+        let registration_id = connection.register_object(
+            object_path,
+            &interface_info,
+            move |_connection, _sender, _path, _interface, method, parameters, invocation| {
+                Self::method_call(&method_sender, method, &parameters, invocation)
+            },
+            move |_connection, _sender, _path, _interface, property| Self::get_property(&property_state, property),
+            |_connection, _sender, _path, _interface, _property, _value| false,
+        );
+
+        match registration_id {
+            Ok(registration_id) => Some(DBusService {
+                connection: connection.clone(),
+                object_path: object_path.to_owned(),
+                registration_id: Some(registration_id),
+                state,
+            }),
+            Err(error) => {
+                error!("Failed to register D-Bus object '{}': {}", object_path, error);
+                None
+            }
+        }
+    }
+
+    pub fn unregister(&mut self) {
+        if let Some(registration_id) = self.registration_id.take() {
+            if let Err(error) = self.connection.unregister_object(registration_id) {
+                warn!("{}", error);
+            }
+        }
+    }
+
+    fn set_unread_count(&self, unread_count: i64) {
+        if self.state.read().unread_count != unread_count {
+            self.state.write().unread_count = unread_count;
+            self.emit_property_changed("UnreadCount", unread_count.to_variant());
+        }
+    }
+
+    // keep the last published count if the database can't be read, 0 would tell clients there is nothing unread
+    pub fn update_unread_count(&self, news_flash: &NewsFlash) {
+        match news_flash.unread_count_all() {
+            Ok(unread_count) => self.set_unread_count(unread_count),
+            Err(error) => error!("Failed to read unread count for D-Bus: {}", error),
+        }
+    }
+
+    pub fn set_syncing(&self, syncing: bool) {
+        if self.state.read().syncing != syncing {
+            self.state.write().syncing = syncing;
+            self.emit_property_changed("Syncing", syncing.to_variant());
+        }
+    }
+
+    pub fn set_offline(&self, offline: bool) {
+        if self.state.read().offline != offline {
+            self.state.write().offline = offline;
+            self.emit_property_changed("Offline", offline.to_variant());
+        }
+    }
+
+    fn method_call(sender: &Sender<Action>, method: &str, parameters: &Variant, invocation: DBusMethodInvocation) {
+        let string_arg = |index: usize| Self::tuple_child(parameters, index).and_then(|v| v.get::<String>());
+        let bool_arg = |index: usize| Self::tuple_child(parameters, index).and_then(|v| v.get::<bool>());
+
+        match method {
+            "Sync" => Util::send(sender, Action::Sync),
+            "SetOfflineMode" => match bool_arg(0) {
+                Some(offline) => Util::send(sender, Action::SetOfflineMode(offline)),
+                None => return invocation.return_dbus_error(INVALID_ARGS_ERROR, "Expected (b)"),
+            },
+            "AddFeed" => {
+                let url = match string_arg(0).map(|url| Url::parse(&url)) {
+                    Some(Ok(url)) => url,
+                    _ => return invocation.return_dbus_error(INVALID_ARGS_ERROR, "Invalid feed url"),
+                };
+                let title = string_arg(1).filter(|title| !title.is_empty());
+                let category = string_arg(2)
+                    .filter(|category_id| !category_id.is_empty())
+                    .map(|category_id| AddCategory::Existing(CategoryID::new(&category_id)));
+                Util::send(sender, Action::AddFeed((url, title, category)));
+            }
+            "ShowArticle" => match string_arg(0) {
+                Some(article_id) => {
+                    Util::send(sender, Action::ShowMainWindow);
+                    Util::send(sender, Action::ShowArticle(ArticleID::new(&article_id)));
+                }
+                None => return invocation.return_dbus_error(INVALID_ARGS_ERROR, "Expected (s)"),
+            },
+            "MarkArticleRead" => match (string_arg(0), bool_arg(1)) {
+                (Some(article_id), Some(read)) => {
+                    let update = ReadUpdate {
+                        article_id: ArticleID::new(&article_id),
+                        read: if read { Read::Read } else { Read::Unread },
+                    };
+                    Util::send(sender, Action::MarkArticleRead(update));
+                }
+                _ => return invocation.return_dbus_error(INVALID_ARGS_ERROR, "Expected (sb)"),
+            },
+            "Search" => match string_arg(0) {
+                Some(search_term) => Util::send(sender, Action::SearchTerm(search_term)),
+                None => return invocation.return_dbus_error(INVALID_ARGS_ERROR, "Expected (s)"),
+            },
+            _ => return invocation.return_dbus_error(UNKNOWN_METHOD_ERROR, method),
+        }
+
+        invocation.return_value(None);
+    }
+
+    fn get_property(state: &Arc<RwLock<DBusState>>, property: &str) -> Variant {
+        let state = state.read();
+        match property {
+            "UnreadCount" => state.unread_count.to_variant(),
+            "Syncing" => state.syncing.to_variant(),
+            "Offline" => state.offline.to_variant(),
+            _ => {
+                warn!("Unknown D-Bus property requested: {}", property);
+                false.to_variant()
+            }
+        }
+    }
+
+    fn emit_property_changed(&self, property: &str, value: Variant) {
+        // org.freedesktop.DBus.Properties.PropertiesChanged (sa{sv}as)
+        let changed = Self::dict_entry(&property.to_variant(), &Self::boxed(&value));
+        let changed = Self::array(VariantTy::new("{sv}").expect("valid type string"), &[changed]);
+        let invalidated = Self::array(VariantTy::new("s").expect("valid type string"), &[]);
+        let parameters = Self::tuple(&[DBUS_INTERFACE.to_variant(), changed, invalidated]);
+
+        if let Err(error) = self.connection.emit_signal(
+            None,
+            &self.object_path,
+            PROPERTIES_INTERFACE,
+            "PropertiesChanged",
+            Some(&parameters),
+        ) {
+            warn!("Failed to emit D-Bus property change for '{}': {}", property, error);
+        }
+    }
+
+    // glib 0.10 has no tuple/container support for `Variant`, so these helpers fall back to glib-sys
+
+    fn tuple_child(tuple: &Variant, index: usize) -> Option<Variant> {
+        unsafe {
+            let ptr: *mut glib_sys::GVariant = tuple.to_glib_none().0;
+            if glib_sys::g_variant_is_container(ptr) == glib_sys::GFALSE || glib_sys::g_variant_n_children(ptr) <= index
+            {
+                return None;
+            }
+            Some(from_glib_full(glib_sys::g_variant_get_child_value(ptr, index)))
+        }
+    }
+
+    fn tuple(children: &[Variant]) -> Variant {
+        let children: Vec<*mut glib_sys::GVariant> = children.iter().map(|c| c.to_glib_none().0).collect();
+        unsafe { from_glib_none(glib_sys::g_variant_new_tuple(children.as_ptr(), children.len())) }
+    }
+
+    fn array(child_type: &VariantTy, children: &[Variant]) -> Variant {
+        let children: Vec<*mut glib_sys::GVariant> = children.iter().map(|c| c.to_glib_none().0).collect();
+        unsafe {
+            from_glib_none(glib_sys::g_variant_new_array(
+                child_type.to_glib_none().0,
+                children.as_ptr(),
+                children.len(),
+            ))
+        }
+    }
+
+    fn dict_entry(key: &Variant, value: &Variant) -> Variant {
+        unsafe {
+            from_glib_none(glib_sys::g_variant_new_dict_entry(
+                key.to_glib_none().0,
+                value.to_glib_none().0,
+            ))
+        }
+    }
+
+    fn boxed(value: &Variant) -> Variant {
+        unsafe { from_glib_none(glib_sys::g_variant_new_variant(value.to_glib_none().0)) }
+    }
+}
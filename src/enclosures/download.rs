@@ -0,0 +1,141 @@
+use super::error::{EnclosureError, EnclosureErrorKind};
+use super::{Enclosure, EnclosureKind};
+use crate::app::DATA_DIR;
+use crate::util::FileUtil;
+use failure::ResultExt;
+use log::warn;
+use reqwest::{Client, Response};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+
+const DOWNLOAD_DIR: &str = "enclosures";
+// scheme the article view serves downloaded enclosures with, see `EnclosureDownloads::resolve_uri_path`
+pub const URI_SCHEME: &str = "newsflash-enclosure";
+
+// Files are stored in a directory per article named after the hashed article id.
+// The file name is the hashed url, keeping the extension so players recognize the format.
+pub struct EnclosureDownloads {
+    path: PathBuf,
+}
+
+impl Default for EnclosureDownloads {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EnclosureDownloads {
+    pub fn new() -> Self {
+        EnclosureDownloads {
+            path: DATA_DIR.join(DOWNLOAD_DIR),
+        }
+    }
+
+    fn relative_path(enclosure: &Enclosure) -> PathBuf {
+        let url = enclosure.url.get();
+        let mut file_name = FileUtil::hashed_file_name(url.as_str());
+        let extension = url
+            .path()
+            .rsplit('/')
+            .next()
+            .and_then(|name| name.rfind('.').map(|index| &name[index + 1..]))
+            .filter(|extension| {
+                !extension.is_empty() && extension.len() <= 5 && extension.chars().all(|c| c.is_ascii_alphanumeric())
+            });
+        if let Some(extension) = extension {
+            file_name.push('.');
+            file_name.push_str(extension);
+        }
+        Path::new(&FileUtil::hashed_file_name(&enclosure.article_id.to_string())).join(file_name)
+    }
+
+    pub fn local_path(&self, enclosure: &Enclosure) -> Option<PathBuf> {
+        let path = self.path.join(Self::relative_path(enclosure));
+        if path.exists() {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    pub fn local_uri(&self, enclosure: &Enclosure) -> Option<String> {
+        self.local_path(enclosure)?;
+        let relative_path = Self::relative_path(enclosure);
+        Some(format!("{}:///{}", URI_SCHEME, relative_path.to_string_lossy()))
+    }
+
+    // only downloaded files are served, so paths leaving the download directory are rejected
+    pub fn resolve_uri_path(&self, uri_path: &str) -> Option<PathBuf> {
+        let relative_path = Path::new(uri_path.trim_start_matches('/'));
+        if relative_path
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_)))
+        {
+            return None;
+        }
+        let path = self.path.join(relative_path);
+        if path.is_file() {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    pub async fn download(&self, enclosure: &Enclosure, client: &Client) -> Result<PathBuf, EnclosureError> {
+        let path = self.path.join(Self::relative_path(enclosure));
+        if path.exists() {
+            return Ok(path);
+        }
+
+        let response = client
+            .get(enclosure.url.get().as_str())
+            .send()
+            .await
+            .context(EnclosureErrorKind::Download)?
+            .error_for_status()
+            .context(EnclosureErrorKind::Download)?;
+
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory).context(EnclosureErrorKind::CreateDirectory)?;
+        }
+        // don't leave a truncated file behind that looks like a finished download
+        let tmp_path = path.with_extension("part");
+        if let Err(error) = Self::write_response(response, &tmp_path).await {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(error);
+        }
+        fs::rename(&tmp_path, &path).context(EnclosureErrorKind::Write)?;
+        Ok(path)
+    }
+
+    // audio, video and pdf enclosures of an article, stops at the first failed download
+    pub async fn download_all(&self, enclosures: &[Enclosure], client: &Client) -> Result<(), EnclosureError> {
+        for enclosure in enclosures
+            .iter()
+            .filter(|enclosure| EnclosureKind::detect(enclosure) != EnclosureKind::Other)
+        {
+            if let Err(error) = self.download(enclosure, client).await {
+                warn!("Failed to download '{}'", enclosure.url.get());
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    // enclosures can be too large to keep in memory
+    async fn write_response(mut response: Response, path: &Path) -> Result<(), EnclosureError> {
+        let mut file = File::create(path).context(EnclosureErrorKind::Write)?;
+        while let Some(chunk) = response.chunk().await.context(EnclosureErrorKind::Download)? {
+            file.write_all(&chunk).context(EnclosureErrorKind::Write)?;
+        }
+        Ok(())
+    }
+
+    pub fn clear(&self) -> Result<(), EnclosureError> {
+        if self.path.exists() {
+            fs::remove_dir_all(&self.path).context(EnclosureErrorKind::Write)?;
+        }
+        Ok(())
+    }
+}
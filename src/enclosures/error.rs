@@ -0,0 +1,70 @@
+use failure::{Backtrace, Context, Error, Fail};
+use std::fmt;
+
+#[derive(Debug)]
+pub struct EnclosureError {
+    inner: Context<EnclosureErrorKind>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Fail)]
+pub enum EnclosureErrorKind {
+    #[fail(display = "Failed to create directory")]
+    CreateDirectory,
+    #[fail(display = "Failed to read file")]
+    Read,
+    #[fail(display = "Failed to write file")]
+    Write,
+    #[fail(display = "File is not valid json")]
+    InvalidJsonContent,
+    #[fail(display = "Failed to load data from the database")]
+    DataBase,
+    #[fail(display = "Failed to download enclosure")]
+    Download,
+    #[fail(display = "Unknown Error")]
+    Unknown,
+}
+
+impl Fail for EnclosureError {
+    fn cause(&self) -> Option<&dyn Fail> {
+        self.inner.cause()
+    }
+
+    fn backtrace(&self) -> Option<&Backtrace> {
+        self.inner.backtrace()
+    }
+}
+
+impl fmt::Display for EnclosureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, f)
+    }
+}
+
+impl EnclosureError {
+    #[allow(dead_code)]
+    pub fn kind(&self) -> EnclosureErrorKind {
+        *self.inner.get_context()
+    }
+}
+
+impl From<EnclosureErrorKind> for EnclosureError {
+    fn from(kind: EnclosureErrorKind) -> EnclosureError {
+        EnclosureError {
+            inner: Context::new(kind),
+        }
+    }
+}
+
+impl From<Context<EnclosureErrorKind>> for EnclosureError {
+    fn from(inner: Context<EnclosureErrorKind>) -> EnclosureError {
+        EnclosureError { inner }
+    }
+}
+
+impl From<Error> for EnclosureError {
+    fn from(_: Error) -> EnclosureError {
+        EnclosureError {
+            inner: Context::new(EnclosureErrorKind::Unknown),
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use super::error::{EnclosureError, EnclosureErrorKind};
+use super::{Enclosure, EnclosureKind};
+use crate::app::DATA_DIR;
+use crate::util::{FileUtil, Util};
+use failure::ResultExt;
+use news_flash::models::{Article, ArticleFilter, ArticleID};
+use news_flash::NewsFlash;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+const INDEX_NAME: &str = "media_articles.json";
+// the content of this many articles is loaded at once while scanning
+const SCAN_CHUNK_SIZE: usize = 200;
+
+// Articles with an audio or video enclosure, so `has:media` doesn't look up the enclosures of every article.
+// Updated with the articles of every sync, the first sync looks at all articles.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MediaArticles {
+    article_ids: HashSet<String>,
+    complete: bool,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    path: PathBuf,
+}
+
+struct MediaScan {
+    // indexed articles still in the database, `None` if all articles were scanned
+    existing: Option<HashSet<String>>,
+    media: Vec<String>,
+}
+
+impl Default for MediaArticles {
+    fn default() -> Self {
+        MediaArticles {
+            article_ids: HashSet::new(),
+            complete: false,
+            path: DATA_DIR.join(INDEX_NAME),
+        }
+    }
+}
+
+impl MediaArticles {
+    pub fn open() -> Result<Self, EnclosureError> {
+        let path = DATA_DIR.join(INDEX_NAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let data = fs::read_to_string(&path).context(EnclosureErrorKind::Read)?;
+        let mut media: Self = serde_json::from_str(&data).context(EnclosureErrorKind::InvalidJsonContent)?;
+        media.path = path;
+        Ok(media)
+    }
+
+    fn write(&self) -> Result<(), EnclosureError> {
+        FileUtil::write_json_atomic(self, &self.path).context(EnclosureErrorKind::Write)?;
+        Ok(())
+    }
+
+    pub fn contains(&self, article_id: &ArticleID) -> bool {
+        self.article_ids.contains(&article_id.to_string())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    // Look at the articles a sync added, at all articles until the index was complete once.
+    // The index isn't locked while the database is queried.
+    pub fn refresh(
+        media_articles: &RwLock<Self>,
+        news_flash: &NewsFlash,
+        new_articles: Option<&[Article]>,
+    ) -> Result<(), EnclosureError> {
+        let scan = match new_articles {
+            Some(new_articles) if media_articles.read().complete => {
+                let indexed: Vec<ArticleID> = media_articles
+                    .read()
+                    .article_ids
+                    .iter()
+                    .map(|article_id| ArticleID::new(article_id))
+                    .collect();
+                let new_articles: Vec<ArticleID> =
+                    new_articles.iter().map(|article| article.article_id.clone()).collect();
+                MediaScan {
+                    existing: Some(Self::existing(news_flash, &indexed)?),
+                    media: Self::scan(news_flash, &new_articles)?,
+                }
+            }
+            _ => {
+                let all_articles: Vec<ArticleID> = Util::load_all_articles(news_flash, Self::article_filter(None))
+                    .context(EnclosureErrorKind::DataBase)?
+                    .into_iter()
+                    .map(|article| article.article_id)
+                    .collect();
+                MediaScan {
+                    existing: None,
+                    media: Self::scan(news_flash, &all_articles)?,
+                }
+            }
+        };
+        media_articles.write().update(scan)
+    }
+
+    // the ids of `article_ids` still in the database
+    fn existing(news_flash: &NewsFlash, article_ids: &[ArticleID]) -> Result<HashSet<String>, EnclosureError> {
+        let mut existing = HashSet::new();
+        for chunk in article_ids.chunks(SCAN_CHUNK_SIZE) {
+            let articles = news_flash
+                .get_articles(Self::article_filter(Some(chunk)))
+                .context(EnclosureErrorKind::DataBase)?;
+            existing.extend(articles.into_iter().map(|article| article.article_id.to_string()));
+        }
+        Ok(existing)
+    }
+
+    // the ids of the articles in `article_ids` with audio or video
+    fn scan(news_flash: &NewsFlash, article_ids: &[ArticleID]) -> Result<Vec<String>, EnclosureError> {
+        let mut media = Vec::new();
+        for chunk in article_ids.chunks(SCAN_CHUNK_SIZE) {
+            let articles = news_flash
+                .get_fat_articles(Self::article_filter(Some(chunk)))
+                .context(EnclosureErrorKind::DataBase)?;
+            media.extend(
+                articles
+                    .iter()
+                    .filter(|article| {
+                        Enclosure::from_article(article)
+                            .iter()
+                            .any(|enclosure| EnclosureKind::detect(enclosure).is_media())
+                    })
+                    .map(|article| article.article_id.to_string()),
+            );
+        }
+        Ok(media)
+    }
+
+    fn article_filter(ids: Option<&[ArticleID]>) -> ArticleFilter<'_> {
+        ArticleFilter {
+            limit: ids.map(|ids| ids.len() as i64),
+            offset: None,
+            order: None,
+            unread: None,
+            marked: None,
+            feed: None,
+            feed_blacklist: None,
+            category: None,
+            category_blacklist: None,
+            tag: None,
+            ids,
+            newer_than: None,
+            older_than: None,
+            search_term: None,
+        }
+    }
+
+    fn update(&mut self, scan: MediaScan) -> Result<(), EnclosureError> {
+        match scan.existing {
+            Some(existing) => self.article_ids.retain(|article_id| existing.contains(article_id)),
+            None => self.article_ids.clear(),
+        }
+        self.article_ids.extend(scan.media);
+        self.complete = true;
+        self.write()
+    }
+
+    pub fn clear(&mut self) -> Result<(), EnclosureError> {
+        self.article_ids.clear();
+        self.complete = false;
+        self.write()
+    }
+}
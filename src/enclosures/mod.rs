@@ -0,0 +1,235 @@
+mod download;
+mod error;
+mod media;
+mod playback;
+
+pub use self::download::{EnclosureDownloads, URI_SCHEME};
+pub use self::media::MediaArticles;
+pub use self::playback::PlaybackPositions;
+use crate::i18n::i18n;
+use libxml::parser::Parser;
+use libxml::tree::{Node, NodeType};
+use news_flash::models::{ArticleID, FatArticle, Url};
+use std::collections::HashSet;
+
+// news_flash keeps the enclosures of feed entries to itself,
+// so audio, video and documents are picked up from the article content instead
+#[derive(Clone, Debug, PartialEq)]
+pub struct Enclosure {
+    pub article_id: ArticleID,
+    pub url: Url,
+    pub mime_type: Option<String>,
+    pub title: Option<String>,
+}
+
+impl Enclosure {
+    pub fn from_article(article: &FatArticle) -> Vec<Self> {
+        let html = match &article.html {
+            Some(html) if !html.trim().is_empty() => html,
+            _ => return Vec::new(),
+        };
+        let document = match Parser::default_html().parse_string(html) {
+            Ok(document) => document,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut enclosures = Vec::new();
+        if let Some(root) = document.get_root_element() {
+            let base = article.url.as_ref().map(|url| url.get());
+            let mut seen = HashSet::new();
+            Self::collect(&root, article, base.as_ref(), &mut seen, &mut enclosures);
+        }
+        enclosures
+    }
+
+    fn collect(
+        node: &Node,
+        article: &FatArticle,
+        base: Option<&url::Url>,
+        seen: &mut HashSet<String>,
+        enclosures: &mut Vec<Self>,
+    ) {
+        for child in node.get_child_nodes() {
+            if child.get_type() != Some(NodeType::ElementNode) {
+                continue;
+            }
+
+            let link = match child.get_name().to_lowercase().as_str() {
+                "audio" | "video" | "source" => child.get_property("src"),
+                "a" => child.get_property("href"),
+                _ => None,
+            };
+            let url = link.and_then(|link| match base {
+                Some(base) => base.join(&link).ok(),
+                None => url::Url::parse(&link).ok(),
+            });
+            if let Some(url) = url {
+                let mime_type = child.get_property("type").filter(|mime_type| !mime_type.is_empty());
+                let kind = EnclosureKind::from_parts(mime_type.as_deref(), url.path());
+                if kind != EnclosureKind::Other && seen.insert(url.to_string()) {
+                    enclosures.push(Enclosure {
+                        article_id: article.article_id.clone(),
+                        url: Url::new(url),
+                        mime_type,
+                        title: child.get_property("title").filter(|title| !title.is_empty()),
+                    });
+                }
+            }
+
+            Self::collect(&child, article, base, seen, enclosures);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EnclosureKind {
+    Audio,
+    Video,
+    Pdf,
+    Other,
+}
+
+impl EnclosureKind {
+    // feeds often leave out the mime type or use a generic one, so fall back to the file extension
+    pub fn detect(enclosure: &Enclosure) -> Self {
+        Self::from_parts(enclosure.mime_type.as_deref(), enclosure.url.get().path())
+    }
+
+    fn from_parts(mime_type: Option<&str>, path: &str) -> Self {
+        if let Some(mime_type) = mime_type {
+            let mime_type = mime_type.to_lowercase();
+            if mime_type.starts_with("audio/") {
+                return EnclosureKind::Audio;
+            } else if mime_type.starts_with("video/") {
+                return EnclosureKind::Video;
+            } else if mime_type == "application/pdf" {
+                return EnclosureKind::Pdf;
+            }
+        }
+
+        let extension = path.rsplit('.').next().unwrap_or_default().to_lowercase();
+        match extension.as_str() {
+            "mp3" | "m4a" | "aac" | "ogg" | "oga" | "opus" | "flac" | "wav" => EnclosureKind::Audio,
+            "mp4" | "m4v" | "webm" | "ogv" | "mov" | "mkv" => EnclosureKind::Video,
+            "pdf" => EnclosureKind::Pdf,
+            _ => EnclosureKind::Other,
+        }
+    }
+
+    pub fn is_media(self) -> bool {
+        self == EnclosureKind::Audio || self == EnclosureKind::Video
+    }
+}
+
+// with `downloads` the players use the downloaded copies where available,
+// only the first audio or video enclosure resumes from `position`
+pub fn enclosures_html(
+    enclosures: &[Enclosure],
+    downloads: Option<&EnclosureDownloads>,
+    position: Option<f64>,
+) -> String {
+    let mut items = String::new();
+    let mut resume = position;
+    for enclosure in enclosures {
+        let kind = EnclosureKind::detect(enclosure);
+        let remote_url = enclosure.url.get().to_string();
+        let url = downloads
+            .and_then(|downloads| downloads.local_uri(enclosure))
+            .unwrap_or_else(|| remote_url.clone());
+        let title = enclosure
+            .title
+            .clone()
+            .unwrap_or_else(|| remote_url.rsplit('/').next().unwrap_or_default().to_owned());
+
+        let player = match kind {
+            EnclosureKind::Audio | EnclosureKind::Video => {
+                let tag = if kind == EnclosureKind::Audio { "audio" } else { "video" };
+                let fragment = match resume.take() {
+                    Some(position) => format!("#t={:.0}", position),
+                    None => String::new(),
+                };
+                format!(
+                    "<{tag} controls preload=\"metadata\" src=\"{}{}\"></{tag}>",
+                    escape(&url),
+                    fragment,
+                    tag = tag
+                )
+            }
+            EnclosureKind::Pdf | EnclosureKind::Other => String::new(),
+        };
+        let label = match kind {
+            EnclosureKind::Pdf => i18n("Open PDF"),
+            _ => i18n("Open"),
+        };
+
+        items.push_str(&format!(
+            "<div class=\"enclosure\"><div class=\"enclosure-title\">{}</div>{}<a href=\"{}\">{}</a></div>\n",
+            escape(&title),
+            player,
+            escape(&remote_url),
+            escape(&label)
+        ));
+    }
+
+    if items.is_empty() {
+        return String::new();
+    }
+    format!("<section class=\"enclosures\">\n{}</section>", items)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Enclosure, EnclosureKind};
+    use chrono::NaiveDate;
+    use news_flash::models::{ArticleID, FatArticle, FeedID, Marked, Read, Url};
+
+    #[test]
+    fn detect_kind() {
+        assert_eq!(
+            EnclosureKind::from_parts(Some("audio/mpeg"), "/ep1"),
+            EnclosureKind::Audio
+        );
+        assert_eq!(
+            EnclosureKind::from_parts(Some("application/octet-stream"), "/ep1.MP4"),
+            EnclosureKind::Video
+        );
+        assert_eq!(EnclosureKind::from_parts(None, "/paper.pdf"), EnclosureKind::Pdf);
+        assert_eq!(EnclosureKind::from_parts(None, "/image.png"), EnclosureKind::Other);
+    }
+
+    #[test]
+    fn enclosures_from_content() {
+        let article = FatArticle {
+            article_id: ArticleID::new("article"),
+            title: None,
+            author: None,
+            feed_id: FeedID::new("feed"),
+            direction: None,
+            date: NaiveDate::from_ymd(2020, 1, 1).and_hms(0, 0, 0),
+            marked: Marked::Unmarked,
+            unread: Read::Unread,
+            url: Some(Url::parse("https://example.com/posts/1").unwrap()),
+            summary: None,
+            html: Some(
+                "<p><audio controls><source src=\"/ep1.mp3\" type=\"audio/mpeg\"></audio>\
+                 <a href=\"https://example.com/ep1.mp3\">download</a> <a href=\"/about\">about</a></p>"
+                    .to_owned(),
+            ),
+            scraped_content: None,
+            plain_text: None,
+        };
+
+        let enclosures = Enclosure::from_article(&article);
+        assert_eq!(enclosures.len(), 1);
+        assert_eq!(enclosures[0].url.get().as_str(), "https://example.com/ep1.mp3");
+        assert_eq!(enclosures[0].mime_type.as_deref(), Some("audio/mpeg"));
+        assert_eq!(EnclosureKind::detect(&enclosures[0]), EnclosureKind::Audio);
+    }
+}
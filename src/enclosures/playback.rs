@@ -0,0 +1,66 @@
+use super::error::{EnclosureError, EnclosureErrorKind};
+use crate::app::DATA_DIR;
+use crate::util::FileUtil;
+use failure::ResultExt;
+use news_flash::models::ArticleID;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+const POSITIONS_NAME: &str = "playback_positions.json";
+// positions this close to the start are not worth remembering
+const MIN_POSITION: f64 = 5.0;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlaybackPositions {
+    positions: HashMap<String, f64>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    path: PathBuf,
+}
+
+impl Default for PlaybackPositions {
+    fn default() -> Self {
+        PlaybackPositions {
+            positions: HashMap::new(),
+            path: DATA_DIR.join(POSITIONS_NAME),
+        }
+    }
+}
+
+impl PlaybackPositions {
+    pub fn open() -> Result<Self, EnclosureError> {
+        let path = DATA_DIR.join(POSITIONS_NAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let data = fs::read_to_string(&path).context(EnclosureErrorKind::Read)?;
+        let mut positions: Self = serde_json::from_str(&data).context(EnclosureErrorKind::InvalidJsonContent)?;
+        positions.path = path;
+        Ok(positions)
+    }
+
+    fn write(&self) -> Result<(), EnclosureError> {
+        FileUtil::write_json_atomic(self, &self.path).context(EnclosureErrorKind::Write)?;
+        Ok(())
+    }
+
+    pub fn get(&self, article_id: &ArticleID) -> Option<f64> {
+        self.positions.get(&article_id.to_string()).copied()
+    }
+
+    pub fn set(&mut self, article_id: &ArticleID, position: f64) -> Result<(), EnclosureError> {
+        let key = article_id.to_string();
+        let changed = if position < MIN_POSITION {
+            self.positions.remove(&key).is_some()
+        } else {
+            self.positions.insert(key, position) != Some(position)
+        };
+        if changed {
+            self.write()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,517 @@
+use super::error::{ExportError, ExportErrorKind};
+use libxml::parser::Parser;
+use libxml::tree::{Node, NodeType};
+use std::mem;
+
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "head", "noscript", "iframe", "object", "embed", "form", "button", "input", "select", "svg",
+    "video", "audio", "template",
+];
+
+// kept when converting to xhtml, everything else is unwrapped
+const XHTML_ELEMENTS: &[&str] = &[
+    "p",
+    "div",
+    "span",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "a",
+    "img",
+    "ul",
+    "ol",
+    "li",
+    "blockquote",
+    "pre",
+    "code",
+    "em",
+    "i",
+    "strong",
+    "b",
+    "u",
+    "s",
+    "sub",
+    "sup",
+    "br",
+    "hr",
+    "figure",
+    "figcaption",
+    "table",
+    "thead",
+    "tbody",
+    "tfoot",
+    "tr",
+    "td",
+    "th",
+    "caption",
+    "dl",
+    "dt",
+    "dd",
+    "small",
+    "q",
+    "cite",
+    "abbr",
+    "mark",
+];
+
+const BLOCK_ELEMENTS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "header",
+    "footer",
+    "aside",
+    "nav",
+    "figure",
+    "figcaption",
+    "table",
+    "caption",
+    "dl",
+    "dt",
+    "dd",
+    "address",
+    "details",
+    "summary",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Target {
+    Markdown,
+    PlainText,
+    Xhtml,
+}
+
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub file_name: String,
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+// Images embedded as data urls (see `NewsFlash::article_download_images`) are collected and
+// referenced as `<image_dir>/image-<n>.<ext>`, so the caller can write them next to the document.
+pub struct HtmlConverter {
+    target: Target,
+    image_dir: String,
+    images: Vec<Image>,
+    preformatted: bool,
+}
+
+impl HtmlConverter {
+    pub fn new(target: Target, image_dir: &str) -> Self {
+        HtmlConverter {
+            target,
+            image_dir: image_dir.to_owned(),
+            images: Vec::new(),
+            preformatted: false,
+        }
+    }
+
+    pub fn convert(&mut self, html: &str) -> Result<String, ExportError> {
+        if html.trim().is_empty() {
+            return Ok(String::new());
+        }
+
+        let document = Parser::default_html()
+            .parse_string(html)
+            .map_err(|_| ExportErrorKind::Parse)?;
+        let root = document.get_root_element().ok_or(ExportErrorKind::Parse)?;
+
+        let mut out = String::new();
+        self.walk_children(&root, &mut out);
+        Ok(match self.target {
+            Target::Xhtml => out,
+            Target::Markdown | Target::PlainText => Self::normalize(&out),
+        })
+    }
+
+    pub fn take_images(&mut self) -> Vec<Image> {
+        mem::take(&mut self.images)
+    }
+
+    fn walk_children(&mut self, node: &Node, out: &mut String) {
+        for child in node.get_child_nodes() {
+            self.walk(&child, out);
+        }
+    }
+
+    fn walk(&mut self, node: &Node, out: &mut String) {
+        match node.get_type() {
+            Some(NodeType::TextNode) | Some(NodeType::CDataSectionNode) => self.text(&node.get_content(), out),
+            Some(NodeType::ElementNode) => {
+                let name = node.get_name().to_lowercase();
+                if SKIPPED_ELEMENTS.contains(&name.as_str()) {
+                    return;
+                }
+                match self.target {
+                    Target::Xhtml => self.xhtml_element(node, &name, out),
+                    Target::Markdown | Target::PlainText => self.text_element(node, &name, out),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn inline(&mut self, node: &Node) -> String {
+        let mut out = String::new();
+        self.walk_children(node, &mut out);
+        out
+    }
+
+    fn text(&mut self, text: &str, out: &mut String) {
+        if self.preformatted {
+            match self.target {
+                Target::Xhtml => out.push_str(&Self::escape_xml(text)),
+                Target::Markdown | Target::PlainText => out.push_str(text),
+            }
+            return;
+        }
+
+        let mut collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.starts_with(char::is_whitespace) {
+            collapsed.insert(0, ' ');
+        }
+        if text.ends_with(char::is_whitespace) && !collapsed.ends_with(' ') {
+            collapsed.push(' ');
+        }
+
+        match self.target {
+            Target::Xhtml => out.push_str(&Self::escape_xml(&collapsed)),
+            Target::Markdown | Target::PlainText => {
+                // no whitespace at the start of lines
+                if out.is_empty() || out.ends_with(char::is_whitespace) {
+                    collapsed = collapsed.trim_start().to_owned();
+                }
+                if self.target == Target::Markdown {
+                    collapsed = Self::escape_markdown(&collapsed);
+                }
+                out.push_str(&collapsed);
+            }
+        }
+    }
+
+    fn text_element(&mut self, node: &Node, name: &str, out: &mut String) {
+        let markdown = self.target == Target::Markdown;
+        match name {
+            "br" => out.push_str(if markdown { "\\\n" } else { "\n" }),
+            "hr" => {
+                Self::block(out);
+                out.push_str("---");
+                Self::block(out);
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                let heading = self.inline(node);
+                Self::block(out);
+                if markdown {
+                    out.push_str(&"#".repeat(level));
+                    out.push(' ');
+                }
+                out.push_str(heading.trim());
+                Self::block(out);
+            }
+            "a" => {
+                let content = self.inline(node);
+                let content = content.trim();
+                match node.get_property("href") {
+                    Some(href) if !content.is_empty() && markdown => {
+                        out.push_str(&format!("[{}]({})", content, href));
+                    }
+                    Some(href) if !content.is_empty() && content != href => {
+                        out.push_str(&format!("{} ({})", content, href));
+                    }
+                    _ => out.push_str(content),
+                }
+            }
+            "img" => {
+                let alt = node.get_property("alt").unwrap_or_default();
+                if let Some(src) = node.get_property("src") {
+                    if markdown {
+                        let src = self.image_source(&src);
+                        out.push_str(&format!("![{}]({})", alt.trim(), src));
+                    } else if !alt.trim().is_empty() {
+                        out.push_str(&format!("[Image: {}]", alt.trim()));
+                    }
+                }
+            }
+            "strong" | "b" => self.wrap(node, if markdown { "**" } else { "" }, out),
+            "em" | "i" => self.wrap(node, if markdown { "*" } else { "" }, out),
+            "code" if !self.preformatted => {
+                self.preformatted = true;
+                self.wrap(node, if markdown { "`" } else { "" }, out);
+                self.preformatted = false;
+            }
+            "pre" => {
+                let was_preformatted = mem::replace(&mut self.preformatted, true);
+                let code = self.inline(node);
+                self.preformatted = was_preformatted;
+
+                Self::block(out);
+                if markdown {
+                    out.push_str("```\n");
+                    out.push_str(code.trim_end());
+                    out.push_str("\n```");
+                } else {
+                    for line in code.trim_end().lines() {
+                        out.push_str("    ");
+                        out.push_str(line);
+                        out.push('\n');
+                    }
+                }
+                Self::block(out);
+            }
+            "blockquote" => {
+                let quote = Self::normalize(&self.inline(node));
+                Self::block(out);
+                for line in quote.lines() {
+                    out.push_str(if line.is_empty() { ">" } else { "> " });
+                    out.push_str(line);
+                    out.push('\n');
+                }
+                Self::block(out);
+            }
+            "ul" | "ol" => {
+                Self::block(out);
+                let mut index = node
+                    .get_property("start")
+                    .and_then(|start| start.parse::<usize>().ok())
+                    .unwrap_or(1);
+                for child in node.get_child_nodes() {
+                    if child.get_type() != Some(NodeType::ElementNode) {
+                        continue;
+                    }
+                    let item = Self::normalize(&self.inline(&child));
+                    let marker = if name == "ol" {
+                        format!("{}. ", index)
+                    } else {
+                        "- ".to_owned()
+                    };
+                    let indent = " ".repeat(marker.len());
+                    for (line_index, line) in item.lines().enumerate() {
+                        if line_index == 0 {
+                            out.push_str(&marker);
+                        } else if !line.is_empty() {
+                            out.push_str(&indent);
+                        }
+                        out.push_str(line);
+                        out.push('\n');
+                    }
+                    index += 1;
+                }
+                Self::block(out);
+            }
+            "li" | "tr" => {
+                Self::line(out);
+                self.walk_children(node, out);
+                Self::line(out);
+            }
+            "td" | "th" => {
+                self.walk_children(node, out);
+                out.push(' ');
+            }
+            _ if BLOCK_ELEMENTS.contains(&name) => {
+                Self::block(out);
+                self.walk_children(node, out);
+                Self::block(out);
+            }
+            _ => self.walk_children(node, out),
+        }
+    }
+
+    fn wrap(&mut self, node: &Node, marker: &str, out: &mut String) {
+        let content = self.inline(node);
+        if content.trim().is_empty() {
+            out.push_str(&content);
+            return;
+        }
+        out.push_str(marker);
+        out.push_str(content.trim());
+        out.push_str(marker);
+        if content.ends_with(' ') {
+            out.push(' ');
+        }
+    }
+
+    fn xhtml_element(&mut self, node: &Node, name: &str, out: &mut String) {
+        if !XHTML_ELEMENTS.contains(&name) {
+            self.walk_children(node, out);
+            return;
+        }
+
+        out.push('<');
+        out.push_str(name);
+        let attributes: &[&str] = match name {
+            "a" => &["href", "title"],
+            "img" => &["src", "alt", "title"],
+            "td" | "th" => &["colspan", "rowspan"],
+            "ol" => &["start"],
+            _ => &[],
+        };
+        for attribute in attributes {
+            if let Some(value) = node.get_property(attribute) {
+                let value = if name == "img" && *attribute == "src" {
+                    self.image_source(&value)
+                } else {
+                    value
+                };
+                out.push_str(&format!(" {}=\"{}\"", attribute, Self::escape_xml(&value)));
+            }
+        }
+        if name == "img" && node.get_property("alt").is_none() {
+            out.push_str(" alt=\"\"");
+        }
+
+        if name == "br" || name == "hr" || name == "img" {
+            out.push_str("/>");
+            return;
+        }
+
+        out.push('>');
+        let was_preformatted = self.preformatted;
+        if name == "pre" {
+            self.preformatted = true;
+        }
+        self.walk_children(node, out);
+        self.preformatted = was_preformatted;
+        out.push_str(&format!("</{}>", name));
+    }
+
+    fn image_source(&mut self, src: &str) -> String {
+        match Self::decode_data_url(src) {
+            Some((mime_type, data)) => {
+                let file_name = format!("image-{}.{}", self.images.len() + 1, Self::image_extension(&mime_type));
+                let path = format!("{}/{}", self.image_dir, file_name);
+                self.images.push(Image {
+                    file_name,
+                    mime_type,
+                    data,
+                });
+                path
+            }
+            None => src.to_owned(),
+        }
+    }
+
+    fn decode_data_url(src: &str) -> Option<(String, Vec<u8>)> {
+        if !src.starts_with("data:") {
+            return None;
+        }
+        let mut split = src["data:".len()..].splitn(2, ',');
+        let header = split.next()?;
+        let payload = split.next()?;
+        if !header.ends_with(";base64") {
+            return None;
+        }
+        let mime_type = header.split(';').next()?.to_owned();
+        let payload: String = payload.chars().filter(|c| !c.is_whitespace()).collect();
+        let data = base64::decode(&payload).ok()?;
+        Some((mime_type, data))
+    }
+
+    pub fn image_extension(mime_type: &str) -> &'static str {
+        match mime_type {
+            "image/png" => "png",
+            "image/jpeg" | "image/jpg" => "jpg",
+            "image/gif" => "gif",
+            "image/webp" => "webp",
+            "image/svg+xml" => "svg",
+            _ => "bin",
+        }
+    }
+
+    fn block(out: &mut String) {
+        while out.ends_with(' ') {
+            out.pop();
+        }
+        if out.is_empty() || out.ends_with("\n\n") {
+            return;
+        }
+        out.push_str(if out.ends_with('\n') { "\n" } else { "\n\n" });
+    }
+
+    fn line(out: &mut String) {
+        while out.ends_with(' ') {
+            out.pop();
+        }
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+
+    fn normalize(text: &str) -> String {
+        let mut result = String::new();
+        let mut empty_lines = 0;
+        for line in text.lines() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                empty_lines += 1;
+                continue;
+            }
+            if !result.is_empty() {
+                result.push_str(if empty_lines > 0 { "\n\n" } else { "\n" });
+            }
+            empty_lines = 0;
+            result.push_str(line);
+        }
+        result
+    }
+
+    fn escape_markdown(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            if let '\\' | '*' | '_' | '`' | '[' | ']' = c {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
+    pub fn escape_xml(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HtmlConverter, Target};
+
+    #[test]
+    fn markdown_links_and_images() {
+        let html = r#"<p>Read <a href="https://example.com">the <b>full</b> story</a>.</p>
+            <p><img src="data:image/png;base64,iVBORw0KGgo=" alt="Chart"></p>
+            <ul><li>one</li><li>two</li></ul>"#;
+        let mut converter = HtmlConverter::new(Target::Markdown, "story_files");
+        let markdown = converter.convert(html).unwrap();
+        assert_eq!(
+            markdown,
+            "Read [the **full** story](https://example.com).\n\n![Chart](story_files/image-1.png)\n\n- one\n- two"
+        );
+
+        let images = converter.take_images();
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].file_name, "image-1.png");
+        assert_eq!(images[0].mime_type, "image/png");
+    }
+
+    #[test]
+    fn plain_text_and_xhtml() {
+        let html = r#"<h2>Title</h2><p>Some <a href="https://example.com">link</a><br>next&amp;line</p>"#;
+        let text = HtmlConverter::new(Target::PlainText, "images").convert(html).unwrap();
+        assert_eq!(text, "Title\n\nSome link (https://example.com)\nnext&line");
+
+        let xhtml = HtmlConverter::new(Target::Xhtml, "images").convert(html).unwrap();
+        assert_eq!(
+            xhtml,
+            r#"<h2>Title</h2><p>Some <a href="https://example.com">link</a><br/>next&amp;line</p>"#
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use super::convert::{HtmlConverter, Target};
+use super::epub::EpubBuilder;
+use super::error::{ExportError, ExportErrorKind};
+use super::ArticleExport;
+use crate::article_view::ArticleView;
+use crate::content_page::{ContentPage, HeaderSelection, SearchQuery};
+use crate::enclosures::MediaArticles;
+use crate::i18n::i18n;
+use crate::offline::OfflineCache;
+use crate::settings::{DigestFormat, DigestSchedule, Settings};
+use crate::sidebar::models::SidebarSelection;
+use crate::util::{DateUtil, RUNTIME_ERROR};
+use chrono::Local;
+use failure::ResultExt;
+use log::{info, warn};
+use news_flash::models::{ArticleFilter, ArticleID, FatArticle, Feed};
+use news_flash::NewsFlash;
+use parking_lot::RwLock;
+use reqwest::Client;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+pub const MAX_DIGEST_ARTICLES: i64 = 200;
+// subdirectory of html digests with the article pages, owned by the export
+const PAGES_DIR: &str = "articles";
+const INDEX_STYLE: &str = "body { font-family: sans-serif; max-width: 50em; margin: 2em auto; padding: 0 1em; }
+li { margin: 0.3em 0; }
+.meta { color: #666; font-size: 0.9em; }";
+
+pub struct DigestExport;
+
+pub struct Digest {
+    pub path: PathBuf,
+    // the selection has more than `MAX_DIGEST_ARTICLES` articles, only the first ones were written
+    pub truncated: bool,
+}
+
+impl DigestExport {
+    // Loads the articles of the digest selection and writes them. Returns `None` if there is nothing
+    // to write because the selection is gone or empty.
+    pub fn build(
+        news_flash: &NewsFlash,
+        settings: &Arc<RwLock<Settings>>,
+        media_articles: &RwLock<MediaArticles>,
+        digest: &DigestSchedule,
+        offline: bool,
+        client: &Client,
+    ) -> Result<Option<Digest>, ExportError> {
+        let filter = match ContentPage::selection_filter(
+            news_flash,
+            settings,
+            &digest.sidebar_selection,
+            &digest.header_selection,
+            None,
+            None,
+        )
+        .context(ExportErrorKind::LoadArticles)?
+        {
+            Some(filter) => filter,
+            None => {
+                warn!("Selection of digest doesn't exist anymore");
+                return Ok(None);
+            }
+        };
+        let filter = ArticleFilter {
+            // one more than fits to tell whether the selection was cut off
+            limit: Some(MAX_DIGEST_ARTICLES + 1),
+            order: Some(settings.read().get_article_list_order()),
+            ..filter
+        };
+        let not_hidden = ContentPage::not_hidden(news_flash, settings).context(ExportErrorKind::LoadArticles)?;
+        let (mut articles, _offset) = SearchQuery::load_page(news_flash, filter, media_articles, not_hidden)
+            .context(ExportErrorKind::LoadArticles)?;
+        if articles.is_empty() {
+            info!("No articles for digest");
+            return Ok(None);
+        }
+        let truncated = articles.len() as i64 > MAX_DIGEST_ARTICLES;
+        articles.truncate(MAX_DIGEST_ARTICLES as usize);
+
+        let mut runtime = Runtime::new().expect(RUNTIME_ERROR);
+        let cache = OfflineCache::new();
+        let digest_cache = OfflineCache::digest();
+        let articles: Vec<FatArticle> = articles
+            .iter()
+            .filter_map(|article| {
+                // images embedded before are used again, only new articles are downloaded
+                let cached = [&cache, &digest_cache]
+                    .iter()
+                    .find(|cache| cache.contains(&article.article_id))
+                    .copied();
+                let fat_article = if offline || cached.is_some() {
+                    news_flash.get_fat_article(&article.article_id).map(|mut fat_article| {
+                        cached.unwrap_or(&cache).apply(&mut fat_article);
+                        fat_article
+                    })
+                } else {
+                    runtime
+                        .block_on(news_flash.article_download_images(&article.article_id, client))
+                        .map(|fat_article| {
+                            if let Err(error) = digest_cache.store(&fat_article) {
+                                warn!("Failed to cache article '{}' for digest: {}", article.article_id, error);
+                            }
+                            fat_article
+                        })
+                };
+                match fat_article {
+                    Ok(fat_article) => Some(fat_article),
+                    Err(error) => {
+                        warn!("Failed to load article '{}' for digest: {}", article.article_id, error);
+                        None
+                    }
+                }
+            })
+            .collect();
+        let article_ids: Vec<ArticleID> = articles.iter().map(|article| article.article_id.clone()).collect();
+        if let Err(error) = digest_cache.retain(&article_ids) {
+            warn!("Failed to clean up digest cache: {}", error);
+        }
+        let feeds = news_flash
+            .get_feeds()
+            .map(|(feeds, _mappings)| feeds)
+            .unwrap_or_default();
+
+        let title = Self::title(&digest.sidebar_selection, &digest.header_selection);
+        let path = Self::write(digest.format, &title, &articles, &feeds, settings, &digest.directory)?;
+        Ok(Some(Digest { path, truncated }))
+    }
+
+    pub fn title(sidebar_selection: &SidebarSelection, header_selection: &HeaderSelection) -> String {
+        let selection = match sidebar_selection {
+            SidebarSelection::All => i18n("All Articles"),
+            SidebarSelection::Category(_, title)
+            | SidebarSelection::Feed(_, _, title)
+            | SidebarSelection::Tag(_, title)
+            | SidebarSelection::SmartFolder(_, title) => title.clone(),
+        };
+        match header_selection {
+            HeaderSelection::All => selection,
+            HeaderSelection::Unread => format!("{} ({})", selection, i18n("Unread")),
+            HeaderSelection::Marked => format!("{} ({})", selection, i18n("Starred")),
+        }
+    }
+
+    pub fn write(
+        format: DigestFormat,
+        title: &str,
+        articles: &[FatArticle],
+        feeds: &[Feed],
+        settings: &Arc<RwLock<Settings>>,
+        directory: &Path,
+    ) -> Result<PathBuf, ExportError> {
+        let path = Self::path(format, title, directory);
+        let groups = Self::group_by_feed(articles, feeds);
+
+        match format {
+            DigestFormat::Epub => Self::write_epub(title, &groups, &path)?,
+            DigestFormat::Html => Self::write_html(title, &groups, settings, &path)?,
+        }
+        Ok(path)
+    }
+
+    // one digest per day, exports of the same day replace each other
+    pub fn path(format: DigestFormat, title: &str, directory: &Path) -> PathBuf {
+        let name = format!("{} {}", title, Local::now().format("%Y-%m-%d")).replace("/", "_");
+        match format {
+            DigestFormat::Epub => directory.join(format!("{}.epub", name)),
+            DigestFormat::Html => directory.join(name),
+        }
+    }
+
+    fn group_by_feed<'a>(articles: &'a [FatArticle], feeds: &[Feed]) -> Vec<(String, Vec<&'a FatArticle>)> {
+        let mut groups: Vec<(String, Vec<&FatArticle>)> = Vec::new();
+        for article in articles {
+            let label = feeds
+                .iter()
+                .find(|feed| feed.feed_id == article.feed_id)
+                .map(|feed| feed.label.clone())
+                .unwrap_or_default();
+            match groups.iter_mut().find(|(group, _articles)| group == &label) {
+                Some((_group, articles)) => articles.push(article),
+                None => groups.push((label, vec![article])),
+            }
+        }
+        groups.sort_by_cached_key(|(label, _articles)| label.to_lowercase());
+        groups
+    }
+
+    fn write_epub(title: &str, groups: &[(String, Vec<&FatArticle>)], path: &Path) -> Result<(), ExportError> {
+        let mut converter = HtmlConverter::new(Target::Xhtml, EpubBuilder::IMAGE_DIR);
+        let mut epub = EpubBuilder::new(&format!("urn:newsflash:digest:{}", path.display()), title);
+        for (feed_label, articles) in groups {
+            for article in articles {
+                let body = converter.convert(ArticleExport::content(article))?;
+                epub.add_chapter(
+                    Some(feed_label),
+                    ArticleExport::title(article),
+                    &ArticleExport::chapter(article, feed_label, &body),
+                );
+            }
+        }
+        epub.add_images(converter.take_images());
+
+        fs::write(path, epub.build()?).context(ExportErrorKind::Write)?;
+        Ok(())
+    }
+
+    fn write_html(
+        title: &str,
+        groups: &[(String, Vec<&FatArticle>)],
+        settings: &Arc<RwLock<Settings>>,
+        directory: &Path,
+    ) -> Result<(), ExportError> {
+        // pages of a previous export of the same digest would otherwise stay around
+        let pages = directory.join(PAGES_DIR);
+        if pages.exists() {
+            fs::remove_dir_all(&pages).context(ExportErrorKind::Write)?;
+        }
+        fs::create_dir_all(&pages).context(ExportErrorKind::CreateDirectory)?;
+
+        let mut index = String::new();
+        let mut page = 0;
+        for (feed_label, articles) in groups {
+            index.push_str(&format!("<h2>{}</h2>\n<ul>\n", HtmlConverter::escape_xml(feed_label)));
+            for article in articles {
+                page += 1;
+                let file_name = format!("article-{}.html", page);
+                let html =
+                    ArticleView::build_article_static("article", article, feed_label, settings, None, None, true);
+                fs::write(pages.join(&file_name), html).context(ExportErrorKind::Write)?;
+
+                index.push_str(&format!(
+                    "<li><a href=\"{}/{}\">{}</a> <span class=\"meta\">{}</span></li>\n",
+                    PAGES_DIR,
+                    file_name,
+                    HtmlConverter::escape_xml(ArticleExport::title(article)),
+                    DateUtil::format(&article.date)
+                ));
+            }
+            index.push_str("</ul>\n");
+        }
+
+        let index = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n{}\n</style>\n</head>\n<body>\n<h1>{title}</h1>\n{}</body>\n</html>\n",
+            INDEX_STYLE,
+            index,
+            title = HtmlConverter::escape_xml(title)
+        );
+        fs::write(directory.join("index.html"), index).context(ExportErrorKind::Write)?;
+        Ok(())
+    }
+}
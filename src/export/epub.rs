@@ -0,0 +1,207 @@
+use super::convert::{HtmlConverter, Image};
+use super::error::ExportError;
+use super::zip::ZipWriter;
+use chrono::Utc;
+
+const CONTAINER_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+const STYLE_CSS: &str = "body { font-family: serif; line-height: 1.5; }
+h1 { font-size: 1.6em; margin-bottom: 0.2em; }
+img { max-width: 100%; height: auto; }
+pre { white-space: pre-wrap; }
+blockquote { margin-left: 1em; padding-left: 1em; border-left: 3px solid #ccc; }
+.meta { color: #666; font-size: 0.9em; }
+";
+
+struct Chapter {
+    group: Option<String>,
+    title: String,
+    body: String,
+}
+
+// Chapter bodies are expected to be produced by `HtmlConverter` with `Target::Xhtml` and
+// image directory `images`, so the collected images can be added with `add_images`.
+pub struct EpubBuilder {
+    identifier: String,
+    title: String,
+    author: Option<String>,
+    chapters: Vec<Chapter>,
+    images: Vec<Image>,
+}
+
+impl EpubBuilder {
+    pub const IMAGE_DIR: &'static str = "images";
+
+    pub fn new(identifier: &str, title: &str) -> Self {
+        EpubBuilder {
+            identifier: identifier.to_owned(),
+            title: title.to_owned(),
+            author: None,
+            chapters: Vec::new(),
+            images: Vec::new(),
+        }
+    }
+
+    pub fn set_author(&mut self, author: Option<&str>) {
+        self.author = author.map(|author| author.to_owned());
+    }
+
+    pub fn add_chapter(&mut self, group: Option<&str>, title: &str, body: &str) {
+        self.chapters.push(Chapter {
+            group: group.map(|group| group.to_owned()),
+            title: title.to_owned(),
+            body: body.to_owned(),
+        });
+    }
+
+    pub fn add_images(&mut self, images: Vec<Image>) {
+        self.images.extend(images);
+    }
+
+    pub fn build(&self) -> Result<Vec<u8>, ExportError> {
+        let mut zip = ZipWriter::new();
+        zip.add("mimetype", b"application/epub+zip")?;
+        zip.add("META-INF/container.xml", CONTAINER_XML.as_bytes())?;
+        zip.add("OEBPS/content.opf", self.package().as_bytes())?;
+        zip.add("OEBPS/nav.xhtml", self.navigation().as_bytes())?;
+        zip.add("OEBPS/style.css", STYLE_CSS.as_bytes())?;
+        for (index, chapter) in self.chapters.iter().enumerate() {
+            let path = format!("OEBPS/{}", Self::chapter_file(index));
+            zip.add(&path, Self::document(&chapter.title, &chapter.body).as_bytes())?;
+        }
+        for image in &self.images {
+            let path = format!("OEBPS/{}/{}", Self::IMAGE_DIR, image.file_name);
+            zip.add(&path, &image.data)?;
+        }
+        zip.finish()
+    }
+
+    fn chapter_file(index: usize) -> String {
+        format!("chapter-{}.xhtml", index + 1)
+    }
+
+    fn package(&self) -> String {
+        let mut manifest = String::new();
+        let mut spine = String::new();
+        for index in 0..self.chapters.len() {
+            manifest.push_str(&format!(
+                "    <item id=\"chapter-{}\" href=\"{}\" media-type=\"application/xhtml+xml\"/>\n",
+                index + 1,
+                Self::chapter_file(index)
+            ));
+            spine.push_str(&format!("    <itemref idref=\"chapter-{}\"/>\n", index + 1));
+        }
+        for (index, image) in self.images.iter().enumerate() {
+            manifest.push_str(&format!(
+                "    <item id=\"image-{}\" href=\"{}/{}\" media-type=\"{}\"/>\n",
+                index + 1,
+                Self::IMAGE_DIR,
+                HtmlConverter::escape_xml(&image.file_name),
+                image.mime_type
+            ));
+        }
+        let creator = match &self.author {
+            Some(author) => format!("    <dc:creator>{}</dc:creator>\n", HtmlConverter::escape_xml(author)),
+            None => String::new(),
+        };
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="uid">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:identifier id="uid">{}</dc:identifier>
+    <dc:title>{}</dc:title>
+{}    <dc:language>en</dc:language>
+    <meta property="dcterms:modified">{}</meta>
+  </metadata>
+  <manifest>
+    <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+    <item id="style" href="style.css" media-type="text/css"/>
+{}  </manifest>
+  <spine>
+{}  </spine>
+</package>
+"#,
+            HtmlConverter::escape_xml(&self.identifier),
+            HtmlConverter::escape_xml(&self.title),
+            creator,
+            Utc::now().format("%Y-%m-%dT%H:%M:%SZ"),
+            manifest,
+            spine
+        )
+    }
+
+    fn navigation(&self) -> String {
+        let mut items = String::new();
+        let mut current_group: Option<&str> = None;
+        for (index, chapter) in self.chapters.iter().enumerate() {
+            let group = chapter.group.as_deref();
+            if group != current_group {
+                if current_group.is_some() {
+                    items.push_str("</ol></li>\n");
+                }
+                if let Some(group) = group {
+                    items.push_str(&format!("<li><span>{}</span><ol>\n", HtmlConverter::escape_xml(group)));
+                }
+                current_group = group;
+            }
+            items.push_str(&format!(
+                "<li><a href=\"{}\">{}</a></li>\n",
+                Self::chapter_file(index),
+                HtmlConverter::escape_xml(&chapter.title)
+            ));
+        }
+        if current_group.is_some() {
+            items.push_str("</ol></li>\n");
+        }
+        Self::document(
+            &self.title,
+            &format!("<nav epub:type=\"toc\" id=\"toc\">\n<ol>\n{}</ol>\n</nav>", items),
+        )
+    }
+
+    fn document(title: &str, body: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops">
+<head>
+<title>{}</title>
+<link rel="stylesheet" type="text/css" href="style.css"/>
+</head>
+<body>
+{}
+</body>
+</html>
+"#,
+            HtmlConverter::escape_xml(title),
+            body
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EpubBuilder;
+
+    #[test]
+    fn navigation_groups_chapters() {
+        let mut epub = EpubBuilder::new("urn:test", "Digest");
+        epub.add_chapter(Some("Feed A"), "One", "<p>1</p>");
+        epub.add_chapter(Some("Feed A"), "Two", "<p>2</p>");
+        epub.add_chapter(Some("Feed B"), "Three", "<p>3</p>");
+
+        let navigation = epub.navigation();
+        assert!(navigation.contains(
+            "<ol>\n<li><span>Feed A</span><ol>\n<li><a href=\"chapter-1.xhtml\">One</a></li>\n\
+             <li><a href=\"chapter-2.xhtml\">Two</a></li>\n</ol></li>\n\
+             <li><span>Feed B</span><ol>\n<li><a href=\"chapter-3.xhtml\">Three</a></li>\n</ol></li>\n</ol>"
+        ));
+    }
+}
@@ -0,0 +1,68 @@
+use failure::{Backtrace, Context, Error, Fail};
+use std::fmt;
+
+#[derive(Debug)]
+pub struct ExportError {
+    inner: Context<ExportErrorKind>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Fail)]
+pub enum ExportErrorKind {
+    #[fail(display = "Failed to load articles")]
+    LoadArticles,
+    #[fail(display = "Failed to parse article content")]
+    Parse,
+    #[fail(display = "Failed to create directory")]
+    CreateDirectory,
+    #[fail(display = "Failed to write file")]
+    Write,
+    #[fail(display = "Export is too large for a ZIP archive")]
+    TooLarge,
+    #[fail(display = "Unknown Error")]
+    Unknown,
+}
+
+impl Fail for ExportError {
+    fn cause(&self) -> Option<&dyn Fail> {
+        self.inner.cause()
+    }
+
+    fn backtrace(&self) -> Option<&Backtrace> {
+        self.inner.backtrace()
+    }
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, f)
+    }
+}
+
+impl ExportError {
+    #[allow(dead_code)]
+    pub fn kind(&self) -> ExportErrorKind {
+        *self.inner.get_context()
+    }
+}
+
+impl From<ExportErrorKind> for ExportError {
+    fn from(kind: ExportErrorKind) -> ExportError {
+        ExportError {
+            inner: Context::new(kind),
+        }
+    }
+}
+
+impl From<Context<ExportErrorKind>> for ExportError {
+    fn from(inner: Context<ExportErrorKind>) -> ExportError {
+        ExportError { inner }
+    }
+}
+
+impl From<Error> for ExportError {
+    fn from(_: Error) -> ExportError {
+        ExportError {
+            inner: Context::new(ExportErrorKind::Unknown),
+        }
+    }
+}
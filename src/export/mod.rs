@@ -0,0 +1,249 @@
+mod convert;
+mod digest;
+mod epub;
+mod error;
+mod zip;
+
+pub use self::digest::{DigestExport, MAX_DIGEST_ARTICLES};
+pub use self::error::{ExportError, ExportErrorKind};
+
+use self::convert::{HtmlConverter, Target};
+use self::epub::EpubBuilder;
+use crate::article_view::ArticleView;
+use crate::settings::Settings;
+use crate::util::{DateUtil, RUNTIME_ERROR};
+use failure::ResultExt;
+use log::warn;
+use news_flash::models::{ArticleID, FatArticle};
+use news_flash::NewsFlash;
+use parking_lot::RwLock;
+use reqwest::Client;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    Html,
+    Markdown,
+    PlainText,
+    Epub,
+}
+
+impl ExportFormat {
+    pub fn all() -> &'static [ExportFormat] {
+        &[
+            ExportFormat::Html,
+            ExportFormat::Markdown,
+            ExportFormat::PlainText,
+            ExportFormat::Epub,
+        ]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportFormat::Html => "HTML",
+            ExportFormat::Markdown => "Markdown",
+            ExportFormat::PlainText => "Plain Text",
+            ExportFormat::Epub => "EPUB",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|format| format.name() == name)
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Html => "html",
+            ExportFormat::Markdown => "md",
+            ExportFormat::PlainText => "txt",
+            ExportFormat::Epub => "epub",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ExportFormat::Html => "text/html",
+            ExportFormat::Markdown => "text/markdown",
+            ExportFormat::PlainText => "text/plain",
+            ExportFormat::Epub => "application/epub+zip",
+        }
+    }
+}
+
+// The article is expected to have its images embedded as data urls (see
+// `NewsFlash::article_download_images`). Markdown bundles them in a `<name>_files`
+// directory next to the file, EPUB packs them into the archive.
+pub struct ArticleExport;
+
+impl ArticleExport {
+    pub fn write(
+        format: ExportFormat,
+        article: &FatArticle,
+        feed_label: &str,
+        settings: &Arc<RwLock<Settings>>,
+        path: &Path,
+    ) -> Result<(), ExportError> {
+        match format {
+            ExportFormat::Html => {
+                let html =
+                    ArticleView::build_article_static("article", article, feed_label, settings, None, None, true);
+                fs::write(path, html).context(ExportErrorKind::Write)?;
+            }
+            ExportFormat::Markdown | ExportFormat::PlainText => Self::write_text(format, article, feed_label, path)?,
+            ExportFormat::Epub => Self::write_epub(article, feed_label, path)?,
+        }
+        Ok(())
+    }
+
+    // Write the articles as html pages into `directory`. Articles that fail are skipped and counted.
+    pub fn write_html_pages(
+        news_flash: &NewsFlash,
+        article_ids: &[ArticleID],
+        settings: &Arc<RwLock<Settings>>,
+        directory: &Path,
+        offline: bool,
+        client: &Client,
+    ) -> Result<usize, ExportError> {
+        let (feeds, _mappings) = news_flash.get_feeds().context(ExportErrorKind::LoadArticles)?;
+        let mut runtime = Runtime::new().expect(RUNTIME_ERROR);
+        let mut failed = 0;
+
+        for article_id in article_ids {
+            let article = if offline {
+                news_flash.get_fat_article(article_id)
+            } else {
+                runtime.block_on(news_flash.article_download_images(article_id, client))
+            };
+            let article = match article {
+                Ok(article) => article,
+                Err(error) => {
+                    warn!("Failed to load article '{}': {}", article_id, error);
+                    failed += 1;
+                    continue;
+                }
+            };
+            let feed_label = feeds
+                .iter()
+                .find(|feed| feed.feed_id == article.feed_id)
+                .map(|feed| feed.label.clone())
+                .unwrap_or_default();
+
+            // don't overwrite articles with the same title exported in this batch
+            let title = Self::title(&article).replace("/", "_");
+            let mut path = directory.join(format!("{}.html", title));
+            let mut suffix = 2;
+            while path.exists() {
+                path = directory.join(format!("{} ({}).html", title, suffix));
+                suffix += 1;
+            }
+
+            if let Err(error) = Self::write(ExportFormat::Html, &article, &feed_label, settings, &path) {
+                warn!("Failed to write '{}': {}", path.display(), error);
+                failed += 1;
+            }
+        }
+        Ok(failed)
+    }
+
+    fn content(article: &FatArticle) -> &str {
+        article
+            .scraped_content
+            .as_deref()
+            .or(article.html.as_deref())
+            .unwrap_or_default()
+    }
+
+    fn title(article: &FatArticle) -> &str {
+        article.title.as_deref().unwrap_or("Article")
+    }
+
+    fn byline(article: &FatArticle, feed_label: &str) -> String {
+        let mut parts = Vec::new();
+        if let Some(author) = &article.author {
+            parts.push(author.clone());
+        }
+        if !feed_label.is_empty() {
+            parts.push(feed_label.to_owned());
+        }
+        parts.push(DateUtil::format(&article.date));
+        parts.join(" · ")
+    }
+
+    fn write_text(
+        format: ExportFormat,
+        article: &FatArticle,
+        feed_label: &str,
+        path: &Path,
+    ) -> Result<(), ExportError> {
+        let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("article");
+        let image_dir = format!("{}_files", stem);
+        let (target, title) = match format {
+            ExportFormat::Markdown => (Target::Markdown, format!("# {}", Self::title(article))),
+            _ => (
+                Target::PlainText,
+                format!(
+                    "{}\n{}",
+                    Self::title(article),
+                    "=".repeat(Self::title(article).chars().count())
+                ),
+            ),
+        };
+
+        let mut converter = HtmlConverter::new(target, &image_dir);
+        let body = converter.convert(Self::content(article))?;
+
+        let mut text = format!("{}\n\n{}\n", title, Self::byline(article, feed_label));
+        if let Some(url) = &article.url {
+            match target {
+                Target::Markdown => text.push_str(&format!("\n<{}>\n", url.get())),
+                _ => text.push_str(&format!("{}\n", url.get())),
+            }
+        }
+        text.push('\n');
+        if target == Target::Markdown {
+            text.push_str("---\n\n");
+        }
+        text.push_str(&body);
+        text.push('\n');
+        fs::write(path, text).context(ExportErrorKind::Write)?;
+
+        let images = converter.take_images();
+        if !images.is_empty() {
+            let image_dir = path.with_file_name(&image_dir);
+            fs::create_dir_all(&image_dir).context(ExportErrorKind::CreateDirectory)?;
+            for image in images {
+                fs::write(image_dir.join(&image.file_name), &image.data).context(ExportErrorKind::Write)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_epub(article: &FatArticle, feed_label: &str, path: &Path) -> Result<(), ExportError> {
+        let mut converter = HtmlConverter::new(Target::Xhtml, EpubBuilder::IMAGE_DIR);
+        let body = converter.convert(Self::content(article))?;
+
+        let identifier = match &article.url {
+            Some(url) => url.get().to_string(),
+            None => format!("urn:newsflash:{}", article.article_id),
+        };
+        let mut epub = EpubBuilder::new(&identifier, Self::title(article));
+        epub.set_author(article.author.as_deref());
+        epub.add_chapter(None, Self::title(article), &Self::chapter(article, feed_label, &body));
+        epub.add_images(converter.take_images());
+
+        fs::write(path, epub.build()?).context(ExportErrorKind::Write)?;
+        Ok(())
+    }
+
+    fn chapter(article: &FatArticle, feed_label: &str, body: &str) -> String {
+        format!(
+            "<h1>{}</h1>\n<p class=\"meta\">{}</p>\n{}",
+            HtmlConverter::escape_xml(Self::title(article)),
+            HtmlConverter::escape_xml(&Self::byline(article, feed_label)),
+            body
+        )
+    }
+}
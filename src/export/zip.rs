@@ -0,0 +1,162 @@
+use super::error::{ExportError, ExportErrorKind};
+use chrono::{Datelike, Local, Timelike};
+use std::convert::TryFrom;
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x0605_4b50;
+const VERSION: u16 = 20;
+// file names are utf-8 encoded
+const FLAGS: u16 = 0x0800;
+const METHOD_STORED: u16 = 0;
+
+struct Entry {
+    name: String,
+    crc: u32,
+    size: u32,
+    offset: u32,
+}
+
+// No compression: EPUB requires the `mimetype` entry to be stored uncompressed anyway and the images
+// that make up most of an exported article are already compressed.
+// There is no ZIP64 support, archives that don't fit into 4 GiB fail with `ExportErrorKind::TooLarge`.
+pub struct ZipWriter {
+    data: Vec<u8>,
+    entries: Vec<Entry>,
+    time: u16,
+    date: u16,
+}
+
+impl Default for ZipWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ZipWriter {
+    pub fn new() -> Self {
+        let now = Local::now();
+        ZipWriter {
+            data: Vec::new(),
+            entries: Vec::new(),
+            time: ((now.hour() << 11) | (now.minute() << 5) | (now.second() / 2)) as u16,
+            date: (((now.year().max(1980) - 1980) << 9) as u32 | (now.month() << 5) | now.day()) as u16,
+        }
+    }
+
+    pub fn add(&mut self, name: &str, content: &[u8]) -> Result<(), ExportError> {
+        Self::checked_u16(name.len())?;
+        let mut hasher = crc32fast::Hasher::new();
+        hasher.update(content);
+        let entry = Entry {
+            name: name.to_owned(),
+            crc: hasher.finalize(),
+            size: Self::checked_u32(content.len())?,
+            offset: Self::checked_u32(self.data.len())?,
+        };
+
+        Self::write_u32(&mut self.data, LOCAL_HEADER_SIGNATURE);
+        Self::write_u16(&mut self.data, VERSION);
+        Self::write_u16(&mut self.data, FLAGS);
+        Self::write_u16(&mut self.data, METHOD_STORED);
+        Self::write_u16(&mut self.data, self.time);
+        Self::write_u16(&mut self.data, self.date);
+        Self::write_u32(&mut self.data, entry.crc);
+        Self::write_u32(&mut self.data, entry.size);
+        Self::write_u32(&mut self.data, entry.size);
+        Self::write_u16(&mut self.data, entry.name.len() as u16);
+        Self::write_u16(&mut self.data, 0);
+        self.data.extend_from_slice(entry.name.as_bytes());
+        self.data.extend_from_slice(content);
+
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<Vec<u8>, ExportError> {
+        let entry_count = Self::checked_u16(self.entries.len())?;
+        let directory_offset = Self::checked_u32(self.data.len())?;
+        for entry in &self.entries {
+            Self::write_u32(&mut self.data, CENTRAL_HEADER_SIGNATURE);
+            Self::write_u16(&mut self.data, VERSION);
+            Self::write_u16(&mut self.data, VERSION);
+            Self::write_u16(&mut self.data, FLAGS);
+            Self::write_u16(&mut self.data, METHOD_STORED);
+            Self::write_u16(&mut self.data, self.time);
+            Self::write_u16(&mut self.data, self.date);
+            Self::write_u32(&mut self.data, entry.crc);
+            Self::write_u32(&mut self.data, entry.size);
+            Self::write_u32(&mut self.data, entry.size);
+            Self::write_u16(&mut self.data, entry.name.len() as u16);
+            // extra field, comment, disk number, internal and external attributes
+            Self::write_u16(&mut self.data, 0);
+            Self::write_u16(&mut self.data, 0);
+            Self::write_u16(&mut self.data, 0);
+            Self::write_u16(&mut self.data, 0);
+            Self::write_u32(&mut self.data, 0);
+            Self::write_u32(&mut self.data, entry.offset);
+            self.data.extend_from_slice(entry.name.as_bytes());
+        }
+        let directory_size = Self::checked_u32(self.data.len())? - directory_offset;
+
+        Self::write_u32(&mut self.data, END_OF_CENTRAL_DIRECTORY_SIGNATURE);
+        Self::write_u16(&mut self.data, 0);
+        Self::write_u16(&mut self.data, 0);
+        Self::write_u16(&mut self.data, entry_count);
+        Self::write_u16(&mut self.data, entry_count);
+        Self::write_u32(&mut self.data, directory_size);
+        Self::write_u32(&mut self.data, directory_offset);
+        Self::write_u16(&mut self.data, 0);
+
+        Ok(self.data)
+    }
+
+    fn checked_u16(value: usize) -> Result<u16, ExportError> {
+        u16::try_from(value).map_err(|_| ExportErrorKind::TooLarge.into())
+    }
+
+    fn checked_u32(value: usize) -> Result<u32, ExportError> {
+        u32::try_from(value).map_err(|_| ExportErrorKind::TooLarge.into())
+    }
+
+    fn write_u16(data: &mut Vec<u8>, value: u16) {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_u32(data: &mut Vec<u8>, value: u32) {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ZipWriter;
+
+    #[test]
+    fn end_of_central_directory() {
+        let mut zip = ZipWriter::new();
+        zip.add("mimetype", b"application/epub+zip").unwrap();
+        zip.add("a.txt", b"abc").unwrap();
+        let data = zip.finish().unwrap();
+
+        // the mimetype has to be the first file, uncompressed and without extra field
+        assert_eq!(&data[30..38], b"mimetype");
+        assert_eq!(&data[38..58], b"application/epub+zip");
+
+        let end = &data[data.len() - 22..];
+        assert_eq!(&end[0..4], &0x0605_4b50_u32.to_le_bytes());
+        assert_eq!(u16::from_le_bytes([end[10], end[11]]), 2);
+        let directory_offset = u32::from_le_bytes([end[16], end[17], end[18], end[19]]) as usize;
+        assert_eq!(
+            &data[directory_offset..directory_offset + 4],
+            &0x0201_4b50_u32.to_le_bytes()
+        );
+    }
+
+    #[test]
+    fn name_too_long() {
+        let mut zip = ZipWriter::new();
+        let name = "a".repeat(usize::from(u16::MAX) + 1);
+        assert!(zip.add(&name, b"abc").is_err());
+    }
+}
@@ -0,0 +1,369 @@
+use super::{FilterAction, FilterField, FilterRule, FilterRules};
+use crate::app::Action;
+use crate::i18n::{i18n, i18n_f};
+use crate::settings::Settings;
+use crate::util::{BuilderHelper, Util, CHANNEL_ERROR};
+use futures::channel::oneshot;
+use futures::executor::ThreadPool;
+use futures::FutureExt;
+use glib::{clone, Sender};
+use gtk::{
+    prelude::ComboBoxExtManual, Box, Button, ButtonExt, CheckButton, ComboBoxExt, ComboBoxText, ComboBoxTextExt,
+    ContainerExt, Entry, EntryExt, GtkWindowExt, Image, Inhibit, Label, LabelExt, ListBox, ListBoxExt, ListBoxRow,
+    ListBoxRowExt, Orientation, ReliefStyle, Switch, SwitchExt, ToggleButtonExt, WidgetExt, Window,
+};
+use news_flash::models::TagID;
+use news_flash::NewsFlash;
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+const TEST_PREVIEW_COUNT: usize = 5;
+
+pub struct FilterRulesDialog {
+    pub widget: Window,
+}
+
+#[derive(Clone)]
+struct RuleEditor {
+    name_entry: Entry,
+    field_combo: ComboBoxText,
+    pattern_entry: Entry,
+    regex_check: CheckButton,
+    read_check: CheckButton,
+    star_check: CheckButton,
+    hide_check: CheckButton,
+    tag_check: CheckButton,
+    tag_combo: ComboBoxText,
+    test_label: Label,
+}
+
+impl RuleEditor {
+    fn load(&self, rule: Option<&FilterRule>) {
+        let default_rule = FilterRule {
+            name: String::new(),
+            enabled: true,
+            field: FilterField::Title,
+            pattern: String::new(),
+            regex: false,
+            actions: Vec::new(),
+        };
+        let rule = rule.unwrap_or(&default_rule);
+
+        self.name_entry.set_text(&rule.name);
+        self.field_combo.set_active_id(Some(rule.field.to_str()));
+        self.pattern_entry.set_text(&rule.pattern);
+        self.regex_check.set_active(rule.regex);
+        self.read_check
+            .set_active(rule.actions.contains(&FilterAction::MarkRead));
+        self.star_check.set_active(rule.actions.contains(&FilterAction::Star));
+        self.hide_check.set_active(rule.actions.contains(&FilterAction::Hide));
+
+        let tag_id = rule.actions.iter().find_map(|action| match action {
+            FilterAction::Tag(tag_id) => Some(tag_id.to_string()),
+            _ => None,
+        });
+        self.tag_check.set_active(tag_id.is_some());
+        match tag_id {
+            Some(tag_id) => {
+                self.tag_combo.set_active_id(Some(&tag_id));
+            }
+            None => self.tag_combo.set_active(Some(0)),
+        }
+        self.test_label.set_text("");
+    }
+
+    fn read(&self, enabled: bool) -> FilterRule {
+        let field = self
+            .field_combo
+            .get_active_id()
+            .and_then(|id| FilterField::all().into_iter().find(|f| f.to_str() == id.as_str()))
+            .unwrap_or(FilterField::Title);
+
+        let mut actions = Vec::new();
+        if self.read_check.get_active() {
+            actions.push(FilterAction::MarkRead);
+        }
+        if self.star_check.get_active() {
+            actions.push(FilterAction::Star);
+        }
+        if self.hide_check.get_active() {
+            actions.push(FilterAction::Hide);
+        }
+        if self.tag_check.get_active() {
+            if let Some(tag_id) = self.tag_combo.get_active_id() {
+                actions.push(FilterAction::Tag(TagID::new(tag_id.as_str())));
+            }
+        }
+
+        let pattern = self.pattern_entry.get_text().as_str().to_owned();
+        let name = self.name_entry.get_text().as_str().trim().to_owned();
+        FilterRule {
+            name: if name.is_empty() { pattern.clone() } else { name },
+            enabled,
+            field,
+            pattern,
+            regex: self.regex_check.get_active(),
+            actions,
+        }
+    }
+}
+
+impl FilterRulesDialog {
+    pub fn new(
+        window: &gtk::ApplicationWindow,
+        sender: &Sender<Action>,
+        settings: &Arc<RwLock<Settings>>,
+        news_flash: &Arc<RwLock<Option<NewsFlash>>>,
+        threadpool: ThreadPool,
+    ) -> Self {
+        let builder = BuilderHelper::new("filter_rules_dialog");
+        let dialog = builder.get::<Window>("filter_rules_dialog");
+        dialog.set_transient_for(Some(window));
+
+        let rules_list = builder.get::<ListBox>("rules_list");
+        let add_button = builder.get::<Button>("add_button");
+        let test_button = builder.get::<Button>("test_button");
+        let save_button = builder.get::<Button>("save_button");
+
+        let editor = RuleEditor {
+            name_entry: builder.get::<Entry>("name_entry"),
+            field_combo: builder.get::<ComboBoxText>("field_combo"),
+            pattern_entry: builder.get::<Entry>("pattern_entry"),
+            regex_check: builder.get::<CheckButton>("regex_check"),
+            read_check: builder.get::<CheckButton>("read_check"),
+            star_check: builder.get::<CheckButton>("star_check"),
+            hide_check: builder.get::<CheckButton>("hide_check"),
+            tag_check: builder.get::<CheckButton>("tag_check"),
+            tag_combo: builder.get::<ComboBoxText>("tag_combo"),
+            test_label: builder.get::<Label>("test_label"),
+        };
+
+        for field in FilterField::all() {
+            editor.field_combo.append(Some(field.to_str()), &i18n(field.to_str()));
+        }
+
+        let tags = match news_flash.read().as_ref() {
+            Some(news_flash) => news_flash.get_tags().unwrap_or_default(),
+            None => Vec::new(),
+        };
+        for tag in &tags {
+            editor.tag_combo.append(Some(&tag.tag_id.to_string()), &tag.label);
+        }
+        editor.tag_check.set_sensitive(!tags.is_empty());
+        editor.tag_combo.set_sensitive(!tags.is_empty());
+
+        // index of the rule currently shown in the editor, `None` for a new rule
+        let editing: Arc<RwLock<Option<usize>>> = Arc::new(RwLock::new(None));
+        editor.load(None);
+        Self::update_list(&rules_list, settings, sender, &editing, &editor);
+
+        rules_list.connect_row_activated(clone!(
+            @strong settings,
+            @strong editing,
+            @strong editor => @default-panic, move |_list, row|
+        {
+            let index = row.get_index() as usize;
+            if let Some(rule) = settings.read().get_filter_rules().get(index) {
+                editing.write().replace(index);
+                editor.load(Some(rule));
+            }
+        }));
+
+        add_button.connect_clicked(clone!(
+            @weak rules_list,
+            @strong editing,
+            @strong editor => @default-panic, move |_button|
+        {
+            editing.write().take();
+            rules_list.unselect_all();
+            editor.load(None);
+            editor.name_entry.grab_focus();
+        }));
+
+        test_button.connect_clicked(clone!(
+            @strong news_flash,
+            @strong threadpool,
+            @strong editor => @default-panic, move |_button|
+        {
+            let rule = editor.read(true);
+            if let Err(error) = rule.compile() {
+                editor.test_label.set_text(&error.to_string());
+                return;
+            }
+            editor.test_label.set_text(&i18n("Testing…"));
+
+            let (sender, receiver) = oneshot::channel::<Result<Vec<String>, String>>();
+            let news_flash = news_flash.clone();
+            let thread_future = async move {
+                if let Some(news_flash) = news_flash.read().as_ref() {
+                    let result = FilterRules::test(news_flash, &rule)
+                        .map(|articles| {
+                            articles
+                                .iter()
+                                .map(|a| a.title.clone().unwrap_or_else(|| i18n("No Title")))
+                                .collect()
+                        })
+                        .map_err(|error| error.to_string());
+                    sender.send(result).expect(CHANNEL_ERROR);
+                }
+            };
+
+            let glib_future = receiver.map(clone!(@weak editor.test_label as test_label => @default-panic, move |res| {
+                match res {
+                    Ok(Ok(titles)) => {
+                        let mut text = i18n_f("Matches {} unread articles", &[&titles.len().to_string()]);
+                        for title in titles.iter().take(TEST_PREVIEW_COUNT) {
+                            text.push_str("\n• ");
+                            text.push_str(title);
+                        }
+                        if titles.len() > TEST_PREVIEW_COUNT {
+                            text.push_str("\n…");
+                        }
+                        test_label.set_text(&text);
+                    }
+                    Ok(Err(error)) => test_label.set_text(&error),
+                    Err(_) => {}
+                }
+            }));
+
+            threadpool.spawn_ok(thread_future);
+            Util::glib_spawn_future(glib_future);
+        }));
+
+        save_button.connect_clicked(clone!(
+            @weak rules_list,
+            @strong settings,
+            @strong sender,
+            @strong editing,
+            @strong editor => @default-panic, move |_button|
+        {
+            let mut rules = settings.read().get_filter_rules();
+            let index = editing.read().filter(|index| *index < rules.len());
+            let enabled = index.map(|index| rules[index].enabled).unwrap_or(true);
+            let rule = editor.read(enabled);
+
+            if rule.pattern.is_empty() {
+                editor.test_label.set_text(&i18n("The rule needs a pattern to match"));
+                return;
+            }
+            if rule.actions.is_empty() {
+                editor.test_label.set_text(&i18n("The rule needs at least one action"));
+                return;
+            }
+            if let Err(error) = rule.compile() {
+                editor.test_label.set_text(&error.to_string());
+                return;
+            }
+
+            match index {
+                Some(index) => rules[index] = rule,
+                None => {
+                    rules.push(rule);
+                    editing.write().replace(rules.len() - 1);
+                }
+            }
+
+            if settings.write().set_filter_rules(rules).is_err() {
+                Util::send(
+                    &sender,
+                    Action::ErrorSimpleMessage("Failed to set setting 'filter rules'.".to_owned()),
+                );
+                return;
+            }
+            editor.test_label.set_text(&i18n("Saved"));
+            Self::update_list(&rules_list, &settings, &sender, &editing, &editor);
+            Util::send(&sender, Action::UpdateArticleList);
+        }));
+
+        FilterRulesDialog { widget: dialog }
+    }
+
+    fn update_list(
+        list: &ListBox,
+        settings: &Arc<RwLock<Settings>>,
+        sender: &Sender<Action>,
+        editing: &Arc<RwLock<Option<usize>>>,
+        editor: &RuleEditor,
+    ) {
+        for row in list.get_children() {
+            list.remove(&row);
+        }
+
+        for (index, rule) in settings.read().get_filter_rules().iter().enumerate() {
+            let row_box = Box::new(Orientation::Horizontal, 6);
+            row_box.set_margin_start(12);
+            row_box.set_margin_end(6);
+            row_box.set_margin_top(6);
+            row_box.set_margin_bottom(6);
+
+            let label = Label::new(Some(&rule.name));
+            label.set_hexpand(true);
+            label.set_xalign(0.0);
+            label.set_ellipsize(pango::EllipsizeMode::End);
+            row_box.add(&label);
+
+            let enabled_switch = Switch::new();
+            enabled_switch.set_valign(gtk::Align::Center);
+            enabled_switch.set_state(rule.enabled);
+            enabled_switch.connect_state_set(clone!(
+                @strong settings,
+                @strong sender => @default-panic, move |_switch, is_set|
+            {
+                let mut rules = settings.read().get_filter_rules();
+                if let Some(rule) = rules.get_mut(index) {
+                    rule.enabled = is_set;
+                }
+                if settings.write().set_filter_rules(rules).is_err() {
+                    Util::send(
+                        &sender,
+                        Action::ErrorSimpleMessage("Failed to set setting 'filter rules'.".to_owned()),
+                    );
+                }
+                Util::send(&sender, Action::UpdateArticleList);
+                Inhibit(false)
+            }));
+            row_box.add(&enabled_switch);
+
+            let delete_button = Button::new();
+            delete_button.set_relief(ReliefStyle::None);
+            delete_button.set_tooltip_text(Some(&i18n("Delete Rule")));
+            delete_button.add(&Image::from_icon_name(
+                Some("user-trash-symbolic"),
+                gtk::IconSize::Button,
+            ));
+            delete_button.connect_clicked(clone!(
+                @weak list,
+                @strong settings,
+                @strong sender,
+                @strong editing,
+                @strong editor => @default-panic, move |_button|
+            {
+                let mut rules = settings.read().get_filter_rules();
+                if index < rules.len() {
+                    rules.remove(index);
+                }
+                if settings.write().set_filter_rules(rules).is_err() {
+                    Util::send(
+                        &sender,
+                        Action::ErrorSimpleMessage("Failed to set setting 'filter rules'.".to_owned()),
+                    );
+                    return;
+                }
+                editing.write().take();
+                editor.load(None);
+                Self::update_list(&list, &settings, &sender, &editing, &editor);
+                Util::send(&sender, Action::UpdateArticleList);
+            }));
+            row_box.add(&delete_button);
+
+            let row = ListBoxRow::new();
+            row.add(&row_box);
+            row.show_all();
+            list.add(&row);
+
+            if *editing.read() == Some(index) {
+                list.select_row(Some(&row));
+            }
+        }
+    }
+}
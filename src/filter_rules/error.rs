@@ -0,0 +1,64 @@
+use failure::{Backtrace, Context, Error, Fail};
+use std::fmt;
+
+#[derive(Debug)]
+pub struct FilterRuleError {
+    inner: Context<FilterRuleErrorKind>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Fail)]
+pub enum FilterRuleErrorKind {
+    #[fail(display = "Invalid regular expression: {}", _0)]
+    InvalidRegex(String),
+    #[fail(display = "Failed to load data from the database")]
+    DataBase,
+    #[fail(display = "Failed to apply filter action")]
+    Backend,
+    #[fail(display = "Unknown Error")]
+    Unknown,
+}
+
+impl Fail for FilterRuleError {
+    fn cause(&self) -> Option<&dyn Fail> {
+        self.inner.cause()
+    }
+
+    fn backtrace(&self) -> Option<&Backtrace> {
+        self.inner.backtrace()
+    }
+}
+
+impl fmt::Display for FilterRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, f)
+    }
+}
+
+impl FilterRuleError {
+    #[allow(dead_code)]
+    pub fn kind(&self) -> FilterRuleErrorKind {
+        self.inner.get_context().clone()
+    }
+}
+
+impl From<FilterRuleErrorKind> for FilterRuleError {
+    fn from(kind: FilterRuleErrorKind) -> FilterRuleError {
+        FilterRuleError {
+            inner: Context::new(kind),
+        }
+    }
+}
+
+impl From<Context<FilterRuleErrorKind>> for FilterRuleError {
+    fn from(inner: Context<FilterRuleErrorKind>) -> FilterRuleError {
+        FilterRuleError { inner }
+    }
+}
+
+impl From<Error> for FilterRuleError {
+    fn from(_: Error) -> FilterRuleError {
+        FilterRuleError {
+            inner: Context::new(FilterRuleErrorKind::Unknown),
+        }
+    }
+}
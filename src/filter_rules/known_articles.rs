@@ -0,0 +1,72 @@
+use super::{FilterRuleError, FilterRuleErrorKind};
+use crate::util::Util;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use failure::ResultExt;
+use news_flash::models::{Article, ArticleFilter, ArticleID, ArticleOrder};
+use news_flash::NewsFlash;
+use std::collections::HashSet;
+
+// a sync can add articles dated up to this long before the newest article already in the database
+const SYNC_WINDOW_DAYS: i64 = 7;
+
+// Articles present before a sync, so the ones added by it can be told apart afterwards.
+// Only articles dated within `SYNC_WINDOW_DAYS` of the newest one are remembered
+// instead of the whole database.
+pub struct KnownArticles {
+    newer_than: Option<DateTime<Utc>>,
+    article_ids: HashSet<ArticleID>,
+}
+
+impl KnownArticles {
+    pub fn load(news_flash: &NewsFlash) -> Result<Self, FilterRuleError> {
+        let mut newest = Self::article_filter(None);
+        newest.limit = Some(1);
+        newest.order = Some(ArticleOrder::NewestFirst);
+        let newer_than = news_flash
+            .get_articles(newest)
+            .context(FilterRuleErrorKind::DataBase)?
+            .first()
+            .map(|article| Utc.from_utc_datetime(&article.date) - Duration::days(SYNC_WINDOW_DAYS));
+
+        // an empty database: every article of the sync is new
+        let article_ids = match newer_than {
+            Some(newer_than) => Util::load_all_articles(news_flash, Self::article_filter(Some(newer_than)))
+                .context(FilterRuleErrorKind::DataBase)?
+                .into_iter()
+                .map(|article| article.article_id)
+                .collect(),
+            None => HashSet::new(),
+        };
+
+        Ok(KnownArticles {
+            newer_than,
+            article_ids,
+        })
+    }
+
+    pub fn new_articles(&self, news_flash: &NewsFlash) -> Result<Vec<Article>, FilterRuleError> {
+        let mut articles = Util::load_all_articles(news_flash, Self::article_filter(self.newer_than))
+            .context(FilterRuleErrorKind::DataBase)?;
+        articles.retain(|article| !self.article_ids.contains(&article.article_id));
+        Ok(articles)
+    }
+
+    fn article_filter(newer_than: Option<DateTime<Utc>>) -> ArticleFilter<'static> {
+        ArticleFilter {
+            limit: None,
+            offset: None,
+            order: None,
+            unread: None,
+            marked: None,
+            feed: None,
+            feed_blacklist: None,
+            category: None,
+            category_blacklist: None,
+            tag: None,
+            ids: None,
+            newer_than,
+            older_than: None,
+            search_term: None,
+        }
+    }
+}
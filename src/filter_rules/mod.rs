@@ -0,0 +1,381 @@
+mod dialog;
+mod error;
+mod known_articles;
+
+pub use self::dialog::FilterRulesDialog;
+pub use self::error::{FilterRuleError, FilterRuleErrorKind};
+pub use self::known_articles::KnownArticles;
+
+use crate::util::{Util, RUNTIME_ERROR};
+use failure::ResultExt;
+use log::{info, warn};
+use news_flash::models::{
+    Article, ArticleFilter, ArticleID, Category, Feed, FeedID, FeedMapping, Marked, Read, Tag, TagID,
+};
+use news_flash::NewsFlash;
+use regex::{Regex, RegexBuilder};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tokio::runtime::Runtime;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum FilterField {
+    Title,
+    Summary,
+    Author,
+    Feed,
+    Category,
+}
+
+impl FilterField {
+    pub fn all() -> Vec<Self> {
+        vec![
+            FilterField::Title,
+            FilterField::Summary,
+            FilterField::Author,
+            FilterField::Feed,
+            FilterField::Category,
+        ]
+    }
+
+    pub fn to_str(self) -> &'static str {
+        match self {
+            FilterField::Title => "Title",
+            FilterField::Summary => "Summary",
+            FilterField::Author => "Author",
+            FilterField::Feed => "Feed",
+            FilterField::Category => "Category",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FilterAction {
+    MarkRead,
+    Star,
+    Tag(TagID),
+    Hide,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterRule {
+    pub name: String,
+    pub enabled: bool,
+    pub field: FilterField,
+    pub pattern: String,
+    // otherwise `pattern` is a case insensitive substring
+    pub regex: bool,
+    pub actions: Vec<FilterAction>,
+}
+
+impl FilterRule {
+    pub fn compile(&self) -> Result<CompiledFilterRule, FilterRuleError> {
+        let regex = if self.regex {
+            let regex = RegexBuilder::new(&self.pattern)
+                .case_insensitive(true)
+                .build()
+                .map_err(|error| FilterRuleErrorKind::InvalidRegex(error.to_string()))?;
+            Some(regex)
+        } else {
+            None
+        };
+
+        Ok(CompiledFilterRule {
+            rule: self.clone(),
+            regex,
+            pattern: self.pattern.to_lowercase(),
+        })
+    }
+
+    pub fn hides(&self) -> bool {
+        self.actions.contains(&FilterAction::Hide)
+    }
+}
+
+pub struct CompiledFilterRule {
+    rule: FilterRule,
+    regex: Option<Regex>,
+    pattern: String,
+}
+
+impl CompiledFilterRule {
+    pub fn matches(&self, article: &Article, context: &FilterContext) -> bool {
+        match self.rule.field {
+            FilterField::Title => self.matches_values(&[article.title.as_deref().unwrap_or("")]),
+            FilterField::Summary => self.matches_values(&[article.summary.as_deref().unwrap_or("")]),
+            FilterField::Author => self.matches_values(&[article.author.as_deref().unwrap_or("")]),
+            FilterField::Feed => self.matches_values(&[context.feed_label(&article.feed_id)]),
+            FilterField::Category => {
+                let labels: Vec<&str> = context.category_labels(&article.feed_id);
+                self.matches_values(&labels)
+            }
+        }
+    }
+
+    fn matches_values(&self, values: &[&str]) -> bool {
+        if self.pattern.is_empty() {
+            return false;
+        }
+
+        values.iter().any(|value| match &self.regex {
+            Some(regex) => regex.is_match(value),
+            None => value.to_lowercase().contains(&self.pattern),
+        })
+    }
+}
+
+pub struct FilterContext {
+    feeds: HashMap<FeedID, String>,
+    categories: HashMap<FeedID, Vec<String>>,
+}
+
+impl FilterContext {
+    pub fn new(feeds: &[Feed], mappings: &[FeedMapping], categories: &[Category]) -> Self {
+        let mut category_map: HashMap<FeedID, Vec<String>> = HashMap::new();
+        for mapping in mappings {
+            if let Some(category) = categories.iter().find(|c| c.category_id == mapping.category_id) {
+                category_map
+                    .entry(mapping.feed_id.clone())
+                    .or_default()
+                    .push(category.label.clone());
+            }
+        }
+
+        FilterContext {
+            feeds: feeds.iter().map(|f| (f.feed_id.clone(), f.label.clone())).collect(),
+            categories: category_map,
+        }
+    }
+
+    pub fn load(news_flash: &NewsFlash) -> Result<Self, FilterRuleError> {
+        let (feeds, mappings) = news_flash.get_feeds().context(FilterRuleErrorKind::DataBase)?;
+        let categories = news_flash.get_categories().context(FilterRuleErrorKind::DataBase)?;
+        Ok(Self::new(&feeds, &mappings, &categories))
+    }
+
+    fn feed_label(&self, feed_id: &FeedID) -> &str {
+        self.feeds.get(feed_id).map(|label| label.as_str()).unwrap_or("")
+    }
+
+    fn category_labels(&self, feed_id: &FeedID) -> Vec<&str> {
+        self.categories
+            .get(feed_id)
+            .map(|labels| labels.iter().map(|label| label.as_str()).collect())
+            .unwrap_or_default()
+    }
+}
+
+pub struct FilterRules;
+
+impl FilterRules {
+    // rules with invalid patterns are skipped
+    pub fn compile(rules: &[FilterRule]) -> Vec<CompiledFilterRule> {
+        rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .filter_map(|rule| match rule.compile() {
+                Ok(compiled) => Some(compiled),
+                Err(error) => {
+                    warn!("Skipping filter rule '{}': {}", rule.name, error);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    pub fn is_hidden(rules: &[CompiledFilterRule], article: &Article, context: &FilterContext) -> bool {
+        rules
+            .iter()
+            .any(|rule| rule.rule.hides() && rule.matches(article, context))
+    }
+
+    pub fn test(news_flash: &NewsFlash, rule: &FilterRule) -> Result<Vec<Article>, FilterRuleError> {
+        let compiled = rule.compile()?;
+        let context = FilterContext::load(news_flash)?;
+        let articles = Util::load_all_articles(news_flash, Self::article_filter(Some(Read::Unread)))
+            .context(FilterRuleErrorKind::DataBase)?;
+
+        Ok(articles
+            .into_iter()
+            .filter(|article| compiled.matches(article, &context))
+            .collect())
+    }
+
+    // only the articles added by a sync are looked at, articles that existed before are left alone
+    // so changes the user made to them aren't overridden
+    pub fn apply(
+        news_flash: &NewsFlash,
+        rules: &[FilterRule],
+        new_articles: &[Article],
+        client: &Client,
+    ) -> Result<usize, FilterRuleError> {
+        let rules = Self::compile(rules);
+        if !rules
+            .iter()
+            .any(|rule| rule.rule.actions.iter().any(|a| a != &FilterAction::Hide))
+        {
+            return Ok(0);
+        }
+
+        let articles: Vec<&Article> = new_articles
+            .iter()
+            .filter(|article| article.unread == Read::Unread)
+            .collect();
+        if articles.is_empty() {
+            return Ok(0);
+        }
+        let context = FilterContext::load(news_flash)?;
+        let tags = news_flash.get_tags().context(FilterRuleErrorKind::DataBase)?;
+
+        // articles already carrying the tags of the rules, one query per tag
+        let mut tagged: HashMap<&TagID, HashSet<ArticleID>> = HashMap::new();
+        for tag_id in rules
+            .iter()
+            .flat_map(|rule| rule.rule.actions.iter())
+            .filter_map(|action| match action {
+                FilterAction::Tag(tag_id) => Some(tag_id),
+                _ => None,
+            })
+        {
+            if !tagged.contains_key(tag_id) {
+                let mut filter = Self::article_filter(None);
+                filter.tag = Some(tag_id.clone());
+                let article_ids = Util::load_all_articles(news_flash, filter)
+                    .context(FilterRuleErrorKind::DataBase)?
+                    .into_iter()
+                    .map(|article| article.article_id)
+                    .collect();
+                tagged.insert(tag_id, article_ids);
+            }
+        }
+
+        let mut mark_read: Vec<ArticleID> = Vec::new();
+        let mut star: Vec<ArticleID> = Vec::new();
+        let mut tag: Vec<(Article, Tag)> = Vec::new();
+
+        for article in articles {
+            for rule in rules.iter().filter(|rule| rule.matches(article, &context)) {
+                for action in &rule.rule.actions {
+                    match action {
+                        FilterAction::MarkRead => {
+                            if !mark_read.contains(&article.article_id) {
+                                mark_read.push(article.article_id.clone());
+                            }
+                        }
+                        FilterAction::Star => {
+                            if article.marked == Marked::Unmarked && !star.contains(&article.article_id) {
+                                star.push(article.article_id.clone());
+                            }
+                        }
+                        FilterAction::Tag(tag_id) => {
+                            let already_tagged = tagged
+                                .get(tag_id)
+                                .map(|article_ids| article_ids.contains(&article.article_id))
+                                .unwrap_or(false);
+                            let queued = tag
+                                .iter()
+                                .any(|(a, t)| a.article_id == article.article_id && &t.tag_id == tag_id);
+                            if !already_tagged && !queued {
+                                match tags.iter().find(|t| &t.tag_id == tag_id) {
+                                    Some(t) => tag.push((article.clone(), t.clone())),
+                                    None => warn!("Filter rule '{}': tag '{}' not found", rule.rule.name, tag_id),
+                                }
+                            }
+                        }
+                        FilterAction::Hide => {}
+                    }
+                }
+            }
+        }
+
+        let modified: HashSet<&ArticleID> = mark_read
+            .iter()
+            .chain(star.iter())
+            .chain(tag.iter().map(|(article, _tag)| &article.article_id))
+            .collect();
+        let modified = modified.len();
+
+        let mut runtime = Runtime::new().expect(RUNTIME_ERROR);
+        if !mark_read.is_empty() {
+            runtime
+                .block_on(news_flash.set_article_read(&mark_read, Read::Read, client))
+                .context(FilterRuleErrorKind::Backend)?;
+        }
+        if !star.is_empty() {
+            runtime
+                .block_on(news_flash.set_article_marked(&star, Marked::Marked, client))
+                .context(FilterRuleErrorKind::Backend)?;
+        }
+        for (article, tag) in &tag {
+            runtime
+                .block_on(news_flash.tag_article(article, tag, client))
+                .context(FilterRuleErrorKind::Backend)?;
+        }
+
+        info!("Filter rules modified {} articles", modified);
+        Ok(modified)
+    }
+
+    fn article_filter(unread: Option<Read>) -> ArticleFilter<'static> {
+        ArticleFilter {
+            limit: None,
+            offset: None,
+            order: None,
+            unread,
+            marked: None,
+            feed: None,
+            feed_blacklist: None,
+            category: None,
+            category_blacklist: None,
+            tag: None,
+            ids: None,
+            newer_than: None,
+            older_than: None,
+            search_term: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FilterAction, FilterField, FilterRule, FilterRuleErrorKind};
+
+    fn rule(pattern: &str, regex: bool) -> FilterRule {
+        FilterRule {
+            name: "test".to_owned(),
+            enabled: true,
+            field: FilterField::Title,
+            pattern: pattern.to_owned(),
+            regex,
+            actions: vec![FilterAction::Hide],
+        }
+    }
+
+    #[test]
+    fn substring_is_case_insensitive() {
+        let compiled = rule("Sponsored", false).compile().unwrap();
+        assert!(compiled.matches_values(&["This post is sponsored by"]));
+        assert!(!compiled.matches_values(&["Unrelated"]));
+        assert!(!rule("", false).compile().unwrap().matches_values(&["anything"]));
+    }
+
+    #[test]
+    fn regex_matching() {
+        let compiled = rule(r"^\[ad\]", true).compile().unwrap();
+        assert!(compiled.matches_values(&["[AD] buy now"]));
+        assert!(!compiled.matches_values(&["no [ad] at start"]));
+        assert!(compiled.matches_values(&["first", "[ad] second value"]));
+    }
+
+    #[test]
+    fn invalid_regex() {
+        match rule("(unclosed", true).compile() {
+            Err(error) => match error.kind() {
+                FilterRuleErrorKind::InvalidRegex(_) => {}
+                kind => panic!("unexpected error kind {:?}", kind),
+            },
+            Ok(_) => panic!("invalid regex compiled"),
+        }
+    }
+}
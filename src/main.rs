@@ -5,33 +5,44 @@ mod add_dialog;
 mod app;
 mod article_list;
 mod article_view;
+mod cli;
 mod color;
 mod config;
 mod content_page;
+mod dbus;
 mod discover;
+mod enclosures;
 mod error_bar;
 mod error_dialog;
+mod export;
+mod filter_rules;
 mod i18n;
 mod login_screen;
 mod main_window;
 mod main_window_state;
+mod offline;
+mod opml_import;
 mod rename_dialog;
 mod reset_page;
 mod responsive;
 mod settings;
 mod sidebar;
+mod smart_folders;
 mod tag_popover;
 mod undo_bar;
 mod util;
 mod welcome_screen;
 
 use crate::app::App;
+use crate::cli::CommandLine;
 use crate::config::APP_ID;
 use log::LevelFilter;
-use log4rs::append::console::ConsoleAppender;
+use log4rs::append::console::{ConsoleAppender, Target};
 use log4rs::config::{Appender, Config, Root};
 use log4rs::encode::pattern::PatternEncoder;
 use rust_embed::RustEmbed;
+use std::env;
+use std::process;
 use std::str;
 
 #[derive(RustEmbed)]
@@ -42,17 +53,38 @@ fn main() {
     // nicer backtrace
     color_backtrace::install();
 
-    // Logging
+    // command line interface: only handle known subcommands, everything else goes to gtk
+    let args: Vec<String> = env::args().collect();
+    let command_line = CommandLine::parse(&args);
+
+    // Logging (keep stdout clean for command line output)
+    let (target, level) = match command_line {
+        Some(_) => (Target::Stderr, LevelFilter::Warn),
+        None => (Target::Stdout, LevelFilter::Info),
+    };
     let encoder = PatternEncoder::new("{d(%H:%M:%S)} - {h({({l}):5.5})} - {m:<35.} (({M}:{L}))\n");
-    let stdout = ConsoleAppender::builder().encoder(Box::new(encoder)).build();
+    let stdout = ConsoleAppender::builder()
+        .encoder(Box::new(encoder))
+        .target(target)
+        .build();
     let appender = Appender::builder().build("stdout", Box::new(stdout));
-    let root = Root::builder().appender("stdout").build(LevelFilter::Info);
+    let root = Root::builder().appender("stdout").build(level);
     let config = Config::builder()
         .appender(appender)
         .build(root)
         .expect("Failed to create log4rs config.");
     let _handle = log4rs::init_config(config).expect("Failed to init log4rs config.");
 
+    // Headless commands: no display required
+    match command_line {
+        Some(Ok(command_line)) => process::exit(command_line.run()),
+        Some(Err(error)) => {
+            eprintln!("Error: {}\n\n{}", error, CommandLine::usage());
+            process::exit(2);
+        }
+        None => {}
+    }
+
     // Gtk setup
     gtk::init().expect("Error initializing gtk.");
     glib::set_application_name("NewsFlash");
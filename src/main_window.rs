@@ -1,18 +1,22 @@
 use crate::about_dialog::APP_NAME;
 use crate::app::{Action, App};
 use crate::config::{APP_ID, PROFILE};
-use crate::content_page::{ContentHeader, ContentPage, HeaderSelection};
+use crate::content_page::{ContentHeader, ContentPage, DateRange, HeaderSelection, SearchQuery};
+use crate::enclosures::{Enclosure, MediaArticles};
 use crate::error_bar::ErrorBar;
 use crate::login_screen::{LoginHeaderbar, PasswordLogin, WebLogin};
 use crate::main_window_state::MainWindowState;
+use crate::offline::{OfflineCache, OfflineChange, OfflineQueue};
 use crate::reset_page::ResetPage;
 use crate::responsive::ResponsiveLayout;
 use crate::settings::{Keybindings, Settings};
 use crate::sidebar::models::SidebarSelection;
+use crate::smart_folders::SmartFolder;
 use crate::undo_bar::{UndoActionModel, UndoBar};
 use crate::util::{BuilderHelper, GtkUtil, Util, CHANNEL_ERROR, GTK_CSS_ERROR, GTK_RESOURCE_FILE_ERROR, RUNTIME_ERROR};
 use crate::welcome_screen::{WelcomeHeaderbar, WelcomePage};
 use crate::Resources;
+use chrono::{DateTime, TimeZone, Utc};
 use futures::channel::oneshot;
 use futures::executor::ThreadPool;
 use futures::FutureExt;
@@ -25,7 +29,8 @@ use gtk::{
 };
 use log::{error, warn};
 use news_flash::models::{
-    ArticleID, FatArticle, Feed, PasswordLogin as PasswordLoginData, PluginCapabilities, PluginID,
+    ArticleFilter, ArticleID, FatArticle, Feed, FeedID, PasswordLogin as PasswordLoginData, PluginCapabilities,
+    PluginID, Read,
 };
 use news_flash::{NewsFlash, NewsFlashError};
 use parking_lot::RwLock;
@@ -47,6 +52,7 @@ pub struct MainWindow {
     header_stack: Stack,
     responsive_layout: Arc<ResponsiveLayout>,
     pub state: Arc<RwLock<MainWindowState>>,
+    settings: Arc<RwLock<Settings>>,
     sender: Sender<Action>,
 }
 
@@ -56,6 +62,7 @@ impl MainWindow {
         sender: Sender<Action>,
         shutdown_in_progress: Arc<RwLock<bool>>,
         features: &Arc<RwLock<Option<PluginCapabilities>>>,
+        offline_queue: &Arc<RwLock<OfflineQueue>>,
     ) -> Self {
         GtkUtil::register_symbolic_icons();
         let css_provider = Arc::new(RwLock::new(CssProvider::new()));
@@ -79,7 +86,8 @@ impl MainWindow {
 
         let _login_header = LoginHeaderbar::new(&builder, sender.clone());
         let _welcome_header = WelcomeHeaderbar::new(&builder);
-        let content_header = Arc::new(ContentHeader::new(&builder, &state, sender.clone(), features));
+        let content_header = Arc::new(ContentHeader::new(&builder, &state, settings, sender.clone(), features));
+        content_header.set_mark_older_than_days(settings.read().get_mark_older_than_days());
 
         window.set_icon_name(Some(APP_ID));
         window.set_title(APP_NAME);
@@ -98,6 +106,7 @@ impl MainWindow {
             &content_header,
             sender.clone(),
             features,
+            offline_queue,
         ));
         let reset_page = ResetPage::new(&builder, sender.clone());
 
@@ -119,6 +128,7 @@ impl MainWindow {
                         // Workaround SINGLE selection mode of listbox
                         content_page.sidebar.read().feed_list.read().on_window_hidden();
                         content_page.sidebar.read().tag_list.read().on_window_hidden();
+                        content_page.sidebar.read().smart_folder_list.read().on_window_hidden();
                     } else {
                         Util::send(&sender, Action::QueueQuit);
                     }
@@ -171,6 +181,7 @@ impl MainWindow {
             header_stack,
             responsive_layout,
             state,
+            settings: settings.clone(),
             sender,
         }
     }
@@ -241,6 +252,21 @@ impl MainWindow {
                 return Inhibit(true);
             }
 
+            // check redo first: its default binding is a superset of the undo modifiers
+            if Self::check_shortcut("redo", &settings, event) {
+                if !state.read().get_offline() {
+                    Util::send(&sender, Action::Redo);
+                }
+                return Inhibit(true);
+            }
+
+            if Self::check_shortcut("undo", &settings, event) {
+                if !state.read().get_offline() {
+                    Util::send(&sender, Action::Undo);
+                }
+                return Inhibit(true);
+            }
+
             if Self::check_shortcut("next_article", &settings, event) {
                 Util::send(&sender, Action::SelectNextArticle);
                 return Inhibit(true);
@@ -256,17 +282,14 @@ impl MainWindow {
                 return Inhibit(true);
             }
 
+            // queued while offline
             if Self::check_shortcut("toggle_read", &settings, event) {
-                if !state.read().get_offline() {
-                    Util::send(&sender, Action::ToggleArticleRead);
-                }
+                Util::send(&sender, Action::ToggleArticleRead);
                 return Inhibit(true);
             }
 
             if Self::check_shortcut("toggle_marked", &settings, event) {
-                if !state.read().get_offline() {
-                    Util::send(&sender, Action::ToggleArticleMarked);
-                }
+                Util::send(&sender, Action::ToggleArticleMarked);
                 return Inhibit(true);
             }
 
@@ -317,13 +340,19 @@ impl MainWindow {
                 return Inhibit(true);
             }
 
-            if Self::check_shortcut("sidebar_set_read", &settings, event) {
+            // check first: its default binding is a superset of the 'sidebar_set_read' modifiers
+            if Self::check_shortcut("sidebar_set_older_read", &settings, event) {
                 if !state.read().get_offline() {
-                    Util::send(&sender, Action::SetSidebarRead);
+                    content_header.show_mark_older_popover();
                 }
                 return Inhibit(true);
             }
 
+            if Self::check_shortcut("sidebar_set_read", &settings, event) {
+                Util::send(&sender, Action::SetSidebarRead);
+                return Inhibit(true);
+            }
+
             Inhibit(false)
         }));
     }
@@ -434,6 +463,7 @@ impl MainWindow {
                 UndoActionModel::DeleteTag(delete_id, _label) => &selected_id == delete_id,
                 _ => false,
             },
+            SidebarSelection::SmartFolder(_id, _label) => false,
         };
         if select_all_button {
             self.state.write().set_sidebar_selection(SidebarSelection::All);
@@ -554,12 +584,18 @@ impl MainWindow {
     ) {
         let mut fat_article: Option<FatArticle> = None;
         let mut feed_vec: Option<Vec<Feed>> = None;
+        let mut enclosures: Vec<Enclosure> = Vec::new();
 
         self.state.write().set_prefer_scraped_content(true);
 
         if let Some(news_flash) = news_flash.read().as_ref() {
             match news_flash.get_fat_article(&article_id) {
-                Ok(article) => fat_article = Some(article),
+                Ok(mut article) => {
+                    enclosures = Enclosure::from_article(&article);
+                    // prefer the copy with embedded images if the article was prepared for offline reading
+                    OfflineCache::new().apply(&mut article);
+                    fat_article = Some(article);
+                }
                 Err(error) => {
                     Util::send(&self.sender, Action::Error("Failed to read article.".to_owned(), error));
                     return;
@@ -586,7 +622,20 @@ impl MainWindow {
                         return;
                     }
                 };
+                if self
+                    .settings
+                    .read()
+                    .get_feed_view_settings(&feed.feed_id)
+                    .open_in_browser
+                {
+                    if let Some(url) = &article.url {
+                        Util::send(&self.sender, Action::OpenUrlInDefaultBrowser(url.get().to_string()));
+                        return;
+                    }
+                }
+
                 self.content_header.show_article(Some(&article), news_flash, features);
+                self.content_page.article_view.set_enclosures(enclosures);
                 self.content_page.article_view.show_article(article, feed.label.clone());
 
                 self.responsive_layout.state.write().major_leaflet_selected = true;
@@ -616,10 +665,16 @@ impl MainWindow {
             },
         };
         if update_sidebar {
-            Util::send(&self.sender, Action::UpdateSidebar);
+            Util::send(&self.sender, Action::RefreshSidebar);
         }
     }
 
+    pub fn set_date_range(&self, date_range: DateRange) {
+        self.content_header.set_date_range(&date_range);
+        self.state.write().set_date_range(date_range);
+        Util::send(&self.sender, Action::UpdateArticleList);
+    }
+
     pub fn set_search_term(&self, search_term: String) {
         if search_term.is_empty() {
             self.state.write().set_search_term(None);
@@ -636,163 +691,197 @@ impl MainWindow {
         threadpool: ThreadPool,
         settings: Arc<RwLock<Settings>>,
     ) {
-        let sidebar_selection = self.state.read().get_sidebar_selection().clone();
-
-        match sidebar_selection {
-            SidebarSelection::All => {
-                let (sender, receiver) = oneshot::channel::<Result<(), NewsFlashError>>();
-
-                let news_flash = news_flash.clone();
-                let thread_future = async move {
-                    let news_flash = news_flash.clone();
-                    let future = async move {
-                        if let Some(news_flash) = news_flash.read().as_ref() {
-                            sender
-                                .send(news_flash.set_all_read(&App::build_client(&settings)).await)
-                                .expect(CHANNEL_ERROR);
-                        }
-                    };
-                    Runtime::new().expect(RUNTIME_ERROR).block_on(future);
-                };
+        let selection = self.state.read().get_sidebar_selection().clone();
+        let smart_folder = match &selection {
+            SidebarSelection::SmartFolder(id, _title) => match settings.read().get_smart_folder(id) {
+                Some(folder) => Some(folder),
+                None => {
+                    self.content_header.finish_mark_all_read();
+                    return;
+                }
+            },
+            _ => None,
+        };
+        let (sender, receiver) = oneshot::channel::<Result<Vec<ArticleID>, NewsFlashError>>();
 
-                let glib_future = receiver.map(clone!(
-                    @strong self.sender as sender,
-                    @weak self.content_header as content_header => @default-panic, move |res|
-                {
-                    content_header.finish_mark_all_read();
-                    res.map(|result| match result {
-                        Ok(_) => {}
-                        Err(error) => {
-                            let message = "Failed to mark all read".to_owned();
-                            error!("{}", message);
-                            Util::send(&sender, Action::Error(message, error));
-                        }
-                    })
-                    .expect(CHANNEL_ERROR);
-                    Util::send(&sender, Action::UpdateArticleHeader);
-                    Util::send(&sender, Action::UpdateArticleList);
-                    Util::send(&sender, Action::UpdateSidebar);
-                }));
-
-                threadpool.spawn_ok(thread_future);
-                Util::glib_spawn_future(glib_future);
-            }
-            SidebarSelection::Category(category_id, _title) => {
-                let (sender, receiver) = oneshot::channel::<Result<(), NewsFlashError>>();
-
-                let news_flash = news_flash.clone();
-                let category_id_vec = vec![category_id];
-                let thread_future = async move {
-                    if let Some(news_flash) = news_flash.read().as_ref() {
-                        sender
-                            .send(Runtime::new().expect(RUNTIME_ERROR).block_on(
-                                news_flash.set_category_read(&category_id_vec, &App::build_client(&settings)),
-                            ))
-                            .expect(CHANNEL_ERROR);
+        let offline = self.state.read().get_offline();
+        let news_flash = news_flash.clone();
+        let media_articles = self.content_page.media_articles.clone();
+        let undo_selection = selection.clone();
+        let thread_future = async move {
+            if let Some(news_flash) = news_flash.read().as_ref() {
+                let future = async {
+                    // remember the affected articles so marking them read can be undone
+                    let article_ids =
+                        Self::load_unread_article_ids(news_flash, &selection, smart_folder, None, &media_articles)?;
+                    if offline {
+                        return Ok(article_ids);
+                    }
+                    // mark exactly the loaded articles, so articles arriving in the meantime
+                    // aren't marked read without being part of the undo
+                    if !article_ids.is_empty() {
+                        let client = App::build_client(&settings);
+                        news_flash.set_article_read(&article_ids, Read::Read, &client).await?;
                     }
+                    Ok(article_ids)
                 };
-
-                let glib_future = receiver.map(clone!(
-                    @strong self.sender as sender,
-                    @weak self.content_header as content_header => @default-panic, move |res|
-                {
-                    content_header.finish_mark_all_read();
-                    res.map(|result| match result {
-                        Ok(_) => {}
-                        Err(error) => {
-                            let message = "Failed to mark all read".to_owned();
-                            error!("{}", message);
-                            Util::send(&sender, Action::Error(message, error));
-                        }
-                    })
+                sender
+                    .send(Runtime::new().expect(RUNTIME_ERROR).block_on(future))
                     .expect(CHANNEL_ERROR);
-                    Util::send(&sender, Action::UpdateArticleHeader);
-                    Util::send(&sender, Action::UpdateArticleList);
-                    Util::send(&sender, Action::UpdateSidebar);
-                }));
-
-                threadpool.spawn_ok(thread_future);
-                Util::glib_spawn_future(glib_future);
-            }
-            SidebarSelection::Feed(feed_id, _parent_id, _title) => {
-                let (sender, receiver) = oneshot::channel::<Result<(), NewsFlashError>>();
-
-                let news_flash = news_flash.clone();
-                let feed_id_vec = vec![feed_id];
-                let thread_future = async move {
-                    if let Some(news_flash) = news_flash.read().as_ref() {
-                        sender
-                            .send(
-                                Runtime::new()
-                                    .expect(RUNTIME_ERROR)
-                                    .block_on(news_flash.set_feed_read(&feed_id_vec, &App::build_client(&settings))),
-                            )
-                            .expect(CHANNEL_ERROR);
+            }
+        };
+
+        let glib_future = receiver.map(clone!(
+            @strong self.sender as sender,
+            @weak self.content_header as content_header => @default-panic, move |res|
+        {
+            content_header.finish_mark_all_read();
+            res.map(|result| match result {
+                Ok(article_ids) => {
+                    if offline {
+                        let changes = article_ids
+                            .into_iter()
+                            .map(|article_id| OfflineChange::read(article_id, Read::Read, Read::Unread))
+                            .collect();
+                        Util::send(&sender, Action::QueueOfflineChanges(changes));
+                    } else if !article_ids.is_empty() {
+                        Util::send(
+                            &sender,
+                            Action::UndoableAction(UndoActionModel::MarkAllRead(undo_selection, article_ids)),
+                        );
                     }
+                }
+                Err(error) => {
+                    let message = "Failed to mark all read".to_owned();
+                    error!("{}", message);
+                    Util::send(&sender, Action::Error(message, error));
+                }
+            })
+            .expect(CHANNEL_ERROR);
+            Util::send(&sender, Action::UpdateArticleHeader);
+            Util::send(&sender, Action::UpdateArticleList);
+            Util::send(&sender, Action::UpdateSidebar);
+        }));
+
+        threadpool.spawn_ok(thread_future);
+        Util::glib_spawn_future(glib_future);
+    }
+
+    fn load_unread_article_ids(
+        news_flash: &NewsFlash,
+        selection: &SidebarSelection,
+        smart_folder: Option<SmartFolder>,
+        older_than: Option<DateTime<Utc>>,
+        media_articles: &RwLock<MediaArticles>,
+    ) -> Result<Vec<ArticleID>, NewsFlashError> {
+        let mut filter = ArticleFilter {
+            limit: None,
+            offset: None,
+            order: None,
+            unread: Some(Read::Unread),
+            marked: None,
+            feed: None,
+            feed_blacklist: None,
+            category: None,
+            category_blacklist: None,
+            tag: None,
+            ids: None,
+            newer_than: None,
+            older_than,
+            search_term: None,
+        };
+        match selection {
+            SidebarSelection::All => {}
+            SidebarSelection::Category(category_id, _title) => filter.category = Some(category_id.clone()),
+            SidebarSelection::Feed(feed_id, _parent_id, _title) => filter.feed = Some(feed_id.clone()),
+            SidebarSelection::Tag(tag_id, _title) => filter.tag = Some(tag_id.clone()),
+            SidebarSelection::SmartFolder(_id, _title) => {
+                let folder = match smart_folder {
+                    Some(folder) => folder,
+                    None => return Ok(Vec::new()),
+                };
+                let (feeds, mappings) = news_flash.get_feeds()?;
+                let categories = news_flash.get_categories()?;
+                let feed_ids: Vec<FeedID> = feeds.into_iter().map(|feed| feed.feed_id).collect();
+                let feed_blacklist = folder.feed_blacklist(&feed_ids, &mappings, &categories);
+                let folder_filter = folder.article_filter(&HeaderSelection::Unread, None, feed_blacklist);
+                let (newer_than, older_than) =
+                    DateRange::intersect((folder_filter.newer_than, folder_filter.older_than), (None, older_than));
+                filter = ArticleFilter {
+                    newer_than,
+                    older_than,
+                    ..folder_filter
                 };
+            }
+        }
 
-                let glib_future = receiver.map(clone!(
-                    @strong self.sender as sender,
-                    @weak self.content_header as content_header => @default-panic, move |res|
-                {
-                    content_header.finish_mark_all_read();
-                    res.map(|result| match result {
-                        Ok(_) => {}
-                        Err(error) => {
-                            let message = "Failed to mark all read".to_owned();
-                            error!("{}", message);
-                            Util::send(&sender, Action::Error(message, error));
-                        }
-                    })
-                    .expect(CHANNEL_ERROR);
-                    Util::send(&sender, Action::UpdateArticleHeader);
-                    Util::send(&sender, Action::UpdateArticleList);
-                    Util::send(&sender, Action::UpdateSidebar);
-                }));
-
-                threadpool.spawn_ok(thread_future);
-                Util::glib_spawn_future(glib_future);
-            }
-            SidebarSelection::Tag(tag_id, _title) => {
-                let (sender, receiver) = oneshot::channel::<Result<(), NewsFlashError>>();
-
-                let news_flash = news_flash.clone();
-                let tag_id_vec = vec![tag_id];
-                let thread_future = async move {
-                    if let Some(news_flash) = news_flash.read().as_ref() {
-                        sender
-                            .send(
-                                Runtime::new()
-                                    .expect(RUNTIME_ERROR)
-                                    .block_on(news_flash.set_tag_read(&tag_id_vec, &App::build_client(&settings))),
-                            )
-                            .expect(CHANNEL_ERROR);
+        Ok(SearchQuery::load_articles(news_flash, filter, media_articles)?
+            .into_iter()
+            .map(|article| article.article_id)
+            .collect())
+    }
+
+    pub fn mark_older_read(
+        &self,
+        selection: SidebarSelection,
+        days: u32,
+        timestamp: i64,
+        news_flash: &Arc<RwLock<Option<NewsFlash>>>,
+        threadpool: ThreadPool,
+        settings: Arc<RwLock<Settings>>,
+    ) {
+        let undo_action = UndoActionModel::MarkOlderRead(selection.clone(), days, timestamp);
+        let processing_actions = self.undo_bar.processing_actions();
+        let older_than = Utc.timestamp(timestamp, 0);
+        let smart_folder = match &selection {
+            SidebarSelection::SmartFolder(id, _title) => settings.read().get_smart_folder(id),
+            _ => None,
+        };
+        let (sender, receiver) = oneshot::channel::<Result<(), NewsFlashError>>();
+
+        let news_flash = news_flash.clone();
+        let media_articles = self.content_page.media_articles.clone();
+        let thread_future = async move {
+            if let Some(news_flash) = news_flash.read().as_ref() {
+                let future = async {
+                    let article_ids = Self::load_unread_article_ids(
+                        news_flash,
+                        &selection,
+                        smart_folder,
+                        Some(older_than),
+                        &media_articles,
+                    )?;
+                    if article_ids.is_empty() {
+                        return Ok(());
                     }
+                    news_flash
+                        .set_article_read(&article_ids, Read::Read, &App::build_client(&settings))
+                        .await
                 };
-
-                let glib_future = receiver.map(clone!(
-                    @strong self.sender as sender,
-                    @weak self.content_header as content_header => @default-panic, move |res|
-                {
-                    content_header.finish_mark_all_read();
-                    res.map(|result| match result {
-                        Ok(_) => {}
-                        Err(error) => {
-                            let message = "Failed to mark all read".to_owned();
-                            error!("{}", message);
-                            Util::send(&sender, Action::Error(message, error));
-                        }
-                    })
+                sender
+                    .send(Runtime::new().expect(RUNTIME_ERROR).block_on(future))
                     .expect(CHANNEL_ERROR);
-                    Util::send(&sender, Action::UpdateArticleHeader);
-                    Util::send(&sender, Action::UpdateArticleList);
-                    Util::send(&sender, Action::UpdateSidebar);
-                }));
-
-                threadpool.spawn_ok(thread_future);
-                Util::glib_spawn_future(glib_future);
             }
-        }
+        };
+
+        let glib_future = receiver.map(clone!(@strong self.sender as sender => @default-panic, move |res| {
+            processing_actions.write().remove(&undo_action);
+            res.map(|result| match result {
+                Ok(_) => {}
+                Err(error) => {
+                    let message = "Failed to mark older articles read".to_owned();
+                    error!("{}", message);
+                    Util::send(&sender, Action::Error(message, error));
+                }
+            })
+            .expect(CHANNEL_ERROR);
+            Util::send(&sender, Action::UpdateArticleHeader);
+            Util::send(&sender, Action::UpdateArticleList);
+            Util::send(&sender, Action::UpdateSidebar);
+        }));
+
+        threadpool.spawn_ok(thread_future);
+        Util::glib_spawn_future(glib_future);
     }
 
     pub fn update_article_header(
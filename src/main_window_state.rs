@@ -1,4 +1,4 @@
-use crate::content_page::HeaderSelection;
+use crate::content_page::{DateRange, HeaderSelection};
 use crate::sidebar::models::SidebarSelection;
 
 #[derive(Clone, Debug)]
@@ -6,7 +6,10 @@ pub struct MainWindowState {
     sidebar: SidebarSelection,
     header: HeaderSelection,
     search_term: Option<String>,
+    date_range: DateRange,
     reset_article_list: bool,
+    // can be more than the listed articles if some were left out by conditions the database can't check
+    article_list_offset: i64,
     offline: bool,
     prefer_scraped_content: bool,
 }
@@ -19,7 +22,9 @@ impl MainWindowState {
             sidebar: SidebarSelection::All,
             header: HeaderSelection::All,
             search_term: None,
+            date_range: DateRange::All,
             reset_article_list: false,
+            article_list_offset: 0,
             offline: false,
             prefer_scraped_content: false,
         }
@@ -35,6 +40,14 @@ impl MainWindowState {
         reset_article_list
     }
 
+    pub fn get_article_list_offset(&self) -> i64 {
+        self.article_list_offset
+    }
+
+    pub fn set_article_list_offset(&mut self, offset: i64) {
+        self.article_list_offset = offset;
+    }
+
     pub fn get_sidebar_selection(&self) -> &SidebarSelection {
         &self.sidebar
     }
@@ -63,6 +76,15 @@ impl MainWindowState {
         self.reset_article_list = true;
     }
 
+    pub fn get_date_range(&self) -> &DateRange {
+        &self.date_range
+    }
+
+    pub fn set_date_range(&mut self, date_range: DateRange) {
+        self.date_range = date_range;
+        self.reset_article_list = true;
+    }
+
     pub fn set_offline(&mut self, offline: bool) {
         self.offline = offline;
     }
@@ -88,6 +110,9 @@ impl PartialEq for MainWindowState {
         if self.header != other.header {
             return false;
         }
+        if self.date_range != other.date_range {
+            return false;
+        }
         match &self.search_term {
             Some(self_search_term) => match &other.search_term {
                 Some(other_search_term) => {
@@ -0,0 +1,141 @@
+use super::error::{OfflineError, OfflineErrorKind};
+use crate::app::DATA_DIR;
+use crate::util::FileUtil;
+use failure::ResultExt;
+use log::warn;
+use news_flash::models::{ArticleID, FatArticle};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CACHE_DIR: &str = "offline_cache";
+const DIGEST_CACHE_DIR: &str = "digest_cache";
+const HTML_SUFFIX: &str = "html";
+const SCRAPED_SUFFIX: &str = "scraped.html";
+
+// Every article is stored as up to two files named after the hashed article id:
+// the original html and the scraped content.
+pub struct OfflineCache {
+    path: PathBuf,
+}
+
+impl Default for OfflineCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OfflineCache {
+    pub fn new() -> Self {
+        OfflineCache {
+            path: DATA_DIR.join(CACHE_DIR),
+        }
+    }
+
+    // articles of the last digest, so rebuilding it doesn't download their images again
+    pub fn digest() -> Self {
+        OfflineCache {
+            path: DATA_DIR.join(DIGEST_CACHE_DIR),
+        }
+    }
+
+    fn file_name(article_id: &ArticleID, suffix: &str) -> String {
+        format!("{}.{}", FileUtil::hashed_file_name(&article_id.to_string()), suffix)
+    }
+
+    fn article_files(&self, article_id: &ArticleID) -> [PathBuf; 2] {
+        [
+            self.path.join(Self::file_name(article_id, HTML_SUFFIX)),
+            self.path.join(Self::file_name(article_id, SCRAPED_SUFFIX)),
+        ]
+    }
+
+    pub fn contains(&self, article_id: &ArticleID) -> bool {
+        self.path.join(Self::file_name(article_id, HTML_SUFFIX)).exists()
+    }
+
+    pub fn apply(&self, article: &mut FatArticle) {
+        let html = self.path.join(Self::file_name(&article.article_id, HTML_SUFFIX));
+        if let Ok(html) = fs::read_to_string(html) {
+            article.html = Some(html);
+        }
+        let scraped = self.path.join(Self::file_name(&article.article_id, SCRAPED_SUFFIX));
+        if let Ok(scraped) = fs::read_to_string(scraped) {
+            article.scraped_content = Some(scraped);
+        }
+    }
+
+    pub fn store(&self, article: &FatArticle) -> Result<(), OfflineError> {
+        fs::create_dir_all(&self.path).context(OfflineErrorKind::CreateDirectory)?;
+
+        let html = article.html.as_deref().unwrap_or_default();
+        fs::write(self.path.join(Self::file_name(&article.article_id, HTML_SUFFIX)), html)
+            .context(OfflineErrorKind::Write)?;
+        if let Some(scraped) = &article.scraped_content {
+            fs::write(
+                self.path.join(Self::file_name(&article.article_id, SCRAPED_SUFFIX)),
+                scraped,
+            )
+            .context(OfflineErrorKind::Write)?;
+        }
+        Ok(())
+    }
+
+    pub fn article_size(&self, article_id: &ArticleID) -> u64 {
+        self.article_files(article_id)
+            .iter()
+            .filter_map(|path| fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum()
+    }
+
+    pub fn remove(&self, article_id: &ArticleID) -> Result<(), OfflineError> {
+        for path in self.article_files(article_id).iter().filter(|path| path.exists()) {
+            fs::remove_file(path).context(OfflineErrorKind::Write)?;
+        }
+        Ok(())
+    }
+
+    pub fn retain(&self, article_ids: &[ArticleID]) -> Result<(), OfflineError> {
+        let keep: HashSet<PathBuf> = article_ids
+            .iter()
+            .flat_map(|article_id| self.article_files(article_id).to_vec())
+            .collect();
+        for (path, _size) in self.files()? {
+            if keep.contains(&path) {
+                continue;
+            }
+            if let Err(error) = fs::remove_file(&path) {
+                warn!("Failed to remove '{}' from offline cache: {}", path.display(), error);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn clear(&self) -> Result<(), OfflineError> {
+        if self.path.exists() {
+            fs::remove_dir_all(&self.path).context(OfflineErrorKind::Write)?;
+        }
+        Ok(())
+    }
+
+    fn files(&self) -> Result<Vec<(PathBuf, u64)>, OfflineError> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let entries = fs::read_dir(&self.path).context(OfflineErrorKind::Read)?;
+        Ok(entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                Some((entry.path(), metadata.len()))
+            })
+            .filter(|(path, _size)| Self::is_cache_file(path))
+            .collect())
+    }
+
+    fn is_cache_file(path: &Path) -> bool {
+        path.extension().map(|ext| ext == HTML_SUFFIX).unwrap_or(false)
+    }
+}
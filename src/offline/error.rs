@@ -0,0 +1,68 @@
+use failure::{Backtrace, Context, Error, Fail};
+use std::fmt;
+
+#[derive(Debug)]
+pub struct OfflineError {
+    inner: Context<OfflineErrorKind>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Fail)]
+pub enum OfflineErrorKind {
+    #[fail(display = "Failed to create the offline cache directory")]
+    CreateDirectory,
+    #[fail(display = "Failed to read from the offline cache")]
+    Read,
+    #[fail(display = "Failed to write to the offline cache")]
+    Write,
+    #[fail(display = "Offline queue is not valid json")]
+    InvalidJsonContent,
+    #[fail(display = "Failed to download images")]
+    ImageDownload,
+    #[fail(display = "Unknown Error")]
+    Unknown,
+}
+
+impl Fail for OfflineError {
+    fn cause(&self) -> Option<&dyn Fail> {
+        self.inner.cause()
+    }
+
+    fn backtrace(&self) -> Option<&Backtrace> {
+        self.inner.backtrace()
+    }
+}
+
+impl fmt::Display for OfflineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, f)
+    }
+}
+
+impl OfflineError {
+    #[allow(dead_code)]
+    pub fn kind(&self) -> OfflineErrorKind {
+        *self.inner.get_context()
+    }
+}
+
+impl From<OfflineErrorKind> for OfflineError {
+    fn from(kind: OfflineErrorKind) -> OfflineError {
+        OfflineError {
+            inner: Context::new(kind),
+        }
+    }
+}
+
+impl From<Context<OfflineErrorKind>> for OfflineError {
+    fn from(inner: Context<OfflineErrorKind>) -> OfflineError {
+        OfflineError { inner }
+    }
+}
+
+impl From<Error> for OfflineError {
+    fn from(_: Error) -> OfflineError {
+        OfflineError {
+            inner: Context::new(OfflineErrorKind::Unknown),
+        }
+    }
+}
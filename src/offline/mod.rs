@@ -0,0 +1,11 @@
+mod cache;
+mod error;
+mod prepare;
+mod queue;
+mod replay;
+mod scrape;
+
+pub use self::cache::OfflineCache;
+pub use self::prepare::OfflinePreparer;
+pub use self::queue::{OfflineChange, OfflineQueue};
+pub use self::scrape::SyncScraper;
@@ -0,0 +1,359 @@
+use super::cache::OfflineCache;
+use super::error::{OfflineError, OfflineErrorKind};
+use crate::app::DATA_DIR;
+use crate::settings::{OfflineArticles, OfflineSettings};
+use crate::util::RUNTIME_ERROR;
+use article_scraper::ArticleScraper;
+use failure::ResultExt;
+use log::{error, info, warn};
+use news_flash::models::{Article, ArticleFilter, ArticleID, ArticleOrder, FatArticle, Marked, Read};
+use news_flash::{NewsFlash, NewsFlashError};
+use parking_lot::RwLock;
+use reqwest::Client;
+use std::collections::HashSet;
+use tokio::runtime::Runtime;
+
+// same directory news_flash keeps the scraper configs in
+const SCRAPER_DATA_DIR: &str = "scraper_data";
+// upper bound of articles that are considered when preparing for offline reading
+const MAX_OFFLINE_ARTICLES: i64 = 500;
+
+// Grabs content and images for the offline cache without writing them to the database,
+// so the cache budget covers everything that was downloaded.
+pub struct OfflinePreparer {
+    scraper: ArticleScraper,
+}
+
+impl Default for OfflinePreparer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OfflinePreparer {
+    pub fn new() -> Self {
+        OfflinePreparer {
+            scraper: ArticleScraper::new(DATA_DIR.join(SCRAPER_DATA_DIR)),
+        }
+    }
+
+    pub async fn prepare(
+        &self,
+        mut article: FatArticle,
+        scrape: bool,
+        client: &Client,
+    ) -> Result<FatArticle, OfflineError> {
+        if scrape && article.scraped_content.is_none() {
+            if let Some(url) = &article.url {
+                match self.scraper.parse(url.get(), false, client).await {
+                    Ok(scraped) => article.scraped_content = scraped.html,
+                    Err(error) => warn!("Failed to scrape article '{}': {}", article.article_id, error),
+                }
+            }
+        }
+
+        let image_downloader = &self.scraper.image_downloader;
+        if let Some(scraped_content) = &article.scraped_content {
+            let scraped_content = image_downloader
+                .download_images_from_string(scraped_content, client)
+                .await
+                .context(OfflineErrorKind::ImageDownload)?;
+            article.scraped_content = Some(scraped_content);
+        } else if let Some(html) = &article.html {
+            let html = image_downloader
+                .download_images_from_string(html, client)
+                .await
+                .context(OfflineErrorKind::ImageDownload)?;
+            article.html = Some(html);
+        }
+        Ok(article)
+    }
+
+    // Fill the offline cache with unread and/or starred articles until its size budget is used up.
+    // Stops early once `keep_going` returns false, `progress` gets the number of prepared articles.
+    pub fn fill_cache<K, P>(
+        &self,
+        news_flash: &RwLock<Option<NewsFlash>>,
+        offline_settings: &OfflineSettings,
+        client: &Client,
+        keep_going: K,
+        progress: P,
+    ) -> Result<(), OfflineError>
+    where
+        K: Fn() -> bool,
+        P: Fn(usize, usize),
+    {
+        let cache = OfflineCache::new();
+        let budget = u64::from(offline_settings.budget) * 1024 * 1024;
+        let candidates = match news_flash.read().as_ref() {
+            Some(news_flash) => {
+                Self::load_candidates(news_flash, offline_settings.articles).context(OfflineErrorKind::Read)?
+            }
+            None => return Ok(()),
+        };
+
+        // articles that were read or unstarred in the meantime make room first
+        if let Err(error) = cache.retain(&candidates) {
+            error!("Failed to clean up offline cache: {}", error);
+        }
+
+        // candidates are ordered by priority, the index is the priority of an article
+        let mut cached = Vec::new();
+        let mut missing: Vec<(usize, &ArticleID)> = Vec::new();
+        for (priority, article_id) in candidates.iter().enumerate() {
+            if cache.contains(article_id) {
+                cached.push((priority, article_id.clone(), cache.article_size(article_id)));
+            } else {
+                missing.push((priority, article_id));
+            }
+        }
+        let mut cache_budget = CacheBudget::new(budget, cached);
+
+        let total = missing.len();
+        if total > 0 {
+            info!("Preparing {} articles for offline reading", total);
+            progress(0, total);
+        }
+
+        let mut runtime = Runtime::new().expect(RUNTIME_ERROR);
+        for (index, (priority, article_id)) in missing.into_iter().enumerate() {
+            if !keep_going() {
+                break;
+            }
+            // at least some room is needed before downloading anything
+            if !Self::make_room(&cache, &mut cache_budget, 1, priority) {
+                info!("Offline cache budget of {} MB reached", offline_settings.budget);
+                break;
+            }
+
+            let article = match news_flash.read().as_ref() {
+                Some(news_flash) => news_flash.get_fat_article(article_id),
+                None => break,
+            };
+            match article {
+                Ok(article) => match runtime.block_on(self.prepare(article, offline_settings.scrape_content, client)) {
+                    Ok(article) => {
+                        let article_size = article.html.as_ref().map(|html| html.len() as u64).unwrap_or(0)
+                            + article
+                                .scraped_content
+                                .as_ref()
+                                .map(|scraped| scraped.len() as u64)
+                                .unwrap_or(0);
+                        if !Self::make_room(&cache, &mut cache_budget, article_size, priority) {
+                            info!("Offline cache budget of {} MB reached", offline_settings.budget);
+                            break;
+                        }
+                        if let Err(error) = cache.store(&article) {
+                            error!(
+                                "Failed to store article '{}' for offline reading: {}",
+                                article_id, error
+                            );
+                            break;
+                        }
+                        cache_budget.insert(priority, article_id.clone(), article_size);
+                    }
+                    Err(error) => warn!("Failed to download images of article '{}': {}", article_id, error),
+                },
+                Err(error) => warn!("Failed to read article '{}': {}", article_id, error),
+            }
+
+            progress(index + 1, total);
+        }
+        Ok(())
+    }
+
+    // evicts what `cache_budget` decides on, `false` if there is no room for the article
+    fn make_room(cache: &OfflineCache, cache_budget: &mut CacheBudget, needed: u64, priority: usize) -> bool {
+        let evicted = match cache_budget.make_room(needed, priority) {
+            Some(evicted) => evicted,
+            None => return false,
+        };
+        for article_id in &evicted {
+            if let Err(error) = cache.remove(article_id) {
+                error!(
+                    "Failed to remove article '{}' from offline cache: {}",
+                    article_id, error
+                );
+                return false;
+            }
+        }
+        true
+    }
+
+    // articles to keep available offline, ordered by priority: newest first
+    fn load_candidates(news_flash: &NewsFlash, articles: OfflineArticles) -> Result<Vec<ArticleID>, NewsFlashError> {
+        let (unread, starred) = match articles {
+            OfflineArticles::Unread => (true, false),
+            OfflineArticles::Starred => (false, true),
+            OfflineArticles::UnreadAndStarred => (true, true),
+        };
+
+        let load = |unread: Option<Read>, marked: Option<Marked>| {
+            news_flash.get_articles(ArticleFilter {
+                limit: Some(MAX_OFFLINE_ARTICLES),
+                offset: None,
+                order: Some(ArticleOrder::NewestFirst),
+                unread,
+                marked,
+                feed: None,
+                feed_blacklist: None,
+                category: None,
+                category_blacklist: None,
+                tag: None,
+                ids: None,
+                newer_than: None,
+                older_than: None,
+                search_term: None,
+            })
+        };
+
+        let mut candidates = Vec::new();
+        if unread {
+            candidates.extend(load(Some(Read::Unread), None)?);
+        }
+        if starred {
+            candidates.extend(load(None, Some(Marked::Marked))?);
+        }
+        Ok(Self::prioritize(candidates))
+    }
+
+    // newest first, articles both unread and starred only once
+    fn prioritize(mut candidates: Vec<Article>) -> Vec<ArticleID> {
+        candidates.sort_by(|a, b| b.date.cmp(&a.date));
+
+        let mut seen = HashSet::new();
+        candidates
+            .into_iter()
+            .map(|article| article.article_id)
+            .filter(|article_id| seen.insert(article_id.clone()))
+            .take(MAX_OFFLINE_ARTICLES as usize)
+            .collect()
+    }
+}
+
+// Size of the cached articles by priority, a lower number is more important.
+// Decides which articles to evict to stay within the budget.
+struct CacheBudget {
+    budget: u64,
+    size: u64,
+    // (priority, article, size) ordered by priority
+    cached: Vec<(usize, ArticleID, u64)>,
+}
+
+impl CacheBudget {
+    fn new(budget: u64, mut cached: Vec<(usize, ArticleID, u64)>) -> Self {
+        cached.sort_by_key(|(priority, _article_id, _size)| *priority);
+        CacheBudget {
+            budget,
+            size: cached.iter().map(|(_priority, _article_id, size)| size).sum(),
+            cached,
+        }
+    }
+
+    // The articles to evict, lowest priority first, so `needed` bytes of an article with `priority` fit.
+    // `None` and nothing is evicted if evicting all articles of lower priority isn't enough.
+    fn make_room(&mut self, needed: u64, priority: usize) -> Option<Vec<ArticleID>> {
+        let mut size = self.size;
+        let mut keep = self.cached.len();
+        while size + needed > self.budget {
+            match self.cached[..keep].last() {
+                Some((lowest, _article_id, article_size)) if *lowest > priority => {
+                    size -= article_size;
+                    keep -= 1;
+                }
+                _ => return None,
+            }
+        }
+
+        self.size = size;
+        Some(
+            self.cached
+                .drain(keep..)
+                .rev()
+                .map(|(_priority, article_id, _size)| article_id)
+                .collect(),
+        )
+    }
+
+    fn insert(&mut self, priority: usize, article_id: ArticleID, size: u64) {
+        let position = self
+            .cached
+            .binary_search_by_key(&priority, |(priority, _article_id, _size)| *priority)
+            .unwrap_or_else(|position| position);
+        self.cached.insert(position, (priority, article_id, size));
+        self.size += size;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CacheBudget, OfflinePreparer};
+    use chrono::NaiveDate;
+    use news_flash::models::{Article, ArticleID, FeedID, Marked, Read};
+
+    fn article(id: &str, day: u32) -> Article {
+        Article {
+            article_id: ArticleID::new(id),
+            title: None,
+            author: None,
+            feed_id: FeedID::new("feed"),
+            url: None,
+            date: NaiveDate::from_ymd(2020, 6, day).and_hms(12, 0, 0),
+            summary: None,
+            direction: None,
+            unread: Read::Unread,
+            marked: Marked::Unmarked,
+        }
+    }
+
+    fn ids(ids: &[&str]) -> Vec<ArticleID> {
+        ids.iter().map(|id| ArticleID::new(id)).collect()
+    }
+
+    #[test]
+    fn priority_order() {
+        // unread and starred are loaded separately, an article can be in both
+        let candidates = vec![
+            article("old", 1),
+            article("new", 3),
+            article("middle", 2),
+            article("new", 3),
+        ];
+        assert_eq!(OfflinePreparer::prioritize(candidates), ids(&["new", "middle", "old"]));
+    }
+
+    #[test]
+    fn evict_lowest_priority_first() {
+        let cached = vec![
+            (4, ArticleID::new("low"), 30),
+            (0, ArticleID::new("high"), 30),
+            (2, ArticleID::new("middle"), 30),
+        ];
+        let mut budget = CacheBudget::new(100, cached);
+        assert_eq!(budget.make_room(10, 1), Some(Vec::new()));
+
+        assert_eq!(budget.make_room(50, 1), Some(ids(&["low", "middle"])));
+        budget.insert(1, ArticleID::new("new"), 50);
+        assert_eq!(budget.size, 80);
+        assert_eq!(budget.make_room(20, 3), Some(Vec::new()));
+    }
+
+    #[test]
+    fn over_budget() {
+        let cached = vec![(0, ArticleID::new("high"), 60), (2, ArticleID::new("low"), 30)];
+        let mut budget = CacheBudget::new(100, cached);
+
+        // articles of higher priority are never evicted for one of lower priority
+        assert_eq!(budget.make_room(20, 3), None);
+        // nothing is evicted if the article doesn't fit anyway
+        assert_eq!(budget.make_room(50, 1), None);
+        assert_eq!(budget.size, 90);
+        assert_eq!(budget.make_room(40, 1), Some(ids(&["low"])));
+
+        // a budget that was lowered in the meantime
+        let cached = vec![(0, ArticleID::new("high"), 60), (2, ArticleID::new("low"), 30)];
+        let mut budget = CacheBudget::new(50, cached);
+        assert_eq!(budget.make_room(1, 1), None);
+        assert_eq!(budget.make_room(1, 0), None);
+    }
+}
@@ -0,0 +1,304 @@
+use super::error::{OfflineError, OfflineErrorKind};
+use crate::app::DATA_DIR;
+use crate::util::FileUtil;
+use failure::ResultExt;
+use news_flash::models::{Article, ArticleID, Marked, Read, TagID};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const QUEUE_NAME: &str = "offline_queue.json";
+
+// Each change knows the state it was made on, so it isn't replayed over a change made elsewhere.
+// Tagging is only possible on an untagged article and the other way around, so the variant itself is that state.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum OfflineChange {
+    Read {
+        article_id: ArticleID,
+        unread: bool,
+        was_unread: bool,
+    },
+    Marked {
+        article_id: ArticleID,
+        marked: bool,
+        was_marked: bool,
+    },
+    TagArticle {
+        article_id: ArticleID,
+        tag_id: TagID,
+    },
+    UntagArticle {
+        article_id: ArticleID,
+        tag_id: TagID,
+    },
+}
+
+pub struct ArticleState {
+    pub read: Read,
+    pub marked: Marked,
+    pub tags: Vec<TagID>,
+}
+
+impl OfflineChange {
+    pub fn read(article_id: ArticleID, read: Read, previous: Read) -> Self {
+        OfflineChange::Read {
+            article_id,
+            unread: read == Read::Unread,
+            was_unread: previous == Read::Unread,
+        }
+    }
+
+    pub fn marked(article_id: ArticleID, marked: Marked, previous: Marked) -> Self {
+        OfflineChange::Marked {
+            article_id,
+            marked: marked == Marked::Marked,
+            was_marked: previous == Marked::Marked,
+        }
+    }
+
+    pub fn article_id(&self) -> &ArticleID {
+        match self {
+            OfflineChange::Read { article_id, .. }
+            | OfflineChange::Marked { article_id, .. }
+            | OfflineChange::TagArticle { article_id, .. }
+            | OfflineChange::UntagArticle { article_id, .. } => article_id,
+        }
+    }
+
+    pub fn read_state(&self) -> Option<Read> {
+        match self {
+            OfflineChange::Read { unread: true, .. } => Some(Read::Unread),
+            OfflineChange::Read { unread: false, .. } => Some(Read::Read),
+            _ => None,
+        }
+    }
+
+    pub fn marked_state(&self) -> Option<Marked> {
+        match self {
+            OfflineChange::Marked { marked: true, .. } => Some(Marked::Marked),
+            OfflineChange::Marked { marked: false, .. } => Some(Marked::Unmarked),
+            _ => None,
+        }
+    }
+
+    // otherwise the article was changed elsewhere in the meantime and the change must not be replayed
+    pub fn applies_to(&self, state: &ArticleState) -> bool {
+        match self {
+            OfflineChange::Read { was_unread, .. } => (state.read == Read::Unread) == *was_unread,
+            OfflineChange::Marked { was_marked, .. } => (state.marked == Marked::Marked) == *was_marked,
+            OfflineChange::TagArticle { tag_id, .. } => !state.tags.contains(tag_id),
+            OfflineChange::UntagArticle { tag_id, .. } => state.tags.contains(tag_id),
+        }
+    }
+
+    fn replaces(&self, other: &Self) -> bool {
+        let same_state = match (self, other) {
+            (OfflineChange::Read { .. }, OfflineChange::Read { .. })
+            | (OfflineChange::Marked { .. }, OfflineChange::Marked { .. }) => true,
+            (OfflineChange::TagArticle { tag_id, .. }, OfflineChange::TagArticle { tag_id: other_id, .. })
+            | (OfflineChange::TagArticle { tag_id, .. }, OfflineChange::UntagArticle { tag_id: other_id, .. })
+            | (OfflineChange::UntagArticle { tag_id, .. }, OfflineChange::TagArticle { tag_id: other_id, .. })
+            | (OfflineChange::UntagArticle { tag_id, .. }, OfflineChange::UntagArticle { tag_id: other_id, .. }) => {
+                tag_id == other_id
+            }
+            _ => false,
+        };
+        same_state && self.article_id() == other.article_id()
+    }
+
+    // the state the earlier change was made on is kept, `None` if the changes cancel each other out
+    fn after(self, earlier: &Self) -> Option<Self> {
+        let change = match (self, earlier) {
+            (OfflineChange::Read { article_id, unread, .. }, OfflineChange::Read { was_unread, .. }) => {
+                OfflineChange::Read {
+                    article_id,
+                    unread,
+                    was_unread: *was_unread,
+                }
+            }
+            (OfflineChange::Marked { article_id, marked, .. }, OfflineChange::Marked { was_marked, .. }) => {
+                OfflineChange::Marked {
+                    article_id,
+                    marked,
+                    was_marked: *was_marked,
+                }
+            }
+            (OfflineChange::TagArticle { .. }, OfflineChange::UntagArticle { .. })
+            | (OfflineChange::UntagArticle { .. }, OfflineChange::TagArticle { .. }) => return None,
+            (change, _) => change,
+        };
+        Some(change)
+    }
+
+    fn changes_nothing(&self) -> bool {
+        match self {
+            OfflineChange::Read { unread, was_unread, .. } => unread == was_unread,
+            OfflineChange::Marked { marked, was_marked, .. } => marked == was_marked,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OfflineQueue {
+    changes: Vec<OfflineChange>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    path: PathBuf,
+}
+
+impl Default for OfflineQueue {
+    fn default() -> Self {
+        OfflineQueue {
+            changes: Vec::new(),
+            path: DATA_DIR.join(QUEUE_NAME),
+        }
+    }
+}
+
+impl OfflineQueue {
+    pub fn open() -> Result<Self, OfflineError> {
+        let path = DATA_DIR.join(QUEUE_NAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let data = fs::read_to_string(&path).context(OfflineErrorKind::Read)?;
+        let mut queue: Self = serde_json::from_str(&data).context(OfflineErrorKind::InvalidJsonContent)?;
+        queue.path = path;
+        Ok(queue)
+    }
+
+    fn write(&self) -> Result<(), OfflineError> {
+        FileUtil::write_json_atomic(self, &self.path).context(OfflineErrorKind::Write)?;
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn changes(&self) -> &[OfflineChange] {
+        &self.changes
+    }
+
+    pub fn push(&mut self, changes: Vec<OfflineChange>) -> Result<(), OfflineError> {
+        for change in changes {
+            self.insert(change);
+        }
+        self.write()
+    }
+
+    fn insert(&mut self, change: OfflineChange) {
+        let change = match self.changes.iter().position(|queued| change.replaces(queued)) {
+            Some(index) => {
+                let earlier = self.changes.remove(index);
+                change.after(&earlier)
+            }
+            None => Some(change),
+        };
+        if let Some(change) = change.filter(|change| !change.changes_nothing()) {
+            self.changes.push(change);
+        }
+    }
+
+    pub fn remove(&mut self, handled: &[OfflineChange]) -> Result<(), OfflineError> {
+        self.changes.retain(|change| !handled.contains(change));
+        self.write()
+    }
+
+    pub fn clear(&mut self) -> Result<(), OfflineError> {
+        self.changes.clear();
+        self.write()
+    }
+
+    pub fn apply(&self, articles: &mut [Article]) {
+        for change in &self.changes {
+            for article in articles
+                .iter_mut()
+                .filter(|article| &article.article_id == change.article_id())
+            {
+                if let Some(read) = change.read_state() {
+                    article.unread = read;
+                }
+                if let Some(marked) = change.marked_state() {
+                    article.marked = marked;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArticleState, OfflineChange, OfflineQueue};
+    use news_flash::models::{ArticleID, Marked, Read, TagID};
+
+    fn state(read: Read, marked: Marked, tags: Vec<TagID>) -> ArticleState {
+        ArticleState { read, marked, tags }
+    }
+
+    #[test]
+    fn later_change_replaces_earlier() {
+        let mut queue = OfflineQueue::default();
+        let article_id = ArticleID::new("article_1");
+        queue.insert(OfflineChange::read(article_id.clone(), Read::Read, Read::Unread));
+        queue.insert(OfflineChange::marked(
+            article_id.clone(),
+            Marked::Marked,
+            Marked::Unmarked,
+        ));
+        queue.insert(OfflineChange::read(article_id.clone(), Read::Unread, Read::Read));
+
+        assert_eq!(
+            queue.changes(),
+            &[OfflineChange::marked(article_id, Marked::Marked, Marked::Unmarked)]
+        );
+    }
+
+    #[test]
+    fn skip_changes_made_elsewhere() {
+        let article_id = ArticleID::new("article_1");
+        let mark_read = OfflineChange::read(article_id.clone(), Read::Read, Read::Unread);
+        assert!(mark_read.applies_to(&state(Read::Unread, Marked::Unmarked, Vec::new())));
+        assert!(!mark_read.applies_to(&state(Read::Read, Marked::Unmarked, Vec::new())));
+
+        let unstar = OfflineChange::marked(article_id, Marked::Unmarked, Marked::Marked);
+        assert!(unstar.applies_to(&state(Read::Read, Marked::Marked, Vec::new())));
+        assert!(!unstar.applies_to(&state(Read::Read, Marked::Unmarked, Vec::new())));
+    }
+
+    #[test]
+    fn tag_changes() {
+        let mut queue = OfflineQueue::default();
+        let article_id = ArticleID::new("article_1");
+        let later = TagID::new("later");
+        let done = TagID::new("done");
+        let tag = OfflineChange::TagArticle {
+            article_id: article_id.clone(),
+            tag_id: later.clone(),
+        };
+        assert!(tag.applies_to(&state(Read::Unread, Marked::Unmarked, vec![done.clone()])));
+        assert!(!tag.applies_to(&state(Read::Unread, Marked::Unmarked, vec![later.clone()])));
+
+        queue.insert(tag.clone());
+        queue.insert(OfflineChange::UntagArticle {
+            article_id: article_id.clone(),
+            tag_id: done.clone(),
+        });
+        assert_eq!(queue.changes().len(), 2);
+
+        // untagging what was tagged while offline leaves nothing to replay
+        queue.insert(OfflineChange::UntagArticle {
+            article_id: article_id.clone(),
+            tag_id: later,
+        });
+        assert_eq!(
+            queue.changes(),
+            &[OfflineChange::UntagArticle {
+                article_id,
+                tag_id: done
+            }]
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use super::queue::{ArticleState, OfflineChange, OfflineQueue};
+use crate::util::RUNTIME_ERROR;
+use log::{info, warn};
+use news_flash::models::{ArticleID, Marked, Read};
+use news_flash::NewsFlash;
+use reqwest::Client;
+use tokio::runtime::Runtime;
+
+impl OfflineQueue {
+    // Send queued changes to the backend. Returns the changes that can be dropped from the queue and
+    // how many of them were skipped because the article was changed elsewhere.
+    // Changes that failed to send are kept and retried after the next sync.
+    pub fn replay(news_flash: &NewsFlash, changes: &[OfflineChange], client: &Client) -> (Vec<OfflineChange>, usize) {
+        let mut handled = Vec::new();
+        let mut skipped = 0;
+        let (mut read, mut unread, mut marked, mut unmarked) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        let mut tag_changes = Vec::new();
+
+        for change in changes {
+            let article = match news_flash.get_article(change.article_id()) {
+                Ok(article) => article,
+                Err(_) => {
+                    info!("Dropping offline change of '{}': article is gone", change.article_id());
+                    handled.push(change.clone());
+                    continue;
+                }
+            };
+            let tags = match change {
+                OfflineChange::TagArticle { .. } | OfflineChange::UntagArticle { .. } => {
+                    match news_flash.get_tags_of_article(change.article_id()) {
+                        Ok(tags) => tags.into_iter().map(|tag| tag.tag_id).collect(),
+                        Err(error) => {
+                            warn!("Failed to load tags of '{}': {}", change.article_id(), error);
+                            continue;
+                        }
+                    }
+                }
+                _ => Vec::new(),
+            };
+            let state = ArticleState {
+                read: article.unread,
+                marked: article.marked,
+                tags,
+            };
+
+            if !change.applies_to(&state) {
+                info!(
+                    "Skipping offline change of '{}': article was changed elsewhere",
+                    change.article_id()
+                );
+                handled.push(change.clone());
+                skipped += 1;
+                continue;
+            }
+
+            match change {
+                OfflineChange::Read { unread: false, .. } => read.push(change.clone()),
+                OfflineChange::Read { unread: true, .. } => unread.push(change.clone()),
+                OfflineChange::Marked { marked: true, .. } => marked.push(change.clone()),
+                OfflineChange::Marked { marked: false, .. } => unmarked.push(change.clone()),
+                OfflineChange::TagArticle { .. } | OfflineChange::UntagArticle { .. } => {
+                    tag_changes.push((change.clone(), article))
+                }
+            }
+        }
+
+        let article_ids = |changes: &[OfflineChange]| -> Vec<ArticleID> {
+            changes.iter().map(|change| change.article_id().clone()).collect()
+        };
+        let mut runtime = Runtime::new().expect(RUNTIME_ERROR);
+        for (changes, state) in vec![(read, Read::Read), (unread, Read::Unread)] {
+            if changes.is_empty() {
+                continue;
+            }
+            match runtime.block_on(news_flash.set_article_read(&article_ids(&changes), state, client)) {
+                Ok(()) => handled.extend(changes),
+                Err(error) => warn!("Failed to replay offline read changes: {}", error),
+            }
+        }
+        for (changes, state) in vec![(marked, Marked::Marked), (unmarked, Marked::Unmarked)] {
+            if changes.is_empty() {
+                continue;
+            }
+            match runtime.block_on(news_flash.set_article_marked(&article_ids(&changes), state, client)) {
+                Ok(()) => handled.extend(changes),
+                Err(error) => warn!("Failed to replay offline starred changes: {}", error),
+            }
+        }
+
+        if !tag_changes.is_empty() {
+            let tags = match news_flash.get_tags() {
+                Ok(tags) => tags,
+                Err(error) => {
+                    warn!("Failed to load tags: {}", error);
+                    return (handled, skipped);
+                }
+            };
+            for (change, article) in tag_changes {
+                let (tag_id, tag) = match &change {
+                    OfflineChange::TagArticle { tag_id, .. } | OfflineChange::UntagArticle { tag_id, .. } => {
+                        (tag_id, tags.iter().find(|tag| &tag.tag_id == tag_id))
+                    }
+                    _ => continue,
+                };
+                let tag = match tag {
+                    Some(tag) => tag,
+                    None => {
+                        info!(
+                            "Dropping offline change of '{}': tag '{}' is gone",
+                            article.article_id, tag_id
+                        );
+                        handled.push(change);
+                        continue;
+                    }
+                };
+                let result = if let OfflineChange::TagArticle { .. } = change {
+                    runtime.block_on(news_flash.tag_article(&article, tag, client))
+                } else {
+                    runtime.block_on(news_flash.untag_article(&article, tag, client))
+                };
+                match result {
+                    Ok(()) => handled.push(change),
+                    Err(error) => warn!("Failed to replay offline tag change: {}", error),
+                }
+            }
+        }
+
+        (handled, skipped)
+    }
+}
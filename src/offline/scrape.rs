@@ -0,0 +1,149 @@
+use crate::app::App;
+use crate::settings::Settings;
+use crate::util::RUNTIME_ERROR;
+use futures::executor::ThreadPool;
+use log::{error, info, warn};
+use news_flash::models::{ArticleFilter, ArticleID, ArticleOrder, FeedID, Read};
+use news_flash::NewsFlash;
+use parking_lot::RwLock;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+const MAX_CONCURRENT_SCRAPES: usize = 3;
+// only the most recent unread articles of a feed are scraped after a sync
+const MAX_SCRAPES_PER_FEED: i64 = 50;
+
+// Scrapes the full content of unread articles of the feeds that have `scrape_on_sync` set.
+#[derive(Default)]
+pub struct SyncScraper {
+    in_progress: Arc<RwLock<bool>>,
+    // articles that failed once aren't retried until restart
+    failed: Arc<RwLock<HashSet<ArticleID>>>,
+}
+
+impl SyncScraper {
+    pub fn new() -> Self {
+        SyncScraper::default()
+    }
+
+    pub fn scrape(
+        &self,
+        news_flash: &Arc<RwLock<Option<NewsFlash>>>,
+        settings: &Arc<RwLock<Settings>>,
+        threadpool: &ThreadPool,
+    ) {
+        if *self.in_progress.read() {
+            return;
+        }
+
+        let feed_ids = settings.read().get_scrape_on_sync_feeds();
+        if feed_ids.is_empty() {
+            return;
+        }
+        *self.in_progress.write() = true;
+
+        let news_flash = news_flash.clone();
+        let settings = settings.clone();
+        let worker_threadpool = threadpool.clone();
+        let in_progress = self.in_progress.clone();
+        let failed = self.failed.clone();
+        let thread_future = async move {
+            let article_ids = match news_flash.read().as_ref() {
+                Some(news_flash) => Self::load_unscraped_articles(news_flash, &feed_ids, &failed.read()),
+                None => Vec::new(),
+            };
+            if article_ids.is_empty() {
+                *in_progress.write() = false;
+                return;
+            }
+            info!("Scraping {} articles in the background", article_ids.len());
+
+            // workers pop from the back: scrape the newest articles first
+            let mut article_ids = article_ids;
+            article_ids.reverse();
+            let worker_count = MAX_CONCURRENT_SCRAPES.min(article_ids.len());
+            let queue = Arc::new(RwLock::new(article_ids));
+            let running_workers = Arc::new(RwLock::new(worker_count));
+            for _ in 0..worker_count {
+                let news_flash = news_flash.clone();
+                let settings = settings.clone();
+                let queue = queue.clone();
+                let running_workers = running_workers.clone();
+                let in_progress = in_progress.clone();
+                let failed = failed.clone();
+                worker_threadpool.spawn_ok(async move {
+                    let client = App::build_client(&settings);
+                    let mut runtime = Runtime::new().expect(RUNTIME_ERROR);
+                    loop {
+                        let article_id = match queue.write().pop() {
+                            Some(article_id) => article_id,
+                            None => break,
+                        };
+                        if let Some(news_flash) = news_flash.read().as_ref() {
+                            if let Err(error) = runtime.block_on(news_flash.article_scrap_content(&article_id, &client))
+                            {
+                                warn!("Failed to scrape article '{}': {}", article_id, error);
+                                failed.write().insert(article_id);
+                            }
+                        }
+                    }
+
+                    let mut running_workers = running_workers.write();
+                    *running_workers -= 1;
+                    if *running_workers == 0 {
+                        *in_progress.write() = false;
+                    }
+                });
+            }
+        };
+
+        threadpool.spawn_ok(thread_future);
+    }
+
+    fn load_unscraped_articles(
+        news_flash: &NewsFlash,
+        feed_ids: &[FeedID],
+        failed: &HashSet<ArticleID>,
+    ) -> Vec<ArticleID> {
+        let mut article_ids = Vec::new();
+        for feed_id in feed_ids {
+            let articles = match news_flash.get_articles(ArticleFilter {
+                limit: Some(MAX_SCRAPES_PER_FEED),
+                offset: None,
+                order: Some(ArticleOrder::NewestFirst),
+                unread: Some(Read::Unread),
+                marked: None,
+                feed: Some(feed_id.clone()),
+                feed_blacklist: None,
+                category: None,
+                category_blacklist: None,
+                tag: None,
+                ids: None,
+                newer_than: None,
+                older_than: None,
+                search_term: None,
+            }) {
+                Ok(articles) => articles,
+                Err(error) => {
+                    error!("Failed to load articles of feed '{}': {}", feed_id, error);
+                    continue;
+                }
+            };
+
+            article_ids.extend(
+                articles
+                    .into_iter()
+                    .filter(|article| !failed.contains(&article.article_id))
+                    .filter(|article| {
+                        news_flash
+                            .get_fat_article(&article.article_id)
+                            .map(|fat_article| fat_article.scraped_content.is_none())
+                            .unwrap_or(false)
+                    })
+                    .map(|article| article.article_id),
+            );
+        }
+        article_ids
+    }
+}
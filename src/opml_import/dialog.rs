@@ -0,0 +1,341 @@
+use super::{CategoryTarget, ImportReport, OpmlImport};
+use crate::app::{Action, App};
+use crate::i18n::{i18n, i18n_f, ni18n_f};
+use crate::settings::Settings;
+use crate::util::{BuilderHelper, Util, CHANNEL_ERROR};
+use futures::channel::oneshot;
+use futures::executor::ThreadPool;
+use futures::FutureExt;
+use glib::{clone, Sender};
+use gtk::{
+    Box, Button, ButtonExt, CheckButton, ComboBoxExt, ComboBoxText, ComboBoxTextExt, ContainerExt, GtkWindowExt, Label,
+    LabelExt, ListBox, ListBoxRow, ListBoxRowExt, Orientation, Stack, StackExt, StyleContextExt, ToggleButtonExt,
+    WidgetExt, Window,
+};
+use news_flash::models::{Category, CategoryID, CategoryType};
+use news_flash::NewsFlash;
+use parking_lot::RwLock;
+use std::iter;
+use std::sync::Arc;
+
+const TARGET_NEW: &str = "new";
+const TARGET_UNCATEGORIZED: &str = "uncategorized";
+const TARGET_EXISTING_PREFIX: &str = "existing:";
+
+pub struct OpmlImportDialog {
+    pub widget: Window,
+}
+
+struct CategoryRow {
+    check: CheckButton,
+    target_combo: ComboBoxText,
+    feed_checks: Vec<CheckButton>,
+}
+
+impl OpmlImportDialog {
+    pub fn new(
+        window: &gtk::ApplicationWindow,
+        sender: &Sender<Action>,
+        settings: &Arc<RwLock<Settings>>,
+        news_flash: &Arc<RwLock<Option<NewsFlash>>>,
+        threadpool: ThreadPool,
+        import: OpmlImport,
+        categories: Vec<Category>,
+    ) -> Self {
+        let builder = BuilderHelper::new("opml_import_dialog");
+        let dialog = builder.get::<Window>("opml_import_dialog");
+        dialog.set_transient_for(Some(window));
+
+        let stack = builder.get::<Stack>("import_stack");
+        let summary_label = builder.get::<Label>("summary_label");
+        let feed_list = builder.get::<ListBox>("feed_list");
+        let report_label = builder.get::<Label>("report_label");
+        let report_list = builder.get::<ListBox>("report_list");
+        let cancel_button = builder.get::<Button>("cancel_button");
+        let import_button = builder.get::<Button>("import_button");
+        let close_button = builder.get::<Button>("close_button");
+
+        let mut categories = categories;
+        categories.retain(|category| category.category_type != CategoryType::Generated);
+        categories.sort_by(|a, b| a.label.to_lowercase().cmp(&b.label.to_lowercase()));
+
+        let feed_count = import
+            .categories
+            .iter()
+            .map(|category| category.feeds.len())
+            .sum::<usize>();
+        let subscribed_count = import
+            .categories
+            .iter()
+            .flat_map(|category| category.feeds.iter())
+            .filter(|feed| feed.subscribed)
+            .count();
+        let mut summary = ni18n_f(
+            "The file contains {} feed.",
+            "The file contains {} feeds.",
+            feed_count as u32,
+            &[&feed_count.to_string()],
+        );
+        if subscribed_count > 0 {
+            summary.push(' ');
+            summary.push_str(&ni18n_f(
+                "{} of them is already subscribed and not selected.",
+                "{} of them are already subscribed and not selected.",
+                subscribed_count as u32,
+                &[&subscribed_count.to_string()],
+            ));
+        }
+        summary_label.set_text(&summary);
+
+        let rows = Self::fill_list(&feed_list, &import, &categories);
+        let rows = Arc::new(rows);
+        let import = Arc::new(import);
+        Self::update_import_button(&import_button, &rows);
+        for row in rows.iter() {
+            for check in iter::once(&row.check).chain(row.feed_checks.iter()) {
+                check.connect_toggled(
+                    clone!(@weak import_button, @strong rows => @default-panic, move |_check| {
+                        Self::update_import_button(&import_button, &rows);
+                    }),
+                );
+            }
+        }
+
+        cancel_button.connect_clicked(clone!(@weak dialog => @default-panic, move |_button| {
+            dialog.close();
+        }));
+
+        close_button.connect_clicked(clone!(@weak dialog => @default-panic, move |_button| {
+            dialog.close();
+        }));
+
+        import_button.connect_clicked(clone!(
+            @weak stack,
+            @weak cancel_button,
+            @weak close_button,
+            @weak report_label,
+            @weak report_list,
+            @strong import,
+            @strong rows,
+            @strong news_flash,
+            @strong settings,
+            @strong threadpool,
+            @strong sender => @default-panic, move |import_button|
+        {
+            let selection = Self::read_selection(&import, &rows);
+            import_button.set_visible(false);
+            cancel_button.set_sensitive(false);
+            stack.set_visible_child_name("importing");
+
+            let (oneshot_sender, receiver) = oneshot::channel::<Vec<ImportReport>>();
+            let news_flash = news_flash.clone();
+            let settings = settings.clone();
+            let thread_future = async move {
+                let reports = match news_flash.read().as_ref() {
+                    Some(news_flash) => selection.import(news_flash, &App::build_client(&settings)),
+                    None => Vec::new(),
+                };
+                oneshot_sender.send(reports).expect(CHANNEL_ERROR);
+            };
+
+            let glib_future = receiver.map(clone!(
+                @weak stack,
+                @weak cancel_button,
+                @weak close_button,
+                @weak report_label,
+                @weak report_list,
+                @strong sender => @default-return (), move |res|
+            {
+                let reports = res.unwrap_or_default();
+                Self::show_report(&report_label, &report_list, &reports);
+                cancel_button.set_visible(false);
+                close_button.set_visible(true);
+                stack.set_visible_child_name("report");
+                Util::send(&sender, Action::UpdateSidebar);
+            }));
+
+            threadpool.spawn_ok(thread_future);
+            Util::glib_spawn_future(glib_future);
+        }));
+
+        dialog.show_all();
+        close_button.set_visible(false);
+        stack.set_visible_child_name("preview");
+        OpmlImportDialog { widget: dialog }
+    }
+
+    fn fill_list(list: &ListBox, import: &OpmlImport, categories: &[Category]) -> Vec<CategoryRow> {
+        let mut rows = Vec::new();
+
+        for (index, opml_category) in import.categories.iter().enumerate() {
+            // nested categories are indented below the category they are part of
+            let indent = 24 * import.depth(index) as i32;
+            let title = opml_category.title.clone().unwrap_or_else(|| i18n("Without Category"));
+            let check = CheckButton::with_label(&title);
+            check.set_hexpand(true);
+
+            let target_combo = ComboBoxText::new();
+            if let Some(title) = &opml_category.title {
+                target_combo.append(Some(TARGET_NEW), &i18n_f("New category “{}”", &[title]));
+            }
+            target_combo.append(Some(TARGET_UNCATEGORIZED), &i18n("No category"));
+            for category in categories {
+                let id = format!("{}{}", TARGET_EXISTING_PREFIX, category.category_id);
+                target_combo.append(Some(&id), &category.label);
+            }
+            let active_id = match &opml_category.target {
+                CategoryTarget::New(_title) => TARGET_NEW.to_owned(),
+                CategoryTarget::Uncategorized => TARGET_UNCATEGORIZED.to_owned(),
+                CategoryTarget::Existing(category_id) => format!("{}{}", TARGET_EXISTING_PREFIX, category_id),
+            };
+            target_combo.set_active_id(Some(&active_id));
+            target_combo.set_tooltip_text(Some(&i18n("Import into")));
+
+            let header_box = Box::new(Orientation::Horizontal, 6);
+            header_box.set_margin_start(6 + indent);
+            header_box.set_margin_end(6);
+            header_box.set_margin_top(12);
+            header_box.set_margin_bottom(6);
+            header_box.add(&check);
+            header_box.add(&target_combo);
+            Self::add_row(list, &header_box);
+
+            let mut feed_checks = Vec::new();
+            for feed in &opml_category.feeds {
+                let feed_box = Box::new(Orientation::Horizontal, 6);
+                feed_box.set_margin_start(30 + indent);
+                feed_box.set_margin_end(6);
+
+                let feed_check = CheckButton::with_label(&feed.title);
+                feed_check.set_hexpand(true);
+                feed_check.set_active(feed.selected);
+                feed_check.set_tooltip_text(Some(&feed.url));
+                feed_box.add(&feed_check);
+
+                if feed.subscribed {
+                    let subscribed_label = Label::new(Some(&i18n("Already subscribed")));
+                    subscribed_label.get_style_context().add_class("dim-label");
+                    feed_box.add(&subscribed_label);
+                }
+                Self::add_row(list, &feed_box);
+                feed_checks.push(feed_check);
+            }
+
+            check.set_active(feed_checks.iter().any(|feed_check| feed_check.get_active()));
+            // a category that only holds other categories always needs a target for them
+            if feed_checks.is_empty() {
+                check.set_active(true);
+                check.set_sensitive(false);
+            }
+            // (un)ticking a category applies to all of its feeds
+            check.connect_clicked(clone!(@strong feed_checks => @default-panic, move |check| {
+                for feed_check in &feed_checks {
+                    feed_check.set_active(check.get_active());
+                }
+            }));
+            check.connect_toggled(clone!(@weak target_combo => @default-panic, move |check| {
+                target_combo.set_sensitive(check.get_active());
+            }));
+            target_combo.set_sensitive(check.get_active());
+
+            rows.push(CategoryRow {
+                check,
+                target_combo,
+                feed_checks,
+            });
+        }
+
+        rows
+    }
+
+    fn add_row(list: &ListBox, child: &Box) {
+        let row = ListBoxRow::new();
+        row.set_activatable(false);
+        row.add(child);
+        list.add(&row);
+    }
+
+    fn read_selection(import: &OpmlImport, rows: &[CategoryRow]) -> OpmlImport {
+        let mut selection = import.clone();
+        for (category, row) in selection.categories.iter_mut().zip(rows.iter()) {
+            let active_id = row.target_combo.get_active_id();
+            category.target = match active_id.as_ref().map(|id| id.as_str()) {
+                Some(TARGET_NEW) => match &category.title {
+                    Some(title) => CategoryTarget::New(title.clone()),
+                    None => CategoryTarget::Uncategorized,
+                },
+                Some(id) if id.starts_with(TARGET_EXISTING_PREFIX) => {
+                    CategoryTarget::Existing(CategoryID::new(&id[TARGET_EXISTING_PREFIX.len()..]))
+                }
+                _ => CategoryTarget::Uncategorized,
+            };
+            for (feed, check) in category.feeds.iter_mut().zip(row.feed_checks.iter()) {
+                feed.selected = check.get_active();
+            }
+        }
+        selection
+    }
+
+    fn update_import_button(button: &Button, rows: &[CategoryRow]) {
+        let selected = rows
+            .iter()
+            .flat_map(|row| row.feed_checks.iter())
+            .filter(|check| check.get_active())
+            .count();
+        button.set_sensitive(selected > 0);
+    }
+
+    fn show_report(label: &Label, list: &ListBox, reports: &[ImportReport]) {
+        let failed = reports.iter().filter(|report| report.error.is_some()).count();
+        let imported = reports.len() - failed;
+        let mut text = ni18n_f(
+            "Imported {} feed.",
+            "Imported {} feeds.",
+            imported as u32,
+            &[&imported.to_string()],
+        );
+        if failed > 0 {
+            text.push(' ');
+            text.push_str(&ni18n_f(
+                "{} feed failed.",
+                "{} feeds failed.",
+                failed as u32,
+                &[&failed.to_string()],
+            ));
+        }
+        label.set_text(&text);
+
+        // failures first, they are what needs attention
+        let mut reports: Vec<&ImportReport> = reports.iter().collect();
+        reports.sort_by_key(|report| report.error.is_none());
+        for report in reports {
+            let report_box = Box::new(Orientation::Vertical, 2);
+            report_box.set_margin_start(12);
+            report_box.set_margin_end(12);
+            report_box.set_margin_top(6);
+            report_box.set_margin_bottom(6);
+
+            let title_label = Label::new(Some(&report.title));
+            title_label.set_xalign(0.0);
+            title_label.set_ellipsize(pango::EllipsizeMode::End);
+            report_box.add(&title_label);
+
+            let status = match &report.error {
+                Some(error) => i18n_f("Failed: {}", &[error]),
+                None => i18n("Imported"),
+            };
+            let status_label = Label::new(Some(&status));
+            status_label.set_xalign(0.0);
+            status_label.set_line_wrap(true);
+            status_label.set_tooltip_text(Some(&report.url));
+            let style = status_label.get_style_context();
+            style.add_class("dim-label");
+            if report.error.is_some() {
+                style.add_class("error");
+            }
+            report_box.add(&status_label);
+            Self::add_row(list, &report_box);
+        }
+        list.show_all();
+    }
+}
@@ -0,0 +1,66 @@
+use failure::{Backtrace, Context, Error, Fail};
+use std::fmt;
+
+#[derive(Debug)]
+pub struct OpmlImportError {
+    inner: Context<OpmlImportErrorKind>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Fail)]
+pub enum OpmlImportErrorKind {
+    #[fail(display = "Failed to read OPML file")]
+    Read,
+    #[fail(display = "Not a valid OPML document")]
+    Parse,
+    #[fail(display = "The OPML document contains no feeds")]
+    NoFeeds,
+    #[fail(display = "Failed to read feeds and categories")]
+    DataBase,
+    #[fail(display = "Unknown Error")]
+    Unknown,
+}
+
+impl Fail for OpmlImportError {
+    fn cause(&self) -> Option<&dyn Fail> {
+        self.inner.cause()
+    }
+
+    fn backtrace(&self) -> Option<&Backtrace> {
+        self.inner.backtrace()
+    }
+}
+
+impl fmt::Display for OpmlImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, f)
+    }
+}
+
+impl OpmlImportError {
+    #[allow(dead_code)]
+    pub fn kind(&self) -> OpmlImportErrorKind {
+        *self.inner.get_context()
+    }
+}
+
+impl From<OpmlImportErrorKind> for OpmlImportError {
+    fn from(kind: OpmlImportErrorKind) -> OpmlImportError {
+        OpmlImportError {
+            inner: Context::new(kind),
+        }
+    }
+}
+
+impl From<Context<OpmlImportErrorKind>> for OpmlImportError {
+    fn from(inner: Context<OpmlImportErrorKind>) -> OpmlImportError {
+        OpmlImportError { inner }
+    }
+}
+
+impl From<Error> for OpmlImportError {
+    fn from(_: Error) -> OpmlImportError {
+        OpmlImportError {
+            inner: Context::new(OpmlImportErrorKind::Unknown),
+        }
+    }
+}
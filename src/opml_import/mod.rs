@@ -0,0 +1,356 @@
+mod dialog;
+mod error;
+
+pub use self::dialog::OpmlImportDialog;
+pub use self::error::{OpmlImportError, OpmlImportErrorKind};
+use crate::util::{FileUtil, RUNTIME_ERROR};
+use failure::ResultExt;
+use gtk::FileFilter;
+use libxml::parser::Parser;
+use libxml::tree::{Node, NodeType};
+use news_flash::models::{Category, CategoryID, Feed, Url, NEWSFLASH_TOPLEVEL};
+use news_flash::NewsFlash;
+use reqwest::Client;
+use std::path::PathBuf;
+use tokio::runtime::Runtime;
+
+#[derive(Clone, Debug)]
+pub struct OpmlFeed {
+    pub title: String,
+    pub url: String,
+    // a feed with the same url is already part of the account
+    pub subscribed: bool,
+    pub selected: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CategoryTarget {
+    New(String),
+    Existing(CategoryID),
+    Uncategorized,
+}
+
+#[derive(Clone, Debug)]
+pub struct OpmlCategory {
+    // `None` for feeds outside of any category in the file
+    pub title: Option<String>,
+    // index of the category this one is nested in, it always comes first
+    pub parent: Option<usize>,
+    pub target: CategoryTarget,
+    pub feeds: Vec<OpmlFeed>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ImportReport {
+    pub title: String,
+    pub url: String,
+    pub error: Option<String>,
+}
+
+// Categories are listed in the order of the file, each after the category it is nested in.
+// Feeds belong to the category they are directly listed in.
+#[derive(Clone, Debug, Default)]
+pub struct OpmlImport {
+    pub categories: Vec<OpmlCategory>,
+}
+
+impl OpmlImport {
+    // read and parse an OPML file and prepare it for the feeds and categories of the account
+    pub fn open(path: &PathBuf, news_flash: &NewsFlash) -> Result<(Self, Vec<Category>), OpmlImportError> {
+        let opml = FileUtil::read_text_file(path).context(OpmlImportErrorKind::Read)?;
+        let mut import = Self::parse(&opml)?;
+        let (feeds, _mappings) = news_flash.get_feeds().context(OpmlImportErrorKind::DataBase)?;
+        let categories = news_flash.get_categories().context(OpmlImportErrorKind::DataBase)?;
+        import.prepare(&feeds, &categories);
+        Ok((import, categories))
+    }
+
+    pub fn file_filter() -> FileFilter {
+        let filter = FileFilter::new();
+        filter.add_pattern("*.OPML");
+        filter.add_pattern("*.opml");
+        filter.add_mime_type("application/xml");
+        filter.add_mime_type("text/xml");
+        filter.add_mime_type("text/x-opml");
+        filter.set_name(Some("OPML"));
+        filter
+    }
+
+    pub fn parse(opml: &str) -> Result<Self, OpmlImportError> {
+        let document = Parser::default()
+            .parse_string(opml)
+            .map_err(|_| OpmlImportErrorKind::Parse)?;
+        let root = document.get_root_element().ok_or(OpmlImportErrorKind::Parse)?;
+        if !root.get_name().eq_ignore_ascii_case("opml") {
+            return Err(OpmlImportErrorKind::Parse.into());
+        }
+
+        let mut import = OpmlImport::default();
+        for body in Self::elements(&root, "body") {
+            import.collect(&body, None);
+        }
+        if import.categories.iter().all(|category| category.feeds.is_empty()) {
+            return Err(OpmlImportErrorKind::NoFeeds.into());
+        }
+        Ok(import)
+    }
+
+    fn collect(&mut self, node: &Node, parent: Option<usize>) {
+        for outline in Self::elements(node, "outline") {
+            let text = Self::attribute(&outline, "title").or_else(|| Self::attribute(&outline, "text"));
+            match Self::attribute(&outline, "xmlUrl") {
+                Some(url) => {
+                    let feed = OpmlFeed {
+                        title: text.unwrap_or_else(|| url.clone()),
+                        url,
+                        subscribed: false,
+                        selected: true,
+                    };
+                    let index = match parent {
+                        Some(index) => index,
+                        None => self.category_index(None, None),
+                    };
+                    self.categories[index].feeds.push(feed);
+                }
+                // outlines without a title only group their children, they stay in the parent category
+                None => match text {
+                    Some(title) => {
+                        let index = self.category_index(Some(title), parent);
+                        self.collect(&outline, Some(index));
+                    }
+                    None => self.collect(&outline, parent),
+                },
+            }
+        }
+    }
+
+    // categories of the same title are merged only if they are nested in the same category
+    fn category_index(&mut self, title: Option<String>, parent: Option<usize>) -> usize {
+        match self
+            .categories
+            .iter()
+            .position(|category| category.title == title && category.parent == parent)
+        {
+            Some(index) => index,
+            None => {
+                self.categories.push(OpmlCategory {
+                    target: match &title {
+                        Some(title) => CategoryTarget::New(title.clone()),
+                        None => CategoryTarget::Uncategorized,
+                    },
+                    title,
+                    parent,
+                    feeds: Vec::new(),
+                });
+                self.categories.len() - 1
+            }
+        }
+    }
+
+    pub fn depth(&self, index: usize) -> usize {
+        let mut depth = 0;
+        let mut parent = self.categories[index].parent;
+        while let Some(index) = parent {
+            depth += 1;
+            parent = self.categories[index].parent;
+        }
+        depth
+    }
+
+    fn elements(node: &Node, name: &str) -> Vec<Node> {
+        node.get_child_nodes()
+            .into_iter()
+            .filter(|child| child.get_type() == Some(NodeType::ElementNode))
+            .filter(|child| child.get_name().eq_ignore_ascii_case(name))
+            .collect()
+    }
+
+    // OPML files in the wild don't agree on the case of attribute names
+    fn attribute(node: &Node, name: &str) -> Option<String> {
+        node.get_properties()
+            .into_iter()
+            .find(|(key, _value)| key.eq_ignore_ascii_case(name))
+            .map(|(_key, value)| value.trim().to_owned())
+            .filter(|value| !value.is_empty())
+    }
+
+    // Deselect feeds that are already subscribed and import into existing categories of the same name
+    // at the same place in the tree.
+    pub fn prepare(&mut self, feeds: &[Feed], categories: &[Category]) {
+        for index in 0..self.categories.len() {
+            let parent_id = match self.categories[index].parent {
+                None => Some(NEWSFLASH_TOPLEVEL.clone()),
+                Some(parent) => match &self.categories[parent].target {
+                    CategoryTarget::Existing(category_id) => Some(category_id.clone()),
+                    _ => None,
+                },
+            };
+            let category = &mut self.categories[index];
+            let existing = match (&category.target, &parent_id) {
+                (CategoryTarget::New(title), Some(parent_id)) => categories.iter().find(|existing| {
+                    &existing.parent_id == parent_id && existing.label.to_lowercase() == title.to_lowercase()
+                }),
+                _ => None,
+            };
+            if let Some(existing) = existing {
+                category.target = CategoryTarget::Existing(existing.category_id.clone());
+            }
+            for opml_feed in &mut category.feeds {
+                let url = Self::normalize_url(&opml_feed.url);
+                opml_feed.subscribed = feeds
+                    .iter()
+                    .filter_map(|feed| feed.feed_url.as_ref())
+                    .any(|feed_url| Self::normalize_url(feed_url.get().as_str()) == url);
+                opml_feed.selected = !opml_feed.subscribed;
+            }
+        }
+    }
+
+    fn normalize_url(url: &str) -> String {
+        url.trim().trim_end_matches('/').to_lowercase()
+    }
+
+    // feeds are added one by one, so a broken feed doesn't stop the others from being imported
+    pub fn import(&self, news_flash: &NewsFlash, client: &Client) -> Vec<ImportReport> {
+        let mut runtime = Runtime::new().expect(RUNTIME_ERROR);
+        let mut reports = Vec::new();
+
+        // a category without selected feeds is still needed for the categories nested in it
+        let mut needed: Vec<bool> = self
+            .categories
+            .iter()
+            .map(|category| category.feeds.iter().any(|feed| feed.selected))
+            .collect();
+        for index in (0..self.categories.len()).rev() {
+            if let (true, Some(parent)) = (needed[index], self.categories[index].parent) {
+                needed[parent] = true;
+            }
+        }
+
+        let mut category_ids: Vec<Result<Option<CategoryID>, String>> = Vec::new();
+        for (index, category) in self.categories.iter().enumerate() {
+            let category_id = if !needed[index] {
+                Ok(None)
+            } else {
+                match &category.target {
+                    CategoryTarget::Uncategorized => Ok(None),
+                    CategoryTarget::Existing(category_id) => Ok(Some(category_id.clone())),
+                    CategoryTarget::New(title) => {
+                        let parent_id = match category.parent.map(|parent| &category_ids[parent]) {
+                            Some(Err(error)) => Err(error.clone()),
+                            Some(Ok(parent_id)) => Ok(parent_id.clone()),
+                            None => Ok(None),
+                        };
+                        parent_id.and_then(|parent_id| {
+                            runtime
+                                .block_on(news_flash.add_category(title, parent_id.as_ref(), None, client))
+                                .map(|new_category| Some(new_category.category_id))
+                                .map_err(|error| error.to_string())
+                        })
+                    }
+                }
+            };
+
+            for feed in category.feeds.iter().filter(|feed| feed.selected) {
+                let result = match (&category_id, Url::parse(&feed.url)) {
+                    (Err(error), _) => Err(error.clone()),
+                    (Ok(_), Err(error)) => Err(error.to_string()),
+                    (Ok(category_id), Ok(url)) => runtime
+                        .block_on(news_flash.add_feed(&url, Some(feed.title.clone()), category_id.clone(), client))
+                        .map(|_| ())
+                        .map_err(|error| error.to_string()),
+                };
+                reports.push(ImportReport {
+                    title: feed.title.clone(),
+                    url: feed.url.clone(),
+                    error: result.err(),
+                });
+            }
+            category_ids.push(category_id);
+        }
+
+        reports
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CategoryTarget, OpmlImport};
+
+    #[test]
+    fn parse_categories() {
+        let opml = r#"<?xml version="1.0"?>
+<opml version="2.0">
+  <head><title>Subscriptions</title></head>
+  <body>
+    <outline text="Planet GNOME" xmlUrl="https://planet.gnome.org/rss20.xml"/>
+    <outline text="News">
+      <outline title="LWN" text="lwn" type="rss" xmlurl="https://lwn.net/headlines/rss"/>
+      <outline text="Tech">
+        <outline xmlUrl="https://example.com/feed"/>
+      </outline>
+    </outline>
+  </body>
+</opml>"#;
+
+        let import = OpmlImport::parse(opml).expect("valid opml");
+        assert_eq!(import.categories.len(), 3);
+        assert_eq!(import.categories[0].title, None);
+        assert_eq!(import.categories[0].target, CategoryTarget::Uncategorized);
+        assert_eq!(import.categories[0].feeds[0].title, "Planet GNOME");
+        assert_eq!(import.categories[1].target, CategoryTarget::New("News".to_owned()));
+        assert_eq!(import.categories[1].feeds[0].title, "LWN");
+        assert_eq!(import.categories[1].feeds[0].url, "https://lwn.net/headlines/rss");
+        assert_eq!(import.categories[2].title.as_deref(), Some("Tech"));
+        assert_eq!(import.categories[2].parent, Some(1));
+        assert_eq!(import.depth(2), 1);
+        assert_eq!(import.categories[2].feeds[0].title, "https://example.com/feed");
+        assert!(import
+            .categories
+            .iter()
+            .all(|category| category.feeds.iter().all(|feed| feed.selected)));
+    }
+
+    #[test]
+    fn keep_nesting() {
+        let opml = r#"<opml version="2.0">
+  <body>
+    <outline text="Linux">
+      <outline text="News">
+        <outline text="LWN" xmlUrl="https://lwn.net/headlines/rss"/>
+      </outline>
+    </outline>
+    <outline text="News">
+      <outline text="Example" xmlUrl="https://example.com/feed"/>
+    </outline>
+    <outline text="Linux">
+      <outline>
+        <outline text="Phoronix" xmlUrl="https://www.phoronix.com/rss.php"/>
+      </outline>
+    </outline>
+  </body>
+</opml>"#;
+
+        let import = OpmlImport::parse(opml).expect("valid opml");
+        let titles: Vec<(Option<&str>, Option<usize>)> = import
+            .categories
+            .iter()
+            .map(|category| (category.title.as_deref(), category.parent))
+            .collect();
+        assert_eq!(
+            titles,
+            vec![(Some("Linux"), None), (Some("News"), Some(0)), (Some("News"), None)]
+        );
+        assert_eq!(import.categories[0].feeds[0].title, "Phoronix");
+        assert_eq!(import.categories[1].feeds[0].title, "LWN");
+        assert_eq!(import.categories[2].feeds[0].title, "Example");
+        assert_eq!(import.depth(1), 1);
+        assert_eq!(import.depth(2), 0);
+    }
+
+    #[test]
+    fn reject_invalid() {
+        assert!(OpmlImport::parse("<html><body></body></html>").is_err());
+        assert!(OpmlImport::parse("<opml><body><outline text=\"Empty\"/></body></opml>").is_err());
+    }
+}
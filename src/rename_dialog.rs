@@ -22,6 +22,7 @@ impl RenameDialog {
             SidebarSelection::Category(_, _) => header.set_title(Some("Rename Category")),
             SidebarSelection::Feed(_, _, _) => header.set_title(Some("Rename Feed")),
             SidebarSelection::Tag(_, _) => header.set_title(Some("Rename Feed")),
+            SidebarSelection::SmartFolder(_, _) => header.set_title(Some("Rename Smart Folder")),
         }
 
         rename_entry.set_text(match item {
@@ -29,6 +30,7 @@ impl RenameDialog {
             SidebarSelection::Category(_, name) => name,
             SidebarSelection::Feed(_, _, name) => name,
             SidebarSelection::Tag(_, name) => name,
+            SidebarSelection::SmartFolder(_, name) => name,
         });
 
         dialog.set_transient_for(Some(parent));
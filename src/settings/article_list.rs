@@ -5,12 +5,22 @@ use std::default::Default;
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ArticleListSettings {
     pub order: ArticleOrder,
+    #[serde(default = "default_mark_older_than_days")]
+    pub mark_older_than_days: u32,
+    #[serde(default)]
+    pub mark_read_on_scroll: bool,
+}
+
+fn default_mark_older_than_days() -> u32 {
+    7
 }
 
 impl Default for ArticleListSettings {
     fn default() -> Self {
         ArticleListSettings {
             order: ArticleOrder::NewestFirst,
+            mark_older_than_days: default_mark_older_than_days(),
+            mark_read_on_scroll: false,
         }
     }
 }
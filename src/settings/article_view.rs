@@ -1,5 +1,6 @@
 use crate::article_view::ArticleTheme;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::default::Default;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -9,6 +10,73 @@ pub struct ArticleViewSettings {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub font: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub monospace_font: Option<String>,
+    // maximum width of the article content in em
+    #[serde(default = "default_line_width")]
+    pub line_width: u32,
+    #[serde(default = "default_line_height")]
+    pub line_height: f64,
+    // space between paragraphs in rem
+    #[serde(default = "default_paragraph_spacing")]
+    pub paragraph_spacing: f64,
+    #[serde(default)]
+    pub justify: bool,
+    // appended to the css of every rendered article
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub custom_css: Option<String>,
+    // per feed overrides, keyed by feed id
+    #[serde(default)]
+    pub feeds: HashMap<String, FeedViewSettings>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeedViewSettings {
+    #[serde(default)]
+    pub scraped_content: bool,
+    #[serde(default)]
+    pub zoom: Option<f64>,
+    #[serde(default)]
+    pub theme: Option<ArticleTheme>,
+    #[serde(default)]
+    pub open_in_browser: bool,
+    #[serde(default)]
+    pub scrape_on_sync: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeedViewSetting {
+    ScrapedContent(bool),
+    Zoom(Option<f64>),
+    Theme(Option<ArticleTheme>),
+    OpenInBrowser(bool),
+    ScrapeOnSync(bool),
+}
+
+impl FeedViewSettings {
+    pub fn apply(&mut self, setting: FeedViewSetting) {
+        match setting {
+            FeedViewSetting::ScrapedContent(scraped_content) => self.scraped_content = scraped_content,
+            FeedViewSetting::Zoom(zoom) => self.zoom = zoom,
+            FeedViewSetting::Theme(theme) => self.theme = theme,
+            FeedViewSetting::OpenInBrowser(open_in_browser) => self.open_in_browser = open_in_browser,
+            FeedViewSetting::ScrapeOnSync(scrape_on_sync) => self.scrape_on_sync = scrape_on_sync,
+        }
+    }
+}
+
+fn default_line_width() -> u32 {
+    50
+}
+
+fn default_line_height() -> f64 {
+    1.8
+}
+
+fn default_paragraph_spacing() -> f64 {
+    1.0
 }
 
 impl Default for ArticleViewSettings {
@@ -17,6 +85,13 @@ impl Default for ArticleViewSettings {
             theme: ArticleTheme::Default,
             allow_select: false,
             font: None,
+            monospace_font: None,
+            line_width: default_line_width(),
+            line_height: default_line_height(),
+            paragraph_spacing: default_paragraph_spacing(),
+            justify: false,
+            custom_css: None,
+            feeds: HashMap::new(),
         }
     }
 }
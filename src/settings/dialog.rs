@@ -1,21 +1,26 @@
-use super::general::SyncInterval;
+use super::general::{QuietHours, SyncInterval};
 use super::keybinding_editor::{KeybindState, KeybindingEditor};
 use super::keybindings::Keybindings;
 use super::theme_chooser::ThemeChooser;
 use crate::app::Action;
-use crate::settings::Settings;
+use crate::article_view::ArticleView;
+use crate::i18n::i18n;
+use crate::settings::{OfflineArticles, Settings};
 use crate::util::{BuilderHelper, GtkUtil, Util, GTK_BUILDER_ERROR};
 use gdk::{EventMask, EventType};
 use glib::{clone, object::Cast, translate::ToGlib, Sender};
 use gtk::{
-    prelude::GtkWindowExtManual, prelude::WidgetExtManual, DialogExt, EventBox, FontButton, FontButtonExt,
-    FontChooserExt, GtkWindowExt, Inhibit, Label, LabelExt, ListBox, ListBoxExt, ListBoxRowExt, Popover, PopoverExt,
-    Settings as GtkSettings, SettingsExt as GtkSettingsExt, Switch, SwitchExt, Widget, WidgetExt, Window,
+    prelude::GtkWindowExtManual, prelude::WidgetExtManual, ComboBoxExt, ComboBoxText, DialogExt, EditableSignals,
+    Entry, EntryExt, EventBox, FontButton, FontButtonExt, FontChooserExt, GtkWindowExt, Inhibit, Label, LabelExt,
+    ListBox, ListBoxExt, ListBoxRowExt, Popover, PopoverExt, Settings as GtkSettings, SettingsExt as GtkSettingsExt,
+    SpinButton, SpinButtonExt, SpinButtonSignals, StyleContextExt, Switch, SwitchExt, TextBufferExt, TextView,
+    TextViewExt, Widget, WidgetExt, Window,
 };
 use libhandy::{ActionRow, PreferencesRowExt};
 use news_flash::models::ArticleOrder;
 use parking_lot::RwLock;
 use std::sync::Arc;
+use webkit2gtk::{WebView, WebViewExt};
 
 pub struct SettingsDialog {
     pub widget: Window,
@@ -33,6 +38,25 @@ pub struct SettingsDialog {
     sync_event: EventBox,
     sync_event_signal: Arc<RwLock<Option<usize>>>,
     sync_row: ActionRow,
+    custom_sync_spin: SpinButton,
+    custom_sync_signal: Arc<RwLock<Option<usize>>>,
+    quiet_hours_switch: Switch,
+    quiet_hours_signal: Arc<RwLock<Option<usize>>>,
+    quiet_hours_row: ActionRow,
+    quiet_start_entry: Entry,
+    quiet_start_signal: Arc<RwLock<Option<usize>>>,
+    quiet_end_entry: Entry,
+    quiet_end_signal: Arc<RwLock<Option<usize>>>,
+    adaptive_sync_switch: Switch,
+    adaptive_sync_signal: Arc<RwLock<Option<usize>>>,
+    offline_prepare_switch: Switch,
+    offline_prepare_signal: Arc<RwLock<Option<usize>>>,
+    offline_articles_combo: ComboBoxText,
+    offline_articles_signal: Arc<RwLock<Option<usize>>>,
+    offline_scrape_switch: Switch,
+    offline_scrape_signal: Arc<RwLock<Option<usize>>>,
+    offline_budget_spin: SpinButton,
+    offline_budget_signal: Arc<RwLock<Option<usize>>>,
     article_order_pop: Popover,
     article_order_list: ListBox,
     article_order_list_signal: Arc<RwLock<Option<usize>>>,
@@ -41,6 +65,8 @@ pub struct SettingsDialog {
     article_order_event_signal: Arc<RwLock<Option<usize>>>,
     article_order_row: ActionRow,
     article_order_listbox_signal: Arc<RwLock<Option<usize>>>,
+    mark_read_on_scroll_switch: Switch,
+    mark_read_on_scroll_signal: Arc<RwLock<Option<usize>>>,
     article_theme_label: Label,
     article_theme_row: ActionRow,
     article_theme_listbox_signal: Arc<RwLock<Option<usize>>>,
@@ -53,6 +79,22 @@ pub struct SettingsDialog {
     font_button_signal: Arc<RwLock<Option<usize>>>,
     use_system_font_switch: Switch,
     use_system_font_switch_signal: Arc<RwLock<Option<usize>>>,
+    line_width_spin: SpinButton,
+    line_width_signal: Arc<RwLock<Option<usize>>>,
+    line_height_spin: SpinButton,
+    line_height_signal: Arc<RwLock<Option<usize>>>,
+    paragraph_spacing_spin: SpinButton,
+    paragraph_spacing_signal: Arc<RwLock<Option<usize>>>,
+    justify_switch: Switch,
+    justify_signal: Arc<RwLock<Option<usize>>>,
+    monospace_font_row: ActionRow,
+    monospace_font_button: FontButton,
+    monospace_font_button_signal: Arc<RwLock<Option<usize>>>,
+    use_system_monospace_font_switch: Switch,
+    use_system_monospace_font_switch_signal: Arc<RwLock<Option<usize>>>,
+    typography_preview: WebView,
+    custom_css_view: TextView,
+    custom_css_signal: Arc<RwLock<Option<usize>>>,
     settings: Arc<RwLock<Settings>>,
     keybind_signals: Arc<RwLock<Vec<(usize, Widget)>>>,
     builder: BuilderHelper,
@@ -61,6 +103,7 @@ pub struct SettingsDialog {
 impl SettingsDialog {
     pub fn new(window: &gtk::ApplicationWindow, sender: &Sender<Action>, settings: &Arc<RwLock<Settings>>) -> Self {
         let have_custom_font = settings.read().get_article_view_font().is_some();
+        let have_custom_monospace_font = settings.read().get_article_view_monospace_font().is_some();
 
         let builder = BuilderHelper::new("settings");
 
@@ -83,6 +126,35 @@ impl SettingsDialog {
         sync_event.set_events(EventMask::BUTTON_PRESS_MASK);
 
         let sync_row = builder.get::<ActionRow>("sync_row");
+
+        let custom_sync_spin = builder.get::<SpinButton>("custom_sync_spin");
+        if let Some(minutes) = settings.read().get_sync_interval().to_minutes() {
+            custom_sync_spin.set_value(f64::from(minutes));
+        }
+
+        let quiet_hours = settings.read().get_quiet_hours();
+        let quiet_hours_switch = builder.get::<Switch>("quiet_hours_switch");
+        quiet_hours_switch.set_state(quiet_hours.enabled);
+        let quiet_hours_row = builder.get::<ActionRow>("quiet_hours_row");
+        quiet_hours_row.set_sensitive(quiet_hours.enabled);
+        let quiet_start_entry = builder.get::<Entry>("quiet_start_entry");
+        quiet_start_entry.set_text(&QuietHours::format_time(quiet_hours.start));
+        let quiet_end_entry = builder.get::<Entry>("quiet_end_entry");
+        quiet_end_entry.set_text(&QuietHours::format_time(quiet_hours.end));
+
+        let adaptive_sync_switch = builder.get::<Switch>("adaptive_sync_switch");
+        adaptive_sync_switch.set_state(settings.read().get_sync_schedule().adaptive);
+
+        let offline_settings = settings.read().get_offline_settings();
+        let offline_prepare_switch = builder.get::<Switch>("offline_prepare_switch");
+        offline_prepare_switch.set_state(offline_settings.prepare_after_sync);
+        let offline_articles_combo = builder.get::<ComboBoxText>("offline_articles_combo");
+        offline_articles_combo.set_active_id(Some(offline_settings.articles.to_id()));
+        let offline_scrape_switch = builder.get::<Switch>("offline_scrape_switch");
+        offline_scrape_switch.set_state(offline_settings.scrape_content);
+        let offline_budget_spin = builder.get::<SpinButton>("offline_budget_spin");
+        offline_budget_spin.set_value(f64::from(offline_settings.budget));
+
         let article_order_pop = builder.get::<Popover>("article_order_pop");
 
         let article_order_label = builder.get::<Label>("article_order_label");
@@ -95,12 +167,15 @@ impl SettingsDialog {
         article_order_event.set_events(EventMask::BUTTON_PRESS_MASK);
 
         let article_theme_label = builder.get::<Label>("article_theme_label");
-        article_theme_label.set_label(settings.read().get_article_view_theme().name());
+        article_theme_label.set_label(&settings.read().get_article_view_theme().name());
 
         let article_theme_row = builder.get::<ActionRow>("article_theme_row");
         let article_theme_event = builder.get::<EventBox>("article_theme_event");
         article_theme_event.set_events(EventMask::BUTTON_PRESS_MASK);
 
+        let mark_read_on_scroll_switch = builder.get::<Switch>("mark_read_on_scroll_switch");
+        mark_read_on_scroll_switch.set_state(settings.read().get_article_list_mark_read_on_scroll());
+
         let allow_selection_switch = builder.get::<Switch>("allow_selection_switch");
         allow_selection_switch.set_state(settings.read().get_article_view_allow_select());
 
@@ -116,6 +191,40 @@ impl SettingsDialog {
         let use_system_font_switch = builder.get::<Switch>("use_system_font_switch");
         use_system_font_switch.set_state(!have_custom_font);
 
+        let line_width_spin = builder.get::<SpinButton>("line_width_spin");
+        line_width_spin.set_value(f64::from(settings.read().get_article_view_line_width()));
+
+        let line_height_spin = builder.get::<SpinButton>("line_height_spin");
+        line_height_spin.set_value(settings.read().get_article_view_line_height());
+
+        let paragraph_spacing_spin = builder.get::<SpinButton>("paragraph_spacing_spin");
+        paragraph_spacing_spin.set_value(settings.read().get_article_view_paragraph_spacing());
+
+        let justify_switch = builder.get::<Switch>("justify_switch");
+        justify_switch.set_state(settings.read().get_article_view_justify());
+
+        let monospace_font_row = builder.get::<ActionRow>("monospace_font_row");
+        monospace_font_row.set_sensitive(have_custom_monospace_font);
+
+        let monospace_font_button = builder.get::<FontButton>("monospace_font_button");
+        monospace_font_button.set_sensitive(have_custom_monospace_font);
+        if let Some(font) = settings.read().get_article_view_monospace_font() {
+            monospace_font_button.set_font(&font);
+        }
+
+        let use_system_monospace_font_switch = builder.get::<Switch>("use_system_monospace_font_switch");
+        use_system_monospace_font_switch.set_state(!have_custom_monospace_font);
+
+        let typography_preview = builder.get::<WebView>("typography_preview");
+        Self::update_typography_preview(&typography_preview, settings);
+
+        let custom_css_view = builder.get::<TextView>("custom_css_view");
+        if let Some(css) = settings.read().get_article_view_custom_css() {
+            if let Some(buffer) = custom_css_view.get_buffer() {
+                buffer.set_text(&css);
+            }
+        }
+
         let settings_dialog = SettingsDialog {
             widget: dialog,
             delete_signal: Arc::new(RwLock::new(None)),
@@ -132,6 +241,25 @@ impl SettingsDialog {
             sync_event,
             sync_event_signal: Arc::new(RwLock::new(None)),
             sync_row,
+            custom_sync_spin,
+            custom_sync_signal: Arc::new(RwLock::new(None)),
+            quiet_hours_switch,
+            quiet_hours_signal: Arc::new(RwLock::new(None)),
+            quiet_hours_row,
+            quiet_start_entry,
+            quiet_start_signal: Arc::new(RwLock::new(None)),
+            quiet_end_entry,
+            quiet_end_signal: Arc::new(RwLock::new(None)),
+            adaptive_sync_switch,
+            adaptive_sync_signal: Arc::new(RwLock::new(None)),
+            offline_prepare_switch,
+            offline_prepare_signal: Arc::new(RwLock::new(None)),
+            offline_articles_combo,
+            offline_articles_signal: Arc::new(RwLock::new(None)),
+            offline_scrape_switch,
+            offline_scrape_signal: Arc::new(RwLock::new(None)),
+            offline_budget_spin,
+            offline_budget_signal: Arc::new(RwLock::new(None)),
             article_order_pop,
             article_order_label,
             article_order_list,
@@ -140,6 +268,8 @@ impl SettingsDialog {
             article_order_event_signal: Arc::new(RwLock::new(None)),
             article_order_row,
             article_order_listbox_signal: Arc::new(RwLock::new(None)),
+            mark_read_on_scroll_switch,
+            mark_read_on_scroll_signal: Arc::new(RwLock::new(None)),
             article_theme_label,
             article_theme_row,
             article_theme_listbox_signal: Arc::new(RwLock::new(None)),
@@ -152,6 +282,22 @@ impl SettingsDialog {
             font_button_signal: Arc::new(RwLock::new(None)),
             use_system_font_switch,
             use_system_font_switch_signal: Arc::new(RwLock::new(None)),
+            line_width_spin,
+            line_width_signal: Arc::new(RwLock::new(None)),
+            line_height_spin,
+            line_height_signal: Arc::new(RwLock::new(None)),
+            paragraph_spacing_spin,
+            paragraph_spacing_signal: Arc::new(RwLock::new(None)),
+            justify_switch,
+            justify_signal: Arc::new(RwLock::new(None)),
+            monospace_font_row,
+            monospace_font_button,
+            monospace_font_button_signal: Arc::new(RwLock::new(None)),
+            use_system_monospace_font_switch,
+            use_system_monospace_font_switch_signal: Arc::new(RwLock::new(None)),
+            typography_preview,
+            custom_css_view,
+            custom_css_signal: Arc::new(RwLock::new(None)),
             settings: settings.clone(),
             keybind_signals: Arc::new(RwLock::new(Vec::new())),
             builder,
@@ -180,6 +326,24 @@ impl SettingsDialog {
                     @strong self.sync_listbox_signal as sync_listbox_signal,
                     @weak self.sync_event as sync_event,
                     @strong self.sync_event_signal as sync_event_signal,
+                    @weak self.custom_sync_spin as custom_sync_spin,
+                    @strong self.custom_sync_signal as custom_sync_signal,
+                    @weak self.quiet_hours_switch as quiet_hours_switch,
+                    @strong self.quiet_hours_signal as quiet_hours_signal,
+                    @weak self.quiet_start_entry as quiet_start_entry,
+                    @strong self.quiet_start_signal as quiet_start_signal,
+                    @weak self.quiet_end_entry as quiet_end_entry,
+                    @strong self.quiet_end_signal as quiet_end_signal,
+                    @weak self.adaptive_sync_switch as adaptive_sync_switch,
+                    @strong self.adaptive_sync_signal as adaptive_sync_signal,
+                    @weak self.offline_prepare_switch as offline_prepare_switch,
+                    @strong self.offline_prepare_signal as offline_prepare_signal,
+                    @weak self.offline_articles_combo as offline_articles_combo,
+                    @strong self.offline_articles_signal as offline_articles_signal,
+                    @weak self.offline_scrape_switch as offline_scrape_switch,
+                    @strong self.offline_scrape_signal as offline_scrape_signal,
+                    @weak self.offline_budget_spin as offline_budget_spin,
+                    @strong self.offline_budget_signal as offline_budget_signal,
                     @weak self.article_order_list as article_order_list,
                     @strong self.article_order_list_signal as article_order_list_signal,
                     @weak self.article_order_event as article_order_event,
@@ -192,22 +356,61 @@ impl SettingsDialog {
                     @strong self.article_theme_listbox_signal as article_theme_listbox_signal,
                     @weak self.allow_selection_switch as allow_selection_switch,
                     @strong self.allow_selection_switch_signal as allow_selection_switch_signal,
+                    @weak self.mark_read_on_scroll_switch as mark_read_on_scroll_switch,
+                    @strong self.mark_read_on_scroll_signal as mark_read_on_scroll_signal,
                     @weak self.use_system_font_switch as use_system_font_switch,
                     @strong self.use_system_font_switch_signal as use_system_font_switch_signal,
                     @strong self.keybind_signals as keybind_signals,
                     @weak self.font_button as font_button,
-                    @strong self.font_button_signal as font_button_signal => @default-panic, move |dialog, _event| {
+                    @strong self.font_button_signal as font_button_signal,
+                    @weak self.line_width_spin as line_width_spin,
+                    @strong self.line_width_signal as line_width_signal,
+                    @weak self.line_height_spin as line_height_spin,
+                    @strong self.line_height_signal as line_height_signal,
+                    @weak self.paragraph_spacing_spin as paragraph_spacing_spin,
+                    @strong self.paragraph_spacing_signal as paragraph_spacing_signal,
+                    @weak self.justify_switch as justify_switch,
+                    @strong self.justify_signal as justify_signal,
+                    @weak self.monospace_font_button as monospace_font_button,
+                    @strong self.monospace_font_button_signal as monospace_font_button_signal,
+                    @weak self.use_system_monospace_font_switch as use_system_monospace_font_switch,
+                    @strong self.use_system_monospace_font_switch_signal as use_system_monospace_font_switch_signal,
+                    @weak self.custom_css_view as custom_css_view,
+                    @strong self.custom_css_signal as custom_css_signal,
+                    @weak self.settings as settings,
+                    @strong sender => @default-panic, move |dialog, _event| {
                         GtkUtil::disconnect_signal(*delete_signal.read(), dialog);
                         GtkUtil::disconnect_signal(*keep_running_signal.read(), &keep_running_switch);
                         GtkUtil::disconnect_signal(*dark_theme_signal.read(), &dark_theme_switch);
                         GtkUtil::disconnect_signal(*sync_list_signal.read(), &sync_list);
                         GtkUtil::disconnect_signal(*sync_event_signal.read(), &sync_event);
+                        GtkUtil::disconnect_signal(*custom_sync_signal.read(), &custom_sync_spin);
+                        GtkUtil::disconnect_signal(*quiet_hours_signal.read(), &quiet_hours_switch);
+                        GtkUtil::disconnect_signal(*quiet_start_signal.read(), &quiet_start_entry);
+                        GtkUtil::disconnect_signal(*quiet_end_signal.read(), &quiet_end_entry);
+                        GtkUtil::disconnect_signal(*adaptive_sync_signal.read(), &adaptive_sync_switch);
+                        GtkUtil::disconnect_signal(*offline_prepare_signal.read(), &offline_prepare_switch);
+                        GtkUtil::disconnect_signal(*offline_articles_signal.read(), &offline_articles_combo);
+                        GtkUtil::disconnect_signal(*offline_scrape_signal.read(), &offline_scrape_switch);
+                        GtkUtil::disconnect_signal(*offline_budget_signal.read(), &offline_budget_spin);
                         GtkUtil::disconnect_signal(*article_order_list_signal.read(), &article_order_list);
                         GtkUtil::disconnect_signal(*article_order_event_signal.read(), &article_order_event);
                         GtkUtil::disconnect_signal(*article_theme_event_signal.read(), &article_theme_event);
                         GtkUtil::disconnect_signal(*allow_selection_switch_signal.read(), &allow_selection_switch);
+                        GtkUtil::disconnect_signal(*mark_read_on_scroll_signal.read(), &mark_read_on_scroll_switch);
                         GtkUtil::disconnect_signal(*use_system_font_switch_signal.read(), &use_system_font_switch);
                         GtkUtil::disconnect_signal(*font_button_signal.read(), &font_button);
+                        GtkUtil::disconnect_signal(*line_width_signal.read(), &line_width_spin);
+                        GtkUtil::disconnect_signal(*line_height_signal.read(), &line_height_spin);
+                        GtkUtil::disconnect_signal(*paragraph_spacing_signal.read(), &paragraph_spacing_spin);
+                        GtkUtil::disconnect_signal(*justify_signal.read(), &justify_switch);
+                        GtkUtil::disconnect_signal(*monospace_font_button_signal.read(), &monospace_font_button);
+                        GtkUtil::disconnect_signal(
+                            *use_system_monospace_font_switch_signal.read(),
+                            &use_system_monospace_font_switch,
+                        );
+                        GtkUtil::disconnect_signal(*custom_css_signal.read(), &custom_css_view);
+                        Self::save_custom_css(&custom_css_view, &settings, &sender);
                         if let Some(gtk_settings) = GtkSettings::get_default() {
                             GtkUtil::disconnect_signal(*gtk_dark_theme_signal.read(), &gtk_settings);
                             gtk_dark_theme_signal.write().take();
@@ -235,12 +438,29 @@ impl SettingsDialog {
                         dark_theme_signal.write().take();
                         sync_list_signal.write().take();
                         sync_event_signal.write().take();
+                        custom_sync_signal.write().take();
+                        quiet_hours_signal.write().take();
+                        quiet_start_signal.write().take();
+                        quiet_end_signal.write().take();
+                        adaptive_sync_signal.write().take();
+                        offline_prepare_signal.write().take();
+                        offline_articles_signal.write().take();
+                        offline_scrape_signal.write().take();
+                        offline_budget_signal.write().take();
                         article_order_list_signal.write().take();
                         article_order_event_signal.write().take();
                         article_theme_event_signal.write().take();
                         allow_selection_switch_signal.write().take();
+                        mark_read_on_scroll_signal.write().take();
                         use_system_font_switch_signal.write().take();
                         font_button_signal.write().take();
+                        line_width_signal.write().take();
+                        line_height_signal.write().take();
+                        paragraph_spacing_signal.write().take();
+                        justify_signal.write().take();
+                        monospace_font_button_signal.write().take();
+                        use_system_monospace_font_switch_signal.write().take();
+                        custom_css_signal.write().take();
 
                         for (id, widget) in &*keybind_signals.read() {
                             GtkUtil::disconnect_signal(Some(*id), widget);
@@ -307,6 +527,7 @@ impl SettingsDialog {
                     @weak self.settings as settings,
                     @weak self.sync_pop as sync_pop,
                     @weak self.sync_label as sync_label,
+                    @weak self.custom_sync_spin as custom_sync_spin,
                     @strong sender => @default-panic, move |_list, row| {
                     sync_pop.popdown();
                     let sync_interval = match row.get_index() {
@@ -319,6 +540,9 @@ impl SettingsDialog {
                         _ => SyncInterval::Never,
                     };
                     sync_label.set_label(&sync_interval.to_string());
+                    if let Some(minutes) = sync_interval.to_minutes() {
+                        custom_sync_spin.set_value(f64::from(minutes));
+                    }
                     if settings.write().set_sync_interval(sync_interval).is_ok() {
                         Util::send(&sender, Action::ScheduleSync);
                     } else {
@@ -368,6 +592,166 @@ impl SettingsDialog {
             }
         }
 
+        self.custom_sync_signal.write().replace(
+            self.custom_sync_spin
+                .connect_value_changed(clone!(
+                    @weak self.settings as settings,
+                    @weak self.sync_label as sync_label,
+                    @strong sender => @default-panic, move |spin| {
+                    let sync_interval = SyncInterval::from_minutes(spin.get_value_as_int() as u32);
+                    if settings.read().get_sync_interval() == sync_interval {
+                        return;
+                    }
+                    sync_label.set_label(&sync_interval.to_string());
+                    if settings.write().set_sync_interval(sync_interval).is_ok() {
+                        Util::send(&sender, Action::ScheduleSync);
+                    } else {
+                        Util::send(
+                            &sender,
+                            Action::ErrorSimpleMessage("Failed to set setting 'sync interval'.".to_owned()),
+                        );
+                    }
+                }))
+                .to_glib() as usize,
+        );
+
+        self.quiet_hours_signal.write().replace(
+            self.quiet_hours_switch
+                .connect_state_set(clone!(
+                    @weak self.settings as settings,
+                    @weak self.quiet_hours_row as quiet_hours_row,
+                    @strong sender => @default-panic, move |_switch, is_set|
+                {
+                    let mut quiet_hours = settings.read().get_quiet_hours();
+                    quiet_hours.enabled = is_set;
+                    if settings.write().set_quiet_hours(quiet_hours).is_ok() {
+                        quiet_hours_row.set_sensitive(is_set);
+                    } else {
+                        Util::send(
+                            &sender,
+                            Action::ErrorSimpleMessage("Failed to set setting 'quiet hours'.".to_owned()),
+                        );
+                    }
+                    Inhibit(false)
+                }))
+                .to_glib() as usize,
+        );
+
+        self.quiet_start_signal.write().replace(
+            self.quiet_start_entry
+                .connect_changed(clone!(
+                    @weak self.settings as settings,
+                    @strong sender => @default-panic, move |entry|
+                {
+                    Self::quiet_hours_entry_changed(entry, &settings, &sender, true);
+                }))
+                .to_glib() as usize,
+        );
+
+        self.quiet_end_signal.write().replace(
+            self.quiet_end_entry
+                .connect_changed(clone!(
+                    @weak self.settings as settings,
+                    @strong sender => @default-panic, move |entry|
+                {
+                    Self::quiet_hours_entry_changed(entry, &settings, &sender, false);
+                }))
+                .to_glib() as usize,
+        );
+
+        self.adaptive_sync_signal.write().replace(
+            self.adaptive_sync_switch
+                .connect_state_set(clone!(
+                    @weak self.settings as settings,
+                    @strong sender => @default-panic, move |_switch, is_set|
+                {
+                    if settings.write().set_sync_adaptive(is_set).is_ok() {
+                        Util::send(&sender, Action::ScheduleSync);
+                    } else {
+                        Util::send(
+                            &sender,
+                            Action::ErrorSimpleMessage("Failed to set setting 'adaptive sync'.".to_owned()),
+                        );
+                    }
+                    Inhibit(false)
+                }))
+                .to_glib() as usize,
+        );
+
+        self.offline_prepare_signal.write().replace(
+            self.offline_prepare_switch
+                .connect_state_set(clone!(
+                    @weak self.settings as settings,
+                    @strong sender => @default-panic, move |_switch, is_set|
+                {
+                    if settings.write().set_offline_prepare_after_sync(is_set).is_ok() {
+                        if is_set {
+                            Util::send(&sender, Action::PrepareOffline);
+                        }
+                    } else {
+                        Util::send(
+                            &sender,
+                            Action::ErrorSimpleMessage("Failed to set setting 'prepare offline'.".to_owned()),
+                        );
+                    }
+                    Inhibit(false)
+                }))
+                .to_glib() as usize,
+        );
+
+        self.offline_articles_signal.write().replace(
+            self.offline_articles_combo
+                .connect_changed(clone!(
+                    @weak self.settings as settings,
+                    @strong sender => @default-panic, move |combo|
+                {
+                    let articles = match combo.get_active_id().and_then(|id| OfflineArticles::from_id(&id)) {
+                        Some(articles) => articles,
+                        None => return,
+                    };
+                    if settings.write().set_offline_articles(articles).is_err() {
+                        Util::send(
+                            &sender,
+                            Action::ErrorSimpleMessage("Failed to set setting 'offline articles'.".to_owned()),
+                        );
+                    }
+                }))
+                .to_glib() as usize,
+        );
+
+        self.offline_scrape_signal.write().replace(
+            self.offline_scrape_switch
+                .connect_state_set(clone!(
+                    @weak self.settings as settings,
+                    @strong sender => @default-panic, move |_switch, is_set|
+                {
+                    if settings.write().set_offline_scrape_content(is_set).is_err() {
+                        Util::send(
+                            &sender,
+                            Action::ErrorSimpleMessage("Failed to set setting 'offline content'.".to_owned()),
+                        );
+                    }
+                    Inhibit(false)
+                }))
+                .to_glib() as usize,
+        );
+
+        self.offline_budget_signal.write().replace(
+            self.offline_budget_spin
+                .connect_value_changed(clone!(
+                    @weak self.settings as settings,
+                    @strong sender => @default-panic, move |spin|
+                {
+                    if settings.write().set_offline_budget(spin.get_value_as_int() as u32).is_err() {
+                        Util::send(
+                            &sender,
+                            Action::ErrorSimpleMessage("Failed to set setting 'offline disk space'.".to_owned()),
+                        );
+                    }
+                }))
+                .to_glib() as usize,
+        );
+
         self.article_order_list_signal.write().replace(
             self.article_order_list
                 .connect_row_activated(clone!(
@@ -453,7 +837,7 @@ impl SettingsDialog {
                     {
                         GtkUtil::disconnect_signal(*theme_chooser_close_signal.read(), pop);
                         theme_chooser_close_signal.write().take();
-                        article_theme_label.set_label(settings.read().get_article_view_theme().name());
+                        article_theme_label.set_label(&settings.read().get_article_view_theme().name());
                         Util::send(&sender, Action::RedrawArticle);
                     })).to_glib() as usize);
                     theme_chooser.widget().popup();
@@ -483,7 +867,7 @@ impl SettingsDialog {
                                 {
                                     GtkUtil::disconnect_signal(*theme_chooser_close_signal.read(), pop);
                                     theme_chooser_close_signal.write().take();
-                                    article_theme_label.set_label(settings.read().get_article_view_theme().name());
+                                    article_theme_label.set_label(&settings.read().get_article_view_theme().name());
                                     Util::send(&sender, Action::RedrawArticle);
                                 })).to_glib() as usize);
                                 theme_chooser.widget().popup();
@@ -494,6 +878,23 @@ impl SettingsDialog {
             }
         }
 
+        self.mark_read_on_scroll_signal.write().replace(
+            self.mark_read_on_scroll_switch
+                .connect_state_set(clone!(
+                    @weak self.settings as settings,
+                    @strong sender => @default-panic, move |_switch, is_set|
+                {
+                    if settings.write().set_article_list_mark_read_on_scroll(is_set).is_err() {
+                        Util::send(
+                            &sender,
+                            Action::ErrorSimpleMessage("Failed to set setting 'mark read on scroll'.".to_owned()),
+                        );
+                    }
+                    Inhibit(false)
+                }))
+                .to_glib() as usize,
+        );
+
         self.allow_selection_switch_signal.write().replace(
             self.allow_selection_switch
                 .connect_state_set(clone!(
@@ -515,22 +916,25 @@ impl SettingsDialog {
 
         self.font_button_signal.write().replace(
             self.font_button
-                .connect_font_set(
-                    clone!(@weak self.settings as settings, @strong sender => @default-panic, move |button| {
-                        let font = match button.get_font() {
-                            Some(font) => Some(font.to_string()),
-                            None => None,
-                        };
-                        if settings.write().set_article_view_font(font).is_ok() {
-                            Util::send(&sender, Action::RedrawArticle);
-                        } else {
-                            Util::send(
-                                &sender,
-                                Action::ErrorSimpleMessage("Failed to set setting 'article font'.".to_owned()),
-                            );
-                        }
-                    }),
-                )
+                .connect_font_set(clone!(
+                    @weak self.settings as settings,
+                    @weak self.typography_preview as typography_preview,
+                    @strong sender => @default-panic, move |button|
+                {
+                    let font = match button.get_font() {
+                        Some(font) => Some(font.to_string()),
+                        None => None,
+                    };
+                    if settings.write().set_article_view_font(font).is_ok() {
+                        Self::update_typography_preview(&typography_preview, &settings);
+                        Util::send(&sender, Action::RedrawArticle);
+                    } else {
+                        Util::send(
+                            &sender,
+                            Action::ErrorSimpleMessage("Failed to set setting 'article font'.".to_owned()),
+                        );
+                    }
+                }))
                 .to_glib() as usize,
         );
 
@@ -539,6 +943,7 @@ impl SettingsDialog {
                 .connect_state_set(clone!(
                     @weak self.font_button as font_button,
                     @weak self.font_row as font_row,
+                    @weak self.typography_preview as typography_preview,
                     @weak self.settings as settings,
                     @strong sender => @default-panic, move |_switch, is_set|
                 {
@@ -552,6 +957,7 @@ impl SettingsDialog {
                     font_button.set_sensitive(!is_set);
                     font_row.set_sensitive(!is_set);
                     if settings.write().set_article_view_font(font).is_ok() {
+                        Self::update_typography_preview(&typography_preview, &settings);
                         Util::send(&sender, Action::RedrawArticle);
                     } else {
                         Util::send(
@@ -563,6 +969,216 @@ impl SettingsDialog {
                 }))
                 .to_glib() as usize,
         );
+
+        self.line_width_signal.write().replace(
+            self.line_width_spin
+                .connect_value_changed(clone!(
+                    @weak self.settings as settings,
+                    @weak self.typography_preview as typography_preview,
+                    @strong sender => @default-panic, move |spin|
+                {
+                    if settings.write().set_article_view_line_width(spin.get_value_as_int() as u32).is_ok() {
+                        Self::update_typography_preview(&typography_preview, &settings);
+                        Util::send(&sender, Action::RedrawArticle);
+                    } else {
+                        Util::send(
+                            &sender,
+                            Action::ErrorSimpleMessage("Failed to set setting 'line width'.".to_owned()),
+                        );
+                    }
+                }))
+                .to_glib() as usize,
+        );
+
+        self.line_height_signal.write().replace(
+            self.line_height_spin
+                .connect_value_changed(clone!(
+                    @weak self.settings as settings,
+                    @weak self.typography_preview as typography_preview,
+                    @strong sender => @default-panic, move |spin|
+                {
+                    if settings.write().set_article_view_line_height(spin.get_value()).is_ok() {
+                        Self::update_typography_preview(&typography_preview, &settings);
+                        Util::send(&sender, Action::RedrawArticle);
+                    } else {
+                        Util::send(
+                            &sender,
+                            Action::ErrorSimpleMessage("Failed to set setting 'line height'.".to_owned()),
+                        );
+                    }
+                }))
+                .to_glib() as usize,
+        );
+
+        self.paragraph_spacing_signal.write().replace(
+            self.paragraph_spacing_spin
+                .connect_value_changed(clone!(
+                    @weak self.settings as settings,
+                    @weak self.typography_preview as typography_preview,
+                    @strong sender => @default-panic, move |spin|
+                {
+                    if settings.write().set_article_view_paragraph_spacing(spin.get_value()).is_ok() {
+                        Self::update_typography_preview(&typography_preview, &settings);
+                        Util::send(&sender, Action::RedrawArticle);
+                    } else {
+                        Util::send(
+                            &sender,
+                            Action::ErrorSimpleMessage("Failed to set setting 'paragraph spacing'.".to_owned()),
+                        );
+                    }
+                }))
+                .to_glib() as usize,
+        );
+
+        self.justify_signal.write().replace(
+            self.justify_switch
+                .connect_state_set(clone!(
+                    @weak self.settings as settings,
+                    @weak self.typography_preview as typography_preview,
+                    @strong sender => @default-panic, move |_switch, is_set|
+                {
+                    if settings.write().set_article_view_justify(is_set).is_ok() {
+                        Self::update_typography_preview(&typography_preview, &settings);
+                        Util::send(&sender, Action::RedrawArticle);
+                    } else {
+                        Util::send(
+                            &sender,
+                            Action::ErrorSimpleMessage("Failed to set setting 'justify text'.".to_owned()),
+                        );
+                    }
+                    Inhibit(false)
+                }))
+                .to_glib() as usize,
+        );
+
+        self.monospace_font_button_signal.write().replace(
+            self.monospace_font_button
+                .connect_font_set(clone!(
+                    @weak self.settings as settings,
+                    @weak self.typography_preview as typography_preview,
+                    @strong sender => @default-panic, move |button|
+                {
+                    let font = match button.get_font() {
+                        Some(font) => Some(font.to_string()),
+                        None => None,
+                    };
+                    if settings.write().set_article_view_monospace_font(font).is_ok() {
+                        Self::update_typography_preview(&typography_preview, &settings);
+                        Util::send(&sender, Action::RedrawArticle);
+                    } else {
+                        Util::send(
+                            &sender,
+                            Action::ErrorSimpleMessage("Failed to set setting 'monospace font'.".to_owned()),
+                        );
+                    }
+                }))
+                .to_glib() as usize,
+        );
+
+        self.use_system_monospace_font_switch_signal.write().replace(
+            self.use_system_monospace_font_switch
+                .connect_state_set(clone!(
+                    @weak self.monospace_font_button as monospace_font_button,
+                    @weak self.monospace_font_row as monospace_font_row,
+                    @weak self.typography_preview as typography_preview,
+                    @weak self.settings as settings,
+                    @strong sender => @default-panic, move |_switch, is_set|
+                {
+                    let font = if is_set {
+                        None
+                    } else if let Some(font_name) = monospace_font_button.get_font() {
+                        Some(font_name.to_string())
+                    } else {
+                        None
+                    };
+                    monospace_font_button.set_sensitive(!is_set);
+                    monospace_font_row.set_sensitive(!is_set);
+                    if settings.write().set_article_view_monospace_font(font).is_ok() {
+                        Self::update_typography_preview(&typography_preview, &settings);
+                        Util::send(&sender, Action::RedrawArticle);
+                    } else {
+                        Util::send(
+                            &sender,
+                            Action::ErrorSimpleMessage("Failed to set setting 'use system monospace font'.".to_owned()),
+                        );
+                    }
+                    Inhibit(false)
+                }))
+                .to_glib() as usize,
+        );
+
+        self.custom_css_signal.write().replace(
+            self.custom_css_view
+                .connect_focus_out_event(clone!(
+                    @weak self.settings as settings,
+                    @strong sender => @default-panic, move |view, _event|
+                {
+                    Self::save_custom_css(view, &settings, &sender);
+                    Inhibit(false)
+                }))
+                .to_glib() as usize,
+        );
+    }
+
+    fn save_custom_css(view: &TextView, settings: &Arc<RwLock<Settings>>, sender: &Sender<Action>) {
+        let buffer = match view.get_buffer() {
+            Some(buffer) => buffer,
+            None => return,
+        };
+        let (start, end) = buffer.get_bounds();
+        let css = buffer
+            .get_text(&start, &end, false)
+            .map(|css| css.to_string())
+            .filter(|css| !css.trim().is_empty());
+        if settings.read().get_article_view_custom_css() == css {
+            return;
+        }
+
+        if settings.write().set_article_view_custom_css(css).is_ok() {
+            Util::send(sender, Action::RedrawArticle);
+        } else {
+            Util::send(
+                sender,
+                Action::ErrorSimpleMessage("Failed to set setting 'custom css'.".to_owned()),
+            );
+        }
+    }
+
+    fn update_typography_preview(view: &WebView, settings: &Arc<RwLock<Settings>>) {
+        let mut article = ThemeChooser::demo_article();
+        article.title = Some(i18n("Preview"));
+        let html =
+            ArticleView::build_article_static("theme_preview", &article, "Feed Name", settings, None, None, false);
+        view.load_html(&html, None);
+    }
+
+    fn quiet_hours_entry_changed(
+        entry: &Entry,
+        settings: &Arc<RwLock<Settings>>,
+        sender: &Sender<Action>,
+        start: bool,
+    ) {
+        let minutes = match QuietHours::parse_time(&entry.get_text()) {
+            Some(minutes) => minutes,
+            None => {
+                entry.get_style_context().add_class("error");
+                return;
+            }
+        };
+        entry.get_style_context().remove_class("error");
+
+        let mut quiet_hours = settings.read().get_quiet_hours();
+        if start {
+            quiet_hours.start = minutes;
+        } else {
+            quiet_hours.end = minutes;
+        }
+        if settings.write().set_quiet_hours(quiet_hours).is_err() {
+            Util::send(
+                sender,
+                Action::ErrorSimpleMessage("Failed to set setting 'quiet hours'.".to_owned()),
+            );
+        }
     }
 
     fn setup_keybindings_section(&self, sender: &Sender<Action>) {
@@ -608,6 +1224,11 @@ impl SettingsDialog {
             self.settings.read().get_keybind_sidebar_set_read(),
             sender,
         );
+        self.setup_keybinding_row(
+            "sidebar_set_older_read",
+            self.settings.read().get_keybind_sidebar_set_older_read(),
+            sender,
+        );
 
         self.setup_keybinding_row("shortcuts", self.settings.read().get_keybind_shortcut(), sender);
         self.setup_keybinding_row("refresh", self.settings.read().get_keybind_refresh(), sender);
@@ -617,6 +1238,8 @@ impl SettingsDialog {
         self.setup_keybinding_row("all_articles", self.settings.read().get_keybind_all_articles(), sender);
         self.setup_keybinding_row("only_unread", self.settings.read().get_keybind_only_unread(), sender);
         self.setup_keybinding_row("only_starred", self.settings.read().get_keybind_only_starred(), sender);
+        self.setup_keybinding_row("undo", self.settings.read().get_keybind_undo(), sender);
+        self.setup_keybinding_row("redo", self.settings.read().get_keybind_redo(), sender);
 
         self.setup_keybinding_row("scroll_up", self.settings.read().get_keybind_article_view_up(), sender);
         self.setup_keybinding_row(
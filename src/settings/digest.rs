@@ -0,0 +1,36 @@
+use crate::content_page::HeaderSelection;
+use crate::sidebar::models::SidebarSelection;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DigestFormat {
+    Epub,
+    Html,
+}
+
+impl DigestFormat {
+    pub fn to_id(self) -> &'static str {
+        match self {
+            DigestFormat::Epub => "epub",
+            DigestFormat::Html => "html",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "epub" => Some(DigestFormat::Epub),
+            "html" => Some(DigestFormat::Html),
+            _ => None,
+        }
+    }
+}
+
+// stored in the settings if the export is repeated after every sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestSchedule {
+    pub sidebar_selection: SidebarSelection,
+    pub header_selection: HeaderSelection,
+    pub format: DigestFormat,
+    pub directory: PathBuf,
+}
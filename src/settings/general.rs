@@ -1,24 +1,59 @@
+use super::sync_schedule::SyncSchedule;
+use chrono::{Local, NaiveTime, Timelike};
 use serde::{Deserialize, Serialize};
 use std::default::Default;
 use std::fmt;
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(from = "StoredSyncInterval")]
 pub enum SyncInterval {
     Never,
     QuaterHour,
     HalfHour,
     Hour,
     TwoHour,
+    Custom(u32),
+}
+
+// the settings file may contain a custom interval of 0 minutes, which is read as `Never`
+#[derive(Deserialize)]
+enum StoredSyncInterval {
+    Never,
+    QuaterHour,
+    HalfHour,
+    Hour,
+    TwoHour,
+    Custom(u32),
+}
+
+impl From<StoredSyncInterval> for SyncInterval {
+    fn from(stored: StoredSyncInterval) -> Self {
+        match stored {
+            StoredSyncInterval::Never => SyncInterval::Never,
+            StoredSyncInterval::QuaterHour => SyncInterval::QuaterHour,
+            StoredSyncInterval::HalfHour => SyncInterval::HalfHour,
+            StoredSyncInterval::Hour => SyncInterval::Hour,
+            StoredSyncInterval::TwoHour => SyncInterval::TwoHour,
+            StoredSyncInterval::Custom(minutes) => SyncInterval::from_minutes(minutes),
+        }
+    }
 }
 
 impl fmt::Display for SyncInterval {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let text = match self {
-            SyncInterval::Never => "Never",
-            SyncInterval::QuaterHour => "15 Minutes",
-            SyncInterval::HalfHour => "30 Minutes",
-            SyncInterval::Hour => "1 Hour",
-            SyncInterval::TwoHour => "2 Hours",
+            SyncInterval::Never | SyncInterval::Custom(0) => "Never".to_owned(),
+            SyncInterval::QuaterHour => "15 Minutes".to_owned(),
+            SyncInterval::HalfHour => "30 Minutes".to_owned(),
+            SyncInterval::Hour => "1 Hour".to_owned(),
+            SyncInterval::TwoHour => "2 Hours".to_owned(),
+            SyncInterval::Custom(minutes) => match (minutes / 60, minutes % 60) {
+                (0, 1) => "1 Minute".to_owned(),
+                (0, minutes) => format!("{} Minutes", minutes),
+                (1, 0) => "1 Hour".to_owned(),
+                (hours, 0) => format!("{} Hours", hours),
+                (hours, minutes) => format!("{}h {}min", hours, minutes),
+            },
         };
 
         write!(f, "{}", text)
@@ -33,6 +68,19 @@ impl SyncInterval {
             SyncInterval::HalfHour => Some(30),
             SyncInterval::Hour => Some(60),
             SyncInterval::TwoHour => Some(120),
+            SyncInterval::Custom(0) => None,
+            SyncInterval::Custom(minutes) => Some(*minutes),
+        }
+    }
+
+    pub fn from_minutes(minutes: u32) -> Self {
+        match minutes {
+            0 => SyncInterval::Never,
+            15 => SyncInterval::QuaterHour,
+            30 => SyncInterval::HalfHour,
+            60 => SyncInterval::Hour,
+            120 => SyncInterval::TwoHour,
+            minutes => SyncInterval::Custom(minutes),
         }
     }
 
@@ -41,11 +89,62 @@ impl SyncInterval {
     }
 }
 
+// `start` and `end` are minutes since midnight, the window may wrap around midnight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub enabled: bool,
+    pub start: u32,
+    pub end: u32,
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        QuietHours {
+            enabled: false,
+            start: 22 * 60,
+            end: 7 * 60,
+        }
+    }
+}
+
+impl QuietHours {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let minutes = time.hour() * 60 + time.minute();
+        if self.start <= self.end {
+            minutes >= self.start && minutes < self.end
+        } else {
+            minutes >= self.start || minutes < self.end
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.contains(Local::now().time())
+    }
+
+    pub fn format_time(minutes: u32) -> String {
+        format!("{:02}:{:02}", minutes / 60, minutes % 60)
+    }
+
+    pub fn parse_time(text: &str) -> Option<u32> {
+        NaiveTime::parse_from_str(text.trim(), "%H:%M")
+            .ok()
+            .map(|time| time.hour() * 60 + time.minute())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GeneralSettings {
     pub keep_running_in_background: bool,
     pub sync_every: SyncInterval,
     pub prefer_dark_theme: bool,
+    #[serde(default)]
+    pub sync_schedule: SyncSchedule,
+    #[serde(default)]
+    pub quiet_hours: QuietHours,
 }
 
 impl Default for GeneralSettings {
@@ -54,6 +153,64 @@ impl Default for GeneralSettings {
             keep_running_in_background: false,
             sync_every: SyncInterval::QuaterHour,
             prefer_dark_theme: false,
+            sync_schedule: SyncSchedule::default(),
+            quiet_hours: QuietHours::default(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{QuietHours, SyncInterval};
+    use chrono::NaiveTime;
+
+    #[test]
+    fn custom_interval() {
+        assert_eq!(SyncInterval::from_minutes(30), SyncInterval::HalfHour);
+        assert_eq!(SyncInterval::from_minutes(45), SyncInterval::Custom(45));
+        assert_eq!(SyncInterval::Custom(45).to_seconds(), Some(45 * 60));
+        assert_eq!(SyncInterval::Custom(0).to_minutes(), None);
+        assert_eq!(SyncInterval::Custom(90).to_string(), "1h 30min");
+        assert_eq!(SyncInterval::Custom(0).to_string(), "Never");
+    }
+
+    #[test]
+    fn zero_custom_interval_is_never() {
+        let interval: SyncInterval = serde_json::from_str(r#"{"Custom":0}"#).unwrap();
+        assert_eq!(interval, SyncInterval::Never);
+        let interval: SyncInterval = serde_json::from_str(r#"{"Custom":45}"#).unwrap();
+        assert_eq!(interval, SyncInterval::Custom(45));
+    }
+
+    #[test]
+    fn quiet_hours_wrap_midnight() {
+        let quiet_hours = QuietHours {
+            enabled: true,
+            start: QuietHours::parse_time("22:30").unwrap(),
+            end: QuietHours::parse_time("06:00").unwrap(),
+        };
+        assert!(quiet_hours.contains(NaiveTime::from_hms(23, 0, 0)));
+        assert!(quiet_hours.contains(NaiveTime::from_hms(2, 0, 0)));
+        assert!(!quiet_hours.contains(NaiveTime::from_hms(6, 0, 0)));
+        assert!(!quiet_hours.contains(NaiveTime::from_hms(12, 0, 0)));
+
+        let disabled = QuietHours {
+            enabled: false,
+            ..quiet_hours
+        };
+        assert!(!disabled.contains(NaiveTime::from_hms(23, 0, 0)));
+    }
+
+    #[test]
+    fn quiet_hours_same_day() {
+        let quiet_hours = QuietHours {
+            enabled: true,
+            start: 9 * 60,
+            end: 17 * 60,
+        };
+        assert!(quiet_hours.contains(NaiveTime::from_hms(9, 0, 0)));
+        assert!(!quiet_hours.contains(NaiveTime::from_hms(17, 0, 0)));
+        assert_eq!(QuietHours::format_time(quiet_hours.start), "09:00");
+        assert_eq!(QuietHours::parse_time("25:00"), None);
+    }
+}
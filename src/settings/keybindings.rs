@@ -211,6 +211,7 @@ impl Keybindings {
             "previous_item" => settings.write().set_keybind_feed_list_prev(keybinding),
             "toggle_category_expanded" => settings.write().set_keybind_feed_list_toggle_expanded(keybinding),
             "sidebar_set_read" => settings.write().set_keybind_sidebar_set_read(keybinding),
+            "sidebar_set_older_read" => settings.write().set_keybind_sidebar_set_older_read(keybinding),
             "shortcuts" => settings.write().set_keybind_shortcut(keybinding),
             "refresh" => settings.write().set_keybind_refresh(keybinding),
             "search" => settings.write().set_keybind_search(keybinding),
@@ -218,6 +219,8 @@ impl Keybindings {
             "all_articles" => settings.write().set_keybind_all_articles(keybinding),
             "only_unread" => settings.write().set_keybind_only_unread(keybinding),
             "only_starred" => settings.write().set_keybind_only_starred(keybinding),
+            "undo" => settings.write().set_keybind_undo(keybinding),
+            "redo" => settings.write().set_keybind_redo(keybinding),
             "scroll_up" => settings.write().set_keybind_article_view_up(keybinding),
             "scroll_down" => settings.write().set_keybind_article_view_down(keybinding),
             "scrap_content" => settings.write().set_keybind_article_view_scrap(keybinding),
@@ -239,6 +242,7 @@ impl Keybindings {
             "previous_item" => Ok(settings.read().get_keybind_feed_list_prev()),
             "toggle_category_expanded" => Ok(settings.read().get_keybind_feed_list_toggle_expanded()),
             "sidebar_set_read" => Ok(settings.read().get_keybind_sidebar_set_read()),
+            "sidebar_set_older_read" => Ok(settings.read().get_keybind_sidebar_set_older_read()),
             "shortcuts" => Ok(settings.read().get_keybind_shortcut()),
             "refresh" => Ok(settings.read().get_keybind_refresh()),
             "search" => Ok(settings.read().get_keybind_search()),
@@ -246,6 +250,8 @@ impl Keybindings {
             "all_articles" => Ok(settings.read().get_keybind_all_articles()),
             "only_unread" => Ok(settings.read().get_keybind_only_unread()),
             "only_starred" => Ok(settings.read().get_keybind_only_starred()),
+            "undo" => Ok(settings.read().get_keybind_undo()),
+            "redo" => Ok(settings.read().get_keybind_redo()),
             "scroll_up" => Ok(settings.read().get_keybind_article_view_up()),
             "scroll_down" => Ok(settings.read().get_keybind_article_view_down()),
             "scrap_content" => Ok(settings.read().get_keybind_article_view_scrap()),
@@ -284,6 +290,12 @@ pub struct KeybindingsGeneral {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub only_starred: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub undo: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub redo: Option<String>,
 }
 
 impl KeybindingsGeneral {
@@ -296,6 +308,8 @@ impl KeybindingsGeneral {
             all_articles: Some("<ctl>1".to_owned()),
             only_unread: Some("<ctl>2".to_owned()),
             only_starred: Some("<ctl>3".to_owned()),
+            undo: Some("<ctl>Z".to_owned()),
+            redo: Some("<Shift><ctl>Z".to_owned()),
         }
     }
 }
@@ -380,6 +394,9 @@ pub struct KeybindingsFeedList {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub read: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub read_older: Option<String>,
 }
 
 impl KeybindingsFeedList {
@@ -389,6 +406,7 @@ impl KeybindingsFeedList {
             prev: Some("<ctl>K".to_owned()),
             toggle_expanded: Some("C".to_owned()),
             read: Some("<Shift>A".to_owned()),
+            read_older: Some("<Shift><ctl>A".to_owned()),
         }
     }
 }
@@ -415,6 +433,8 @@ impl NewsFlashShortcutWindow {
         ui_xml = Self::setup_shortcut(&ui_xml, "$ALLARTICLES", settings.get_keybind_all_articles());
         ui_xml = Self::setup_shortcut(&ui_xml, "$ONLYUNREAD", settings.get_keybind_only_unread());
         ui_xml = Self::setup_shortcut(&ui_xml, "$ONLYSTARRED", settings.get_keybind_only_starred());
+        ui_xml = Self::setup_shortcut(&ui_xml, "$UNDO", settings.get_keybind_undo());
+        ui_xml = Self::setup_shortcut(&ui_xml, "$REDO", settings.get_keybind_redo());
         ui_xml = Self::setup_shortcut(&ui_xml, "$NEXTART", settings.get_keybind_article_list_next());
         ui_xml = Self::setup_shortcut(&ui_xml, "$PREVART", settings.get_keybind_article_list_prev());
         ui_xml = Self::setup_shortcut(&ui_xml, "$TOGGLEREAD", settings.get_keybind_article_list_read());
@@ -431,6 +451,7 @@ impl NewsFlashShortcutWindow {
             settings.get_keybind_feed_list_toggle_expanded(),
         );
         ui_xml = Self::setup_shortcut(&ui_xml, "$ITEMREAD", settings.get_keybind_sidebar_set_read());
+        ui_xml = Self::setup_shortcut(&ui_xml, "$ITEMOLDERREAD", settings.get_keybind_sidebar_set_older_read());
 
         let builder = BuilderHelper::new_from_xml(&ui_xml);
         let widget = builder.get::<ShortcutsWindow>("shortcuts-window");
@@ -2,23 +2,32 @@ mod advanced;
 mod article_list;
 mod article_view;
 mod dialog;
+mod digest;
 mod error;
 mod general;
 mod keybinding_editor;
 mod keybindings;
+mod offline;
+mod sync_schedule;
 mod theme_chooser;
 
 pub use self::advanced::{AdvancedSettings, ProxyModel, ProxyProtocoll};
+pub use self::digest::{DigestFormat, DigestSchedule};
 use self::error::{SettingsError, SettingsErrorKind};
-use self::general::SyncInterval;
+pub use self::general::{QuietHours, SyncInterval};
+pub use self::offline::{OfflineArticles, OfflineSettings};
+pub use self::sync_schedule::{FeedSyncInterval, SyncSchedule};
 use crate::article_view::ArticleTheme;
+use crate::filter_rules::FilterRule;
+use crate::smart_folders::SmartFolder;
 use article_list::ArticleListSettings;
 use article_view::ArticleViewSettings;
+pub use article_view::{FeedViewSetting, FeedViewSettings};
 pub use dialog::SettingsDialog;
 use failure::ResultExt;
 use general::GeneralSettings;
 pub use keybindings::{Keybindings, NewsFlashShortcutWindow};
-use news_flash::models::ArticleOrder;
+use news_flash::models::{ArticleOrder, CategoryID, FeedID};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -32,6 +41,16 @@ pub struct Settings {
     article_list: ArticleListSettings,
     article_view: ArticleViewSettings,
     keybindings: Keybindings,
+    #[serde(default)]
+    filter_rules: Vec<FilterRule>,
+    #[serde(default)]
+    smart_folders: Vec<SmartFolder>,
+    #[serde(default)]
+    podcasts_folder_added: bool,
+    #[serde(default)]
+    offline: OfflineSettings,
+    #[serde(default)]
+    digest: Option<DigestSchedule>,
     #[serde(skip_serializing)]
     #[serde(skip_deserializing)]
     path: PathBuf,
@@ -44,6 +63,11 @@ impl Settings {
             let data = fs::read_to_string(&path).context(SettingsErrorKind::ReadFromDisk)?;
             let mut settings: Self = serde_json::from_str(&data).context(SettingsErrorKind::InvalidJsonContent)?;
             settings.path = path;
+            if !settings.podcasts_folder_added {
+                settings.smart_folders.push(SmartFolder::podcasts());
+                settings.podcasts_folder_added = true;
+                settings.write()?;
+            }
             return Ok(settings);
         }
 
@@ -55,6 +79,11 @@ impl Settings {
             article_list: ArticleListSettings::default(),
             article_view: ArticleViewSettings::default(),
             keybindings: Keybindings::default(),
+            filter_rules: Vec::new(),
+            smart_folders: vec![SmartFolder::podcasts()],
+            podcasts_folder_added: true,
+            offline: OfflineSettings::default(),
+            digest: None,
             path,
         };
         settings.write().context(SettingsErrorKind::WriteToDisk)?;
@@ -87,6 +116,46 @@ impl Settings {
         Ok(())
     }
 
+    pub fn get_quiet_hours(&self) -> QuietHours {
+        self.general.quiet_hours.clone()
+    }
+
+    pub fn set_quiet_hours(&mut self, quiet_hours: QuietHours) -> Result<(), SettingsError> {
+        self.general.quiet_hours = quiet_hours;
+        self.write()?;
+        Ok(())
+    }
+
+    pub fn get_sync_schedule(&self) -> SyncSchedule {
+        self.general.sync_schedule.clone()
+    }
+
+    pub fn set_sync_adaptive(&mut self, adaptive: bool) -> Result<(), SettingsError> {
+        self.general.sync_schedule.adaptive = adaptive;
+        self.write()?;
+        Ok(())
+    }
+
+    pub fn set_feed_sync_interval(
+        &mut self,
+        feed_id: &FeedID,
+        interval: Option<FeedSyncInterval>,
+    ) -> Result<(), SettingsError> {
+        self.general.sync_schedule.set_feed(feed_id, interval);
+        self.write()?;
+        Ok(())
+    }
+
+    pub fn set_category_sync_interval(
+        &mut self,
+        category_id: &CategoryID,
+        interval: Option<FeedSyncInterval>,
+    ) -> Result<(), SettingsError> {
+        self.general.sync_schedule.set_category(category_id, interval);
+        self.write()?;
+        Ok(())
+    }
+
     pub fn get_prefer_dark_theme(&self) -> bool {
         self.general.prefer_dark_theme
     }
@@ -107,6 +176,26 @@ impl Settings {
         Ok(())
     }
 
+    pub fn get_article_list_mark_read_on_scroll(&self) -> bool {
+        self.article_list.mark_read_on_scroll
+    }
+
+    pub fn set_article_list_mark_read_on_scroll(&mut self, mark_read: bool) -> Result<(), SettingsError> {
+        self.article_list.mark_read_on_scroll = mark_read;
+        self.write()?;
+        Ok(())
+    }
+
+    pub fn get_mark_older_than_days(&self) -> u32 {
+        self.article_list.mark_older_than_days
+    }
+
+    pub fn set_mark_older_than_days(&mut self, days: u32) -> Result<(), SettingsError> {
+        self.article_list.mark_older_than_days = days;
+        self.write()?;
+        Ok(())
+    }
+
     pub fn get_article_view_theme(&self) -> ArticleTheme {
         self.article_view.theme.clone()
     }
@@ -137,6 +226,135 @@ impl Settings {
         Ok(())
     }
 
+    pub fn get_article_view_monospace_font(&self) -> Option<String> {
+        self.article_view.monospace_font.clone()
+    }
+
+    pub fn set_article_view_monospace_font(&mut self, font: Option<String>) -> Result<(), SettingsError> {
+        self.article_view.monospace_font = font;
+        self.write()?;
+        Ok(())
+    }
+
+    pub fn get_article_view_line_width(&self) -> u32 {
+        self.article_view.line_width
+    }
+
+    pub fn set_article_view_line_width(&mut self, width: u32) -> Result<(), SettingsError> {
+        self.article_view.line_width = width;
+        self.write()?;
+        Ok(())
+    }
+
+    pub fn get_article_view_line_height(&self) -> f64 {
+        self.article_view.line_height
+    }
+
+    pub fn set_article_view_line_height(&mut self, height: f64) -> Result<(), SettingsError> {
+        self.article_view.line_height = height;
+        self.write()?;
+        Ok(())
+    }
+
+    pub fn get_article_view_paragraph_spacing(&self) -> f64 {
+        self.article_view.paragraph_spacing
+    }
+
+    pub fn set_article_view_paragraph_spacing(&mut self, spacing: f64) -> Result<(), SettingsError> {
+        self.article_view.paragraph_spacing = spacing;
+        self.write()?;
+        Ok(())
+    }
+
+    pub fn get_article_view_justify(&self) -> bool {
+        self.article_view.justify
+    }
+
+    pub fn set_article_view_justify(&mut self, justify: bool) -> Result<(), SettingsError> {
+        self.article_view.justify = justify;
+        self.write()?;
+        Ok(())
+    }
+
+    pub fn get_article_view_custom_css(&self) -> Option<String> {
+        self.article_view.custom_css.clone()
+    }
+
+    pub fn set_article_view_custom_css(&mut self, css: Option<String>) -> Result<(), SettingsError> {
+        self.article_view.custom_css = css;
+        self.write()?;
+        Ok(())
+    }
+
+    pub fn get_feed_view_settings(&self, feed_id: &FeedID) -> FeedViewSettings {
+        self.article_view
+            .feeds
+            .get(&feed_id.to_string())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn get_scrape_on_sync_feeds(&self) -> Vec<FeedID> {
+        self.article_view
+            .feeds
+            .iter()
+            .filter(|(_id, feed_settings)| feed_settings.scrape_on_sync)
+            .map(|(id, _feed_settings)| FeedID::new(id))
+            .collect()
+    }
+
+    pub fn set_feed_view_settings(
+        &mut self,
+        feed_id: &FeedID,
+        feed_settings: FeedViewSettings,
+    ) -> Result<(), SettingsError> {
+        if feed_settings == FeedViewSettings::default() {
+            self.article_view.feeds.remove(&feed_id.to_string());
+        } else {
+            self.article_view.feeds.insert(feed_id.to_string(), feed_settings);
+        }
+        self.write()?;
+        Ok(())
+    }
+
+    pub fn get_offline_settings(&self) -> OfflineSettings {
+        self.offline.clone()
+    }
+
+    pub fn set_offline_prepare_after_sync(&mut self, prepare: bool) -> Result<(), SettingsError> {
+        self.offline.prepare_after_sync = prepare;
+        self.write()?;
+        Ok(())
+    }
+
+    pub fn set_offline_articles(&mut self, articles: OfflineArticles) -> Result<(), SettingsError> {
+        self.offline.articles = articles;
+        self.write()?;
+        Ok(())
+    }
+
+    pub fn set_offline_scrape_content(&mut self, scrape: bool) -> Result<(), SettingsError> {
+        self.offline.scrape_content = scrape;
+        self.write()?;
+        Ok(())
+    }
+
+    pub fn set_offline_budget(&mut self, megabytes: u32) -> Result<(), SettingsError> {
+        self.offline.budget = megabytes;
+        self.write()?;
+        Ok(())
+    }
+
+    pub fn get_digest_schedule(&self) -> Option<DigestSchedule> {
+        self.digest.clone()
+    }
+
+    pub fn set_digest_schedule(&mut self, schedule: Option<DigestSchedule>) -> Result<(), SettingsError> {
+        self.digest = schedule;
+        self.write()?;
+        Ok(())
+    }
+
     pub fn get_keybind_shortcut(&self) -> Option<String> {
         self.keybindings.general.shortcut.clone()
     }
@@ -207,6 +425,26 @@ impl Settings {
         Ok(())
     }
 
+    pub fn get_keybind_undo(&self) -> Option<String> {
+        self.keybindings.general.undo.clone()
+    }
+
+    pub fn set_keybind_undo(&mut self, key: Option<String>) -> Result<(), SettingsError> {
+        self.keybindings.general.undo = key;
+        self.write()?;
+        Ok(())
+    }
+
+    pub fn get_keybind_redo(&self) -> Option<String> {
+        self.keybindings.general.redo.clone()
+    }
+
+    pub fn set_keybind_redo(&mut self, key: Option<String>) -> Result<(), SettingsError> {
+        self.keybindings.general.redo = key;
+        self.write()?;
+        Ok(())
+    }
+
     pub fn get_keybind_article_view_up(&self) -> Option<String> {
         self.keybindings.article_view.scroll_up.clone()
     }
@@ -327,6 +565,16 @@ impl Settings {
         Ok(())
     }
 
+    pub fn get_keybind_sidebar_set_older_read(&self) -> Option<String> {
+        self.keybindings.feed_list.read_older.clone()
+    }
+
+    pub fn set_keybind_sidebar_set_older_read(&mut self, key: Option<String>) -> Result<(), SettingsError> {
+        self.keybindings.feed_list.read_older = key;
+        self.write()?;
+        Ok(())
+    }
+
     pub fn get_accept_invalid_certs(&self) -> bool {
         self.advanced.accept_invalid_certs
     }
@@ -347,6 +595,39 @@ impl Settings {
         Ok(())
     }
 
+    pub fn get_filter_rules(&self) -> Vec<FilterRule> {
+        self.filter_rules.clone()
+    }
+
+    pub fn set_filter_rules(&mut self, rules: Vec<FilterRule>) -> Result<(), SettingsError> {
+        self.filter_rules = rules;
+        self.write()?;
+        Ok(())
+    }
+
+    pub fn get_smart_folders(&self) -> Vec<SmartFolder> {
+        self.smart_folders.clone()
+    }
+
+    pub fn get_smart_folder(&self, id: &str) -> Option<SmartFolder> {
+        self.smart_folders.iter().find(|folder| folder.id == id).cloned()
+    }
+
+    pub fn save_smart_folder(&mut self, folder: SmartFolder) -> Result<(), SettingsError> {
+        match self.smart_folders.iter_mut().find(|f| f.id == folder.id) {
+            Some(existing) => *existing = folder,
+            None => self.smart_folders.push(folder),
+        }
+        self.write()?;
+        Ok(())
+    }
+
+    pub fn delete_smart_folder(&mut self, id: &str) -> Result<(), SettingsError> {
+        self.smart_folders.retain(|folder| folder.id != id);
+        self.write()?;
+        Ok(())
+    }
+
     pub fn get_proxy(&self) -> Vec<ProxyModel> {
         self.advanced.proxy.clone()
     }
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use std::default::Default;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OfflineArticles {
+    Unread,
+    Starred,
+    UnreadAndStarred,
+}
+
+impl OfflineArticles {
+    pub fn to_id(self) -> &'static str {
+        match self {
+            OfflineArticles::Unread => "unread",
+            OfflineArticles::Starred => "starred",
+            OfflineArticles::UnreadAndStarred => "unread_starred",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "unread" => Some(OfflineArticles::Unread),
+            "starred" => Some(OfflineArticles::Starred),
+            "unread_starred" => Some(OfflineArticles::UnreadAndStarred),
+            _ => None,
+        }
+    }
+}
+
+impl Default for OfflineArticles {
+    fn default() -> Self {
+        OfflineArticles::Unread
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfflineSettings {
+    #[serde(default)]
+    pub prepare_after_sync: bool,
+    #[serde(default)]
+    pub articles: OfflineArticles,
+    #[serde(default)]
+    pub scrape_content: bool,
+    // maximum size of the offline cache in MB
+    #[serde(default = "default_budget")]
+    pub budget: u32,
+}
+
+fn default_budget() -> u32 {
+    500
+}
+
+impl Default for OfflineSettings {
+    fn default() -> Self {
+        OfflineSettings {
+            prepare_after_sync: false,
+            articles: OfflineArticles::default(),
+            scrape_content: false,
+            budget: default_budget(),
+        }
+    }
+}
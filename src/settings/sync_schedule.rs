@@ -0,0 +1,242 @@
+use super::general::SyncInterval;
+use chrono::NaiveDateTime;
+use log::warn;
+use news_flash::models::{ArticleFilter, ArticleOrder, CategoryID, FeedID, FeedMapping};
+use news_flash::NewsFlash;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+const ADAPTIVE_MIN_MINUTES: u32 = 15;
+const ADAPTIVE_MAX_MINUTES: u32 = 24 * 60;
+const ADAPTIVE_SAMPLE_SIZE: i64 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum FeedSyncInterval {
+    Adaptive,
+    Every(SyncInterval),
+}
+
+impl fmt::Display for FeedSyncInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeedSyncInterval::Adaptive => write!(f, "Adaptive"),
+            FeedSyncInterval::Every(interval) => write!(f, "{}", interval),
+        }
+    }
+}
+
+impl FeedSyncInterval {
+    pub fn to_action_target(self) -> String {
+        match self {
+            FeedSyncInterval::Adaptive => "adaptive".to_owned(),
+            FeedSyncInterval::Every(interval) => match interval.to_minutes() {
+                Some(minutes) => minutes.to_string(),
+                None => "never".to_owned(),
+            },
+        }
+    }
+
+    pub fn from_action_target(target: &str) -> Option<Self> {
+        match target {
+            "adaptive" => Some(FeedSyncInterval::Adaptive),
+            "never" => Some(FeedSyncInterval::Every(SyncInterval::Never)),
+            minutes => minutes
+                .parse::<u32>()
+                .ok()
+                .map(|minutes| FeedSyncInterval::Every(SyncInterval::from_minutes(minutes))),
+        }
+    }
+
+    pub fn presets() -> Vec<Self> {
+        vec![
+            FeedSyncInterval::Adaptive,
+            FeedSyncInterval::Every(SyncInterval::QuaterHour),
+            FeedSyncInterval::Every(SyncInterval::HalfHour),
+            FeedSyncInterval::Every(SyncInterval::Hour),
+            FeedSyncInterval::Every(SyncInterval::TwoHour),
+            FeedSyncInterval::Every(SyncInterval::Never),
+        ]
+    }
+}
+
+// Refresh intervals that override the global `SyncInterval` for single feeds or whole categories.
+// The backends can only sync the complete account, so these only decide how soon the next sync is due:
+// the feed with the shortest interval wins. A feed with a longer interval is still refreshed with every sync.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SyncSchedule {
+    #[serde(default)]
+    pub adaptive: bool,
+    #[serde(default)]
+    pub feeds: HashMap<String, FeedSyncInterval>,
+    #[serde(default)]
+    pub categories: HashMap<String, FeedSyncInterval>,
+}
+
+impl SyncSchedule {
+    pub fn get_feed(&self, feed_id: &FeedID) -> Option<FeedSyncInterval> {
+        self.feeds.get(&feed_id.to_string()).copied()
+    }
+
+    pub fn set_feed(&mut self, feed_id: &FeedID, interval: Option<FeedSyncInterval>) {
+        match interval {
+            Some(interval) => self.feeds.insert(feed_id.to_string(), interval),
+            None => self.feeds.remove(&feed_id.to_string()),
+        };
+    }
+
+    pub fn get_category(&self, category_id: &CategoryID) -> Option<FeedSyncInterval> {
+        self.categories.get(&category_id.to_string()).copied()
+    }
+
+    pub fn set_category(&mut self, category_id: &CategoryID, interval: Option<FeedSyncInterval>) {
+        match interval {
+            Some(interval) => self.categories.insert(category_id.to_string(), interval),
+            None => self.categories.remove(&category_id.to_string()),
+        };
+    }
+
+    pub fn is_trivial(&self) -> bool {
+        !self.adaptive && self.feeds.is_empty() && self.categories.is_empty()
+    }
+
+    // feed override > category override > adaptive default > global
+    pub fn effective(&self, feed_id: &FeedID, mappings: &[FeedMapping], global: SyncInterval) -> FeedSyncInterval {
+        if let Some(interval) = self.get_feed(feed_id) {
+            return interval;
+        }
+
+        let category_interval = mappings
+            .iter()
+            .filter(|m| &m.feed_id == feed_id)
+            .filter_map(|m| self.get_category(&m.category_id))
+            .min_by_key(|interval| Self::sort_key(*interval));
+        if let Some(interval) = category_interval {
+            return interval;
+        }
+
+        if self.adaptive {
+            FeedSyncInterval::Adaptive
+        } else {
+            FeedSyncInterval::Every(global)
+        }
+    }
+
+    // The backends only support syncing the whole account, so the next sync is due as soon as
+    // the feed with the shortest interval wants it. Adaptive feeds can shorten that, not lengthen it.
+    pub fn next_sync_minutes(&self, news_flash: &NewsFlash, global_interval: SyncInterval) -> Option<u32> {
+        let (feeds, mappings) = match news_flash.get_feeds() {
+            Ok(result) => result,
+            Err(_) => {
+                warn!("Failed to load feeds to calculate sync schedule");
+                return global_interval.to_minutes();
+            }
+        };
+
+        feeds
+            .iter()
+            .filter_map(|feed| match self.effective(&feed.feed_id, &mappings, global_interval) {
+                FeedSyncInterval::Every(interval) => interval.to_minutes(),
+                FeedSyncInterval::Adaptive => {
+                    let dates: Vec<NaiveDateTime> = news_flash
+                        .get_articles(ArticleFilter {
+                            limit: Some(ADAPTIVE_SAMPLE_SIZE),
+                            offset: None,
+                            order: Some(ArticleOrder::NewestFirst),
+                            unread: None,
+                            marked: None,
+                            feed: Some(feed.feed_id.clone()),
+                            feed_blacklist: None,
+                            category: None,
+                            category_blacklist: None,
+                            tag: None,
+                            ids: None,
+                            newer_than: None,
+                            older_than: None,
+                            search_term: None,
+                        })
+                        .map(|articles| articles.iter().map(|a| a.date).collect())
+                        .unwrap_or_default();
+                    Some(Self::adaptive_minutes(&dates))
+                }
+            })
+            .min()
+    }
+
+    fn sort_key(interval: FeedSyncInterval) -> u32 {
+        match interval {
+            FeedSyncInterval::Adaptive => 0,
+            FeedSyncInterval::Every(interval) => interval.to_minutes().unwrap_or(u32::MAX),
+        }
+    }
+
+    // How soon a feed wants the next sync, estimated from the dates of its most recent articles.
+    // Busy feeds ask for a sync every 15 minutes, quiet feeds for one a day.
+    pub fn adaptive_minutes(dates: &[NaiveDateTime]) -> u32 {
+        if dates.len() < 2 {
+            return ADAPTIVE_MAX_MINUTES;
+        }
+
+        let mut dates = dates.to_vec();
+        dates.sort();
+        let span = dates[dates.len() - 1] - dates[0];
+        let average_gap = span.num_minutes() / (dates.len() as i64 - 1);
+        // poll about twice per expected new article
+        let minutes = average_gap / 2;
+
+        minutes
+            .max(ADAPTIVE_MIN_MINUTES as i64)
+            .min(ADAPTIVE_MAX_MINUTES as i64) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FeedSyncInterval, SyncSchedule};
+    use crate::settings::general::SyncInterval;
+    use chrono::{Duration, NaiveDate};
+    use news_flash::models::{CategoryID, FeedID, FeedMapping};
+
+    #[test]
+    fn adaptive_interval_is_clamped() {
+        let start = NaiveDate::from_ymd(2020, 1, 1).and_hms(0, 0, 0);
+        assert_eq!(SyncSchedule::adaptive_minutes(&[start]), 24 * 60);
+
+        let busy: Vec<_> = (0..10).map(|i| start + Duration::minutes(i * 5)).collect();
+        assert_eq!(SyncSchedule::adaptive_minutes(&busy), 15);
+
+        let hourly: Vec<_> = (0..10).map(|i| start + Duration::hours(i * 2)).collect();
+        assert_eq!(SyncSchedule::adaptive_minutes(&hourly), 60);
+
+        let quiet: Vec<_> = (0..5).map(|i| start + Duration::days(i * 7)).collect();
+        assert_eq!(SyncSchedule::adaptive_minutes(&quiet), 24 * 60);
+    }
+
+    #[test]
+    fn feed_override_wins() {
+        let feed_id = FeedID::new("feed");
+        let category_id = CategoryID::new("category");
+        let mappings = vec![FeedMapping {
+            feed_id: feed_id.clone(),
+            category_id: category_id.clone(),
+        }];
+
+        let mut schedule = SyncSchedule::default();
+        assert_eq!(
+            schedule.effective(&feed_id, &mappings, SyncInterval::Hour),
+            FeedSyncInterval::Every(SyncInterval::Hour)
+        );
+
+        schedule.set_category(&category_id, Some(FeedSyncInterval::Adaptive));
+        assert_eq!(
+            schedule.effective(&feed_id, &mappings, SyncInterval::Hour),
+            FeedSyncInterval::Adaptive
+        );
+
+        schedule.set_feed(&feed_id, Some(FeedSyncInterval::Every(SyncInterval::Never)));
+        assert_eq!(
+            schedule.effective(&feed_id, &mappings, SyncInterval::Hour),
+            FeedSyncInterval::Every(SyncInterval::Never)
+        );
+    }
+}
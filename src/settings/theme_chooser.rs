@@ -1,16 +1,21 @@
 use crate::app::Action;
-use crate::article_view::{ArticleTheme, ArticleView};
+use crate::article_view::{ArticleTheme, ArticleView, UserTheme};
 use crate::i18n::i18n;
 use crate::settings::Settings;
 use crate::util::{BuilderHelper, Util};
 use chrono::Utc;
 use glib::{clone, object::IsA, Sender};
-use gtk::{Inhibit, ListBox, ListBoxExt, ListBoxRow, ListBoxRowExt, Popover, PopoverExt, Widget, WidgetExt};
+use gtk::{
+    ContainerExt, Inhibit, ListBox, ListBoxExt, ListBoxRow, ListBoxRowExt, Orientation, Popover, PopoverExt, Separator,
+    StyleContextExt, Widget, WidgetExt,
+};
 use news_flash::models::{ArticleID, FatArticle, FeedID, Marked, Read};
 use parking_lot::RwLock;
 use std::sync::Arc;
 use webkit2gtk::{WebView, WebViewExt};
 
+const USER_THEME_PREFIX: &str = "user-theme:";
+
 pub struct ThemeChooser {
     widget: Popover,
 }
@@ -22,21 +27,7 @@ impl ThemeChooser {
         let pop = builder.get::<Popover>("popover");
         pop.set_relative_to(Some(parent));
 
-        let mut demo_article = FatArticle {
-            article_id: ArticleID::new("demo"),
-            title: None,
-            author: None,
-            feed_id: FeedID::new("demo_feed"),
-            direction: None,
-            date: Utc::now().naive_utc(),
-            marked: Marked::Unmarked,
-            unread: Read::Unread,
-            url: None,
-            summary: None,
-            html: None,
-            scraped_content: None,
-            plain_text: None,
-        };
+        let mut demo_article = Self::demo_article();
 
         Self::prepare_theme_selection(
             &builder,
@@ -80,10 +71,16 @@ impl ThemeChooser {
         );
 
         let theme_list = builder.get::<ListBox>("theme_list");
+        for user_theme in UserTheme::load_all() {
+            Self::add_user_theme(&builder, &theme_list, settings, &mut demo_article, user_theme);
+        }
+
         theme_list.connect_row_activated(
             clone!(@strong sender, @weak settings, @weak pop => @default-panic, move |_list, row| {
                 let row_name = row.get_widget_name();
-                let result = if "default" == row_name {
+                let result = if let Some(id) = row_name.strip_prefix(USER_THEME_PREFIX) {
+                    settings.write().set_article_view_theme(ArticleTheme::User(id.to_owned()))
+                } else if "default" == row_name {
                     settings.write().set_article_view_theme(ArticleTheme::Default)
                 } else if "spring" == row_name {
                     settings.write().set_article_view_theme(ArticleTheme::Spring)
@@ -114,6 +111,30 @@ impl ThemeChooser {
         self.widget.clone()
     }
 
+    pub fn demo_article() -> FatArticle {
+        let html = format!(
+            "<p>{}</p><p>{}</p><pre><code>fn main() {{\n    println!(\"Hello, world!\");\n}}</code></pre>",
+            i18n("The quick brown fox jumps over the lazy dog. Pack my box with five dozen liquor jugs. How vexingly quick daft zebras jump!"),
+            i18n("Sphinx of black quartz, judge my vow. The five boxing wizards jump quickly. Jackdaws love my big sphinx of quartz."),
+        );
+
+        FatArticle {
+            article_id: ArticleID::new("demo"),
+            title: None,
+            author: None,
+            feed_id: FeedID::new("demo_feed"),
+            direction: None,
+            date: Utc::now().naive_utc(),
+            marked: Marked::Unmarked,
+            unread: Read::Unread,
+            url: None,
+            summary: None,
+            html: Some(html),
+            scraped_content: None,
+            plain_text: None,
+        }
+    }
+
     fn prepare_theme_selection(
         builder: &BuilderHelper,
         settings: &Arc<RwLock<Settings>>,
@@ -124,6 +145,56 @@ impl ThemeChooser {
     ) {
         let view = builder.get::<WebView>(&format!("{}_view", id));
         let row = builder.get::<ListBoxRow>(&format!("{}_row", id));
+        Self::load_preview(&view, &row, settings, article, theme, name);
+    }
+
+    fn add_user_theme(
+        builder: &BuilderHelper,
+        theme_list: &ListBox,
+        settings: &Arc<RwLock<Settings>>,
+        article: &mut FatArticle,
+        user_theme: UserTheme,
+    ) {
+        let separator_row = ListBoxRow::new();
+        separator_row.set_activatable(false);
+        separator_row.set_selectable(false);
+        separator_row.get_style_context().add_class("separator-row");
+        separator_row.add(&Separator::new(Orientation::Horizontal));
+        theme_list.add(&separator_row);
+
+        // share the restricted webkit settings of the builtin previews
+        let view = match WebViewExt::get_settings(&builder.get::<WebView>("default_view")) {
+            Some(webkit_settings) => WebView::with_settings(&webkit_settings),
+            None => WebView::new(),
+        };
+        view.set_can_focus(false);
+        let row = ListBoxRow::new();
+        row.set_widget_name(&format!("{}{}", USER_THEME_PREFIX, user_theme.id));
+        row.set_size_request(-1, 80);
+        row.add(&view);
+        theme_list.add(&row);
+        row.show_all();
+        separator_row.show_all();
+
+        Self::load_preview(
+            &view,
+            &row,
+            settings,
+            article,
+            ArticleTheme::User(user_theme.id),
+            &user_theme.name,
+        );
+    }
+
+    fn load_preview(
+        view: &WebView,
+        row: &ListBoxRow,
+        settings: &Arc<RwLock<Settings>>,
+        article: &mut FatArticle,
+        theme: ArticleTheme,
+        name: &str,
+    ) {
+        let row = row.clone();
         view.connect_button_press_event(move |_view, _event| {
             row.emit_activate();
             Inhibit(true)
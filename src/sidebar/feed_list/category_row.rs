@@ -1,6 +1,8 @@
 use crate::app::Action;
 use crate::main_window_state::MainWindowState;
+use crate::settings::FeedSyncInterval;
 use crate::sidebar::feed_list::models::FeedListCategoryModel;
+use crate::sidebar::feed_list::FeedList;
 use crate::undo_bar::UndoActionModel;
 use crate::util::{BuilderHelper, GtkUtil, Util};
 use gdk::{EventMask, EventType};
@@ -9,7 +11,7 @@ use glib::{
     clone,
     object::{Cast, IsA},
     translate::ToGlib,
-    Sender,
+    Sender, VariantTy,
 };
 use gtk::{
     self, prelude::WidgetExtManual, BinExt, Box, ContainerExt, EventBox, Image, Inhibit, Label, LabelExt, ListBoxRow,
@@ -171,7 +173,7 @@ impl CategoryRow {
             support_mutation = features.contains(PluginCapabilities::MODIFY_CATEGORIES);
         }
 
-        if support_mutation && category_type != CategoryType::Generated {
+        if category_type != CategoryType::Generated {
             Some((eventbox.connect_button_press_event(clone!(
                 @strong id as category_id,
                 @strong label,
@@ -192,43 +194,71 @@ impl CategoryRow {
                     return Inhibit(false);
                 }
 
-                let rename_category_dialog_action = SimpleAction::new(&format!("rename-category-{}-dialog", category_id), None);
-                rename_category_dialog_action.connect_activate(clone!(@weak row, @strong sender, @strong category_id => @default-panic, move |_action, _parameter| {
-                    Util::send(&sender, Action::RenameCategoryDialog(category_id.clone()));
+                let model = Menu::new();
+
+                if support_mutation {
+                    let rename_category_dialog_action = SimpleAction::new(&format!("rename-category-{}-dialog", category_id), None);
+                    rename_category_dialog_action.connect_activate(clone!(@weak row, @strong sender, @strong category_id => @default-panic, move |_action, _parameter| {
+                        Util::send(&sender, Action::RenameCategoryDialog(category_id.clone()));
+                        if let Ok(main_window) = GtkUtil::get_main_window(&row) {
+                            main_window.remove_action(&format!("rename-category-{}-dialog", category_id));
+                        }
+                    }));
+
+                    let delete_category_action = SimpleAction::new(&format!("enqueue-delete-{}-category", category_id), None);
+                    delete_category_action.connect_activate(clone!(
+                        @weak row,
+                        @strong label,
+                        @strong category_id,
+                        @strong sender => @default-panic, move |_action, _parameter|
+                    {
+                        let remove_action = UndoActionModel::DeleteCategory(category_id.clone(), label.clone());
+                        Util::send(&sender, Action::UndoableAction(remove_action));
+
+                        if let Ok(main_window) = GtkUtil::get_main_window(&row) {
+                            main_window.remove_action(&format!("enqueue-delete-{}-category", category_id));
+                        }
+                    }));
+
                     if let Ok(main_window) = GtkUtil::get_main_window(&row) {
-                        main_window.remove_action(&format!("rename-category-{}-dialog", category_id));
+                        main_window.add_action(&delete_category_action);
+                        main_window.add_action(&rename_category_dialog_action);
                     }
-                }));
 
-                let delete_category_action = SimpleAction::new(&format!("enqueue-delete-{}-category", category_id), None);
-                delete_category_action.connect_activate(clone!(
+                    let rename_category_item = MenuItem::new(Some("Rename"), None);
+                    rename_category_item.set_action_and_target_value(Some(&format!("rename-category-{}-dialog", category_id)), None);
+                    model.append_item(&rename_category_item);
+
+                    let delete_category_item = MenuItem::new(Some("Delete"), None);
+                    delete_category_item.set_action_and_target_value(Some(&format!("enqueue-delete-{}-category", category_id)), None);
+                    model.append_item(&delete_category_item);
+                }
+
+                let sync_interval_action = SimpleAction::new(
+                    &format!("sync-interval-category-{}", category_id),
+                    Some(VariantTy::new("s").expect("valid type string")),
+                );
+                sync_interval_action.connect_activate(clone!(
                     @weak row,
-                    @strong label,
                     @strong category_id,
-                    @strong sender => @default-panic, move |_action, _parameter|
+                    @strong sender => @default-panic, move |_action, parameter|
                 {
-                    let remove_action = UndoActionModel::DeleteCategory(category_id.clone(), label.clone());
-                    Util::send(&sender, Action::UndoableAction(remove_action));
+                    if let Some(interval) = parameter.and_then(|p| p.get::<String>()) {
+                        let interval = FeedSyncInterval::from_action_target(&interval);
+                        Util::send(&sender, Action::SetCategorySyncInterval(category_id.clone(), interval));
+                    }
 
                     if let Ok(main_window) = GtkUtil::get_main_window(&row) {
-                        main_window.remove_action(&format!("enqueue-delete-{}-category", category_id));
+                        main_window.remove_action(&format!("sync-interval-category-{}", category_id));
                     }
                 }));
-
                 if let Ok(main_window) = GtkUtil::get_main_window(&row) {
-                    main_window.add_action(&delete_category_action);
-                    main_window.add_action(&rename_category_dialog_action);
+                    main_window.add_action(&sync_interval_action);
                 }
-
-                let model = Menu::new();
-
-                let rename_category_item = MenuItem::new(Some("Rename"), None);
-                rename_category_item.set_action_and_target_value(Some(&format!("rename-category-{}-dialog", category_id)), None);
-                model.append_item(&rename_category_item);
-
-                let delete_category_item = MenuItem::new(Some("Delete"), None);
-                delete_category_item.set_action_and_target_value(Some(&format!("enqueue-delete-{}-category", category_id)), None);
-                model.append_item(&delete_category_item);
+                model.append_submenu(
+                    Some("Refresh"),
+                    &FeedList::sync_interval_menu(&format!("sync-interval-category-{}", category_id)),
+                );
 
                 let popover = Popover::new(Some(&row));
                 popover.set_position(PositionType::Bottom);
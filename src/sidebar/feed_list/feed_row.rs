@@ -1,6 +1,8 @@
 use crate::app::Action;
 use crate::main_window_state::MainWindowState;
+use crate::settings::FeedSyncInterval;
 use crate::sidebar::feed_list::models::FeedListFeedModel;
+use crate::sidebar::feed_list::FeedList;
 use crate::undo_bar::UndoActionModel;
 use crate::util::{BuilderHelper, GtkUtil, Util};
 use cairo::{self, Format, ImageSurface};
@@ -14,7 +16,7 @@ use glib::{
     source::Continue,
     source::SourceId,
     translate::{FromGlib, ToGlib},
-    Sender, Source,
+    Sender, Source, VariantTy,
 };
 use gtk::{
     self, prelude::DragContextExtManual, prelude::WidgetExtManual, BinExt, Box, ContainerExt, EventBox, Image,
@@ -155,31 +157,33 @@ impl FeedRow {
                     .to_glib() as usize,
                 revealer.clone().upcast::<Widget>(),
             ));
+        }
 
-            vec.push((row.connect_button_press_event(clone!(
-                @strong id as feed_id,
-                @strong parent_id,
-                @strong label,
-                @weak window_state,
-                @strong sender => @default-panic, move |row, event|
-            {
-                if event.get_button() != 3 {
-                    return Inhibit(false);
-                }
+        vec.push((row.connect_button_press_event(clone!(
+            @strong id as feed_id,
+            @strong parent_id,
+            @strong label,
+            @weak window_state,
+            @strong sender => @default-panic, move |row, event|
+        {
+            if event.get_button() != 3 {
+                return Inhibit(false);
+            }
 
-                match event.get_event_type() {
-                    EventType::ButtonRelease | EventType::DoubleButtonPress | EventType::TripleButtonPress => {
-                        return Inhibit(false)
-                    }
-                    _ => {}
+            match event.get_event_type() {
+                EventType::ButtonRelease | EventType::DoubleButtonPress | EventType::TripleButtonPress => {
+                    return Inhibit(false)
                 }
+                _ => {}
+            }
 
-                if window_state.read().get_offline() {
-                    return Inhibit(false);
-                }
+            if window_state.read().get_offline() {
+                return Inhibit(false);
+            }
 
-                let model = Menu::new();
+            let model = Menu::new();
 
+            if support_mutation {
                 let rename_feed_dialog_action = SimpleAction::new(&format!("rename-feed-{}-dialog", feed_id), None);
                 rename_feed_dialog_action.connect_activate(clone!(
                     @weak row,
@@ -220,19 +224,45 @@ impl FeedRow {
                 }
                 delete_feed_item.set_action_and_target_value(Some(&format!("enqueue-delete-feed-{}", feed_id)), None);
                 model.append_item(&delete_feed_item);
+            }
 
-                let popover = Popover::new(Some(row));
-                popover.set_position(PositionType::Bottom);
-                popover.bind_model(Some(&model), Some("win"));
-                popover.show();
-                popover.connect_closed(clone!(@weak row => @default-panic, move |_popover| {
-                    row.unset_state_flags(StateFlags::PRELIGHT);
-                }));
-                row.set_state_flags(StateFlags::PRELIGHT, false);
+            let sync_interval_action = SimpleAction::new(
+                &format!("sync-interval-feed-{}", feed_id),
+                Some(VariantTy::new("s").expect("valid type string")),
+            );
+            sync_interval_action.connect_activate(clone!(
+                @weak row,
+                @strong feed_id,
+                @strong sender => @default-panic, move |_action, parameter|
+            {
+                if let Some(interval) = parameter.and_then(|p| p.get::<String>()) {
+                    let interval = FeedSyncInterval::from_action_target(&interval);
+                    Util::send(&sender, Action::SetFeedSyncInterval(feed_id.clone(), interval));
+                }
 
-                Inhibit(true)
-            })).to_glib() as usize, row.clone().upcast::<Widget>()));
-        }
+                if let Ok(main_window) = GtkUtil::get_main_window(&row) {
+                    main_window.remove_action(&format!("sync-interval-feed-{}", feed_id));
+                }
+            }));
+            if let Ok(main_window) = GtkUtil::get_main_window(row) {
+                main_window.add_action(&sync_interval_action);
+            }
+            model.append_submenu(
+                Some("Refresh"),
+                &FeedList::sync_interval_menu(&format!("sync-interval-feed-{}", feed_id)),
+            );
+
+            let popover = Popover::new(Some(row));
+            popover.set_position(PositionType::Bottom);
+            popover.bind_model(Some(&model), Some("win"));
+            popover.show();
+            popover.connect_closed(clone!(@weak row => @default-panic, move |_popover| {
+                row.unset_state_flags(StateFlags::PRELIGHT);
+            }));
+            row.set_state_flags(StateFlags::PRELIGHT, false);
+
+            Inhibit(true)
+        })).to_glib() as usize, row.clone().upcast::<Widget>()));
 
         vec
     }
@@ -5,6 +5,7 @@ pub mod models;
 
 use crate::app::Action;
 use crate::main_window_state::MainWindowState;
+use crate::settings::FeedSyncInterval;
 use crate::sidebar::feed_list::error::{FeedListError, FeedListErrorKind};
 use crate::sidebar::feed_list::{
     category_row::CategoryRow,
@@ -17,7 +18,8 @@ use crate::sidebar::feed_list::{
 use crate::sidebar::{SidebarIterateItem, SidebarSelection};
 use crate::util::{BuilderHelper, GtkUtil, Util, NEWSFLASH_UNCATEGORIZED};
 use gdk::{DragAction, EventType};
-use glib::{clone, source::Continue, translate::ToGlib, Sender};
+use gio::{Menu, MenuItem};
+use glib::{clone, source::Continue, translate::ToGlib, Sender, ToVariant};
 use gtk::{
     self, prelude::WidgetExtManual, ContainerExt, DestDefaults, Inhibit, ListBox, ListBoxExt, ListBoxRow,
     ListBoxRowExt, ScrolledWindow, SelectionMode, StyleContextExt, TargetEntry, TargetFlags, WidgetExt,
@@ -634,4 +636,21 @@ impl FeedList {
             }
         }
     }
+
+    // the "default" entry removes the override
+    pub fn sync_interval_menu(action: &str) -> Menu {
+        let menu = Menu::new();
+
+        let default_item = MenuItem::new(Some("Default"), None);
+        default_item.set_action_and_target_value(Some(action), Some(&"default".to_variant()));
+        menu.append_item(&default_item);
+
+        for interval in FeedSyncInterval::presets() {
+            let item = MenuItem::new(Some(&interval.to_string()), None);
+            item.set_action_and_target_value(Some(action), Some(&interval.to_action_target().to_variant()));
+            menu.append_item(&item);
+        }
+
+        menu
+    }
 }
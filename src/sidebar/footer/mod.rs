@@ -1,9 +1,10 @@
 use super::models::SidebarSelection;
 use crate::app::Action;
+use crate::i18n::i18n_f;
 use crate::main_window_state::MainWindowState;
 use crate::util::{BuilderHelper, Util};
 use glib::{clone, Sender};
-use gtk::{Button, ButtonExt, WidgetExt};
+use gtk::{Button, ButtonExt, ProgressBar, ProgressBarExt, Revealer, RevealerExt, WidgetExt};
 use news_flash::models::PluginCapabilities;
 use parking_lot::RwLock;
 use std::sync::Arc;
@@ -12,6 +13,8 @@ use std::sync::Arc;
 pub struct SidebarFooter {
     pub add_button: Button,
     remove_button: Button,
+    offline_revealer: Revealer,
+    offline_progress: ProgressBar,
     state: Arc<RwLock<MainWindowState>>,
     support_mutation: RwLock<bool>,
     sidebar_selection: Arc<RwLock<SidebarSelection>>,
@@ -27,6 +30,8 @@ impl SidebarFooter {
     ) -> Self {
         let add_button = builder.get::<Button>("add_button");
         let remove_button = builder.get::<Button>("remove_button");
+        let offline_revealer = builder.get::<Revealer>("offline_revealer");
+        let offline_progress = builder.get::<ProgressBar>("offline_progress");
 
         remove_button.connect_clicked(clone!(@strong sender => @default-panic, move |_button| {
             Util::send(&sender, Action::DeleteSidebarSelection);
@@ -44,6 +49,8 @@ impl SidebarFooter {
         SidebarFooter {
             add_button,
             remove_button,
+            offline_revealer,
+            offline_progress,
             state: state.clone(),
             support_mutation: RwLock::new(support_mutation),
             sidebar_selection: sidebar_selection.clone(),
@@ -53,11 +60,13 @@ impl SidebarFooter {
     pub fn update(&self) {
         self.add_button
             .set_sensitive(!self.state.read().get_offline() && *self.support_mutation.read());
-        self.remove_button.set_sensitive(
-            !self.state.read().get_offline()
-                && *self.support_mutation.read()
-                && *self.sidebar_selection.read() != SidebarSelection::All,
-        );
+        let removable = match &*self.sidebar_selection.read() {
+            SidebarSelection::All => false,
+            // smart folders are local and can always be removed
+            SidebarSelection::SmartFolder(_, _) => true,
+            _ => !self.state.read().get_offline() && *self.support_mutation.read(),
+        };
+        self.remove_button.set_sensitive(removable);
     }
 
     pub fn update_features(&self, features: &Arc<RwLock<Option<PluginCapabilities>>>) {
@@ -66,4 +75,20 @@ impl SidebarFooter {
             self.update();
         }
     }
+
+    // `None` hides the progress bar
+    pub fn set_offline_progress(&self, progress: Option<(usize, usize)>) {
+        match progress {
+            Some((done, total)) => {
+                let fraction = if total > 0 { done as f64 / total as f64 } else { 0.0 };
+                self.offline_progress.set_fraction(fraction);
+                self.offline_progress.set_text(Some(&i18n_f(
+                    "Preparing offline reading: {}/{}",
+                    &[&done.to_string(), &total.to_string()],
+                )));
+                self.offline_revealer.set_reveal_child(true);
+            }
+            None => self.offline_revealer.set_reveal_child(false),
+        }
+    }
 }
@@ -2,6 +2,7 @@ mod error;
 mod feed_list;
 mod footer;
 pub mod models;
+mod smart_folder_list;
 mod tag_list;
 
 use self::error::{SidebarError, SidebarErrorKind};
@@ -9,6 +10,7 @@ use self::footer::SidebarFooter;
 use crate::app::Action;
 use crate::i18n::i18n;
 use crate::main_window_state::MainWindowState;
+use crate::smart_folders::SmartFolder;
 use crate::util::{BuilderHelper, GtkUtil, Util};
 use failure::ResultExt;
 pub use feed_list::models::{FeedListDndAction, FeedListItemID, FeedListTree};
@@ -24,6 +26,7 @@ use models::SidebarSelection;
 use news_flash::models::{PluginCapabilities, PluginID, PluginIcon};
 use news_flash::NewsFlash;
 use parking_lot::RwLock;
+use smart_folder_list::SmartFolderList;
 use std::sync::Arc;
 pub use tag_list::models::TagListModel;
 use tag_list::TagList;
@@ -34,6 +37,7 @@ pub struct SideBar {
     state: Arc<RwLock<MainWindowState>>,
     sidebar: Box,
     tags_box: Box,
+    smart_folders_box: Box,
     logo: Image,
     all_event_box: EventBox,
     all_label: Label,
@@ -42,6 +46,7 @@ pub struct SideBar {
     scale_factor: i32,
     pub feed_list: Arc<RwLock<FeedList>>,
     pub tag_list: Arc<RwLock<TagList>>,
+    pub smart_folder_list: Arc<RwLock<SmartFolderList>>,
     selection: Arc<RwLock<SidebarSelection>>,
     categories_expander: Image,
     tags_expander: Image,
@@ -63,6 +68,7 @@ impl SideBar {
 
         let sidebar = builder.get::<Box>("toplevel");
         let tags_box = builder.get::<Box>("tags");
+        let smart_folders_box = builder.get::<Box>("smart_folders");
         let logo = builder.get::<Image>("logo");
         let all_label = builder.get::<Label>("unread_count_all");
         let item_count = 0;
@@ -73,9 +79,13 @@ impl SideBar {
         let tags_expander = builder.get::<Image>("tags_expander");
         let categories_revealer = builder.get::<Revealer>("categories_revealer");
         let tags_revealer = builder.get::<Revealer>("tags_revealer");
+        let smart_folders_event_box = builder.get::<EventBox>("smart_folders_event_box");
+        let smart_folders_expander = builder.get::<Image>("smart_folders_expander");
+        let smart_folders_revealer = builder.get::<Revealer>("smart_folders_revealer");
         let all_event_box = builder.get::<EventBox>("all_event_box");
         let feed_list_box = builder.get::<Box>("feed_list_box");
         let tag_list_box = builder.get::<Box>("tags_list_box");
+        let smart_folder_list_box = builder.get::<Box>("smart_folders_list_box");
         let sidebar_scroll = builder.get::<ScrolledWindow>("sidebar_scroll");
 
         let selection_handle = Arc::new(RwLock::new(SidebarSelection::All));
@@ -83,6 +93,7 @@ impl SideBar {
 
         let feed_list = FeedList::new(&sidebar_scroll, state, sender.clone());
         let tag_list = TagList::new(state);
+        let smart_folder_list = SmartFolderList::new(state, &sender);
         let footer = Arc::new(SidebarFooter::new(
             &builder,
             state,
@@ -93,9 +104,11 @@ impl SideBar {
 
         let feed_list_handle = Arc::new(RwLock::new(feed_list));
         let tag_list_handle = Arc::new(RwLock::new(tag_list));
+        let smart_folder_list_handle = Arc::new(RwLock::new(smart_folder_list));
 
         feed_list_box.pack_start(&feed_list_handle.read().widget(), false, true, 0);
         tag_list_box.pack_start(&tag_list_handle.read().widget(), false, true, 0);
+        smart_folder_list_box.pack_start(&smart_folder_list_handle.read().widget(), false, true, 0);
 
         feed_list_handle.read().widget().connect_row_activated(
             clone!(@strong sender, @weak footer, @weak selection_handle => @default-panic, move |_list, _row| {
@@ -110,6 +123,7 @@ impl SideBar {
         feed_list_handle.read().widget().connect_row_selected(clone!(
             @weak all_event_box,
             @weak tag_list_handle,
+            @weak smart_folder_list_handle,
             @strong feed_list_handle as self_handle,
             @strong selection_handle,
             @weak delayed_all_selection => @default-panic, move |_list, row|
@@ -118,9 +132,10 @@ impl SideBar {
             if row.is_none() {
                 return;
             }
-            // deselect 'all' & tag_list
+            // deselect 'all', tag_list & smart folders
             Self::deselect_all_button(&all_event_box, &delayed_all_selection);
             tag_list_handle.read().deselect();
+            smart_folder_list_handle.read().deselect();
 
             if let Some((item, title)) = self_handle.read().get_selection() {
                 let selection = SidebarSelection::from_feed_list_selection(item, title);
@@ -141,6 +156,7 @@ impl SideBar {
         tag_list_handle.read().widget().connect_row_selected(clone!(
             @weak all_event_box,
             @weak feed_list_handle,
+            @weak smart_folder_list_handle,
             @strong tag_list_handle,
             @weak selection_handle,
             @weak delayed_all_selection => @default-panic, move |_list, row| {
@@ -148,9 +164,10 @@ impl SideBar {
             if row.is_none() {
                 return;
             }
-            // deselect 'all' & tag_list
+            // deselect 'all', feed_list & smart folders
             Self::deselect_all_button(&all_event_box, &delayed_all_selection);
             feed_list_handle.read().deselect();
+            smart_folder_list_handle.read().deselect();
 
             if let Some((selected_id, title)) = tag_list_handle.read().get_selection() {
                 let selection = SidebarSelection::Tag(selected_id, title);
@@ -158,10 +175,42 @@ impl SideBar {
             }
         }));
 
+        smart_folder_list_handle.read().widget().connect_row_activated(
+            clone!(@weak selection_handle, @weak footer, @strong sender => @default-panic, move |_list, _row| {
+                Util::send(
+                    &sender,
+                    Action::SidebarSelection((*selection_handle.read()).clone()),
+                );
+                footer.update();
+            }),
+        );
+
+        smart_folder_list_handle.read().widget().connect_row_selected(clone!(
+            @weak all_event_box,
+            @weak feed_list_handle,
+            @weak tag_list_handle,
+            @strong smart_folder_list_handle,
+            @weak selection_handle,
+            @weak delayed_all_selection => @default-panic, move |_list, row| {
+            // do nothing if selection was cleared
+            if row.is_none() {
+                return;
+            }
+            // deselect 'all', feed_list & tag_list
+            Self::deselect_all_button(&all_event_box, &delayed_all_selection);
+            feed_list_handle.read().deselect();
+            tag_list_handle.read().deselect();
+
+            if let Some((id, label)) = smart_folder_list_handle.read().get_selection() {
+                *selection_handle.write() = SidebarSelection::SmartFolder(id, label);
+            }
+        }));
+
         let scale = GtkUtil::get_scale(&sidebar);
 
         let expanded_categories = Arc::new(RwLock::new(true));
         let expanded_tags = Arc::new(RwLock::new(false));
+        let expanded_smart_folders = Arc::new(RwLock::new(true));
 
         Self::setup_expander(
             &categories_event_box,
@@ -170,11 +219,24 @@ impl SideBar {
             &expanded_categories,
         );
         Self::setup_expander(&tags_event_box, &tags_expander, &tags_revealer, &expanded_tags);
+        Self::setup_expander(
+            &smart_folders_event_box,
+            &smart_folders_expander,
+            &smart_folders_revealer,
+            &expanded_smart_folders,
+        );
+        Self::expand_list(
+            true,
+            &smart_folders_revealer,
+            &smart_folders_expander,
+            &expanded_smart_folders,
+        );
         Self::setup_all_button(
             &all_event_box,
             &sender,
             feed_list_handle.clone(),
             tag_list_handle.clone(),
+            smart_folder_list_handle.clone(),
             selection_handle.clone(),
             footer.clone(),
             &delayed_all_selection,
@@ -185,6 +247,7 @@ impl SideBar {
             state: state.clone(),
             sidebar,
             tags_box,
+            smart_folders_box,
             logo,
             all_event_box,
             all_label,
@@ -193,6 +256,7 @@ impl SideBar {
             scale_factor: scale,
             feed_list: feed_list_handle,
             tag_list: tag_list_handle,
+            smart_folder_list: smart_folder_list_handle,
             selection: selection_handle,
             categories_expander,
             tags_expander,
@@ -229,6 +293,17 @@ impl SideBar {
         self.tags_box.show();
     }
 
+    pub fn update_smart_folders(&self, folders: Vec<(SmartFolder, i64)>) {
+        self.smart_folder_list.read().update(folders);
+        if self.smart_folder_list.read().is_empty() {
+            self.smart_folders_box.hide();
+        } else {
+            self.smart_folders_box.show_all();
+            self.smart_folder_list.read().widget().show_all();
+            self.smart_folders_box.show();
+        }
+    }
+
     pub fn update_all(&mut self, item_count: i64) {
         self.item_count = item_count;
         self.update_all_label();
@@ -328,11 +403,13 @@ impl SideBar {
         *expanded.write() = expand;
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn setup_all_button(
         event_box: &EventBox,
         sender: &Sender<Action>,
         feed_list_handle: Arc<RwLock<FeedList>>,
         tag_list_handle: Arc<RwLock<TagList>>,
+        smart_folder_list_handle: Arc<RwLock<SmartFolderList>>,
         selection_handle: Arc<RwLock<SidebarSelection>>,
         footer: Arc<SidebarFooter>,
         delayed_selection: &Arc<RwLock<Option<u32>>>,
@@ -367,6 +444,7 @@ impl SideBar {
 
             feed_list_handle.read().deselect();
             tag_list_handle.read().deselect();
+            smart_folder_list_handle.read().deselect();
 
             Self::select_all_button(widget, &sender, &selection_handle, &delayed_selection);
             footer.update();
@@ -420,6 +498,7 @@ impl SideBar {
                 self.feed_list.read().select_next_item()
             }
             SidebarSelection::Tag(_, _) => self.tag_list.read().get_next_item(),
+            SidebarSelection::SmartFolder(_, _) => SidebarIterateItem::SelectAll,
         };
         self.select_item(select_next)
     }
@@ -431,6 +510,7 @@ impl SideBar {
                 self.feed_list.read().select_prev_item()
             }
             SidebarSelection::Tag(_, _) => self.tag_list.read().get_prev_item(),
+            SidebarSelection::SmartFolder(_, _) => SidebarIterateItem::TagListSelectLastItem,
         };
         self.select_item(select_next)
     }
@@ -534,6 +614,8 @@ impl SideBar {
         self.feed_list.read().widget().unselect_all();
         self.tag_list.read().cancel_selection();
         self.tag_list.read().widget().unselect_all();
+        self.smart_folder_list.read().cancel_selection();
+        self.smart_folder_list.read().widget().unselect_all();
     }
 
     pub fn expand_collapse_selected_category(&self) {
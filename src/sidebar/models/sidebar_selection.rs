@@ -1,6 +1,7 @@
 use crate::sidebar::FeedListItemID;
 use news_flash::models::{CategoryID, FeedID, TagID};
 use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum SidebarSelection {
@@ -8,6 +9,7 @@ pub enum SidebarSelection {
     Category(CategoryID, String),
     Feed(FeedID, CategoryID, String),
     Tag(TagID, String),
+    SmartFolder(String, String),
 }
 
 impl SidebarSelection {
@@ -40,6 +42,38 @@ impl PartialEq for SidebarSelection {
                 SidebarSelection::Tag(other_id, _title) => self_id == other_id,
                 _ => false,
             },
+            SidebarSelection::SmartFolder(self_id, _title) => match other {
+                SidebarSelection::SmartFolder(other_id, _title) => self_id == other_id,
+                _ => false,
+            },
+        }
+    }
+}
+
+impl Eq for SidebarSelection {}
+
+impl Hash for SidebarSelection {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // titles are ignored, same as in `PartialEq`
+        match self {
+            SidebarSelection::All => 0.hash(state),
+            SidebarSelection::Category(id, _title) => {
+                1.hash(state);
+                id.hash(state);
+            }
+            SidebarSelection::Feed(id, parent_id, _title) => {
+                2.hash(state);
+                id.hash(state);
+                parent_id.hash(state);
+            }
+            SidebarSelection::Tag(id, _title) => {
+                3.hash(state);
+                id.hash(state);
+            }
+            SidebarSelection::SmartFolder(id, _title) => {
+                4.hash(state);
+                id.hash(state);
+            }
         }
     }
 }
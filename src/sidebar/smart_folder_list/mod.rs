@@ -0,0 +1,138 @@
+mod smart_folder_row;
+
+use self::smart_folder_row::SmartFolderRow;
+use crate::app::Action;
+use crate::main_window_state::MainWindowState;
+use crate::sidebar::SidebarSelection;
+use crate::smart_folders::SmartFolder;
+use crate::util::{BuilderHelper, GtkUtil};
+use glib::{clone, source::Continue, Sender};
+use gtk::{ContainerExt, ListBox, ListBoxExt, ListBoxRowExt, SelectionMode, WidgetExt};
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+#[derive(Clone, Debug)]
+pub struct SmartFolderList {
+    list: ListBox,
+    rows: Arc<RwLock<Vec<(SmartFolderRow, String)>>>,
+    state: Arc<RwLock<MainWindowState>>,
+    sender: Sender<Action>,
+    delayed_selection: Arc<RwLock<Option<u32>>>,
+}
+
+impl SmartFolderList {
+    pub fn new(state: &Arc<RwLock<MainWindowState>>, sender: &Sender<Action>) -> Self {
+        let builder = BuilderHelper::new("sidebar_list");
+        let list_box = builder.get::<ListBox>("sidebar_list");
+
+        // set selection mode from NONE -> SINGLE after a delay after it's been shown
+        // this ensures selection mode is in SINGLE without having a selected row in the list
+        list_box.connect_show(|list| {
+            gtk::timeout_add(
+                50,
+                clone!(@weak list => @default-panic, move || {
+                    list.set_selection_mode(SelectionMode::Single);
+                    Continue(false)
+                }),
+            );
+        });
+
+        SmartFolderList {
+            list: list_box,
+            rows: Arc::new(RwLock::new(Vec::new())),
+            state: state.clone(),
+            sender: sender.clone(),
+            delayed_selection: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    pub fn widget(&self) -> ListBox {
+        self.list.clone()
+    }
+
+    pub fn on_window_hidden(&self) {
+        self.list.set_selection_mode(SelectionMode::None);
+    }
+
+    pub fn on_window_show(&self) {
+        gtk::timeout_add(
+            50,
+            clone!(
+                @weak self.list as list,
+                @weak self.state as state,
+                @weak self.rows as rows => @default-panic, move ||
+            {
+                list.set_selection_mode(SelectionMode::Single);
+                if let SidebarSelection::SmartFolder(id, _label) = state.read().get_sidebar_selection() {
+                    if let Some((row, _label)) = rows.read().iter().find(|(row, _label)| &row.id == id) {
+                        list.select_row(Some(&row.widget()));
+                    }
+                }
+                Continue(false)
+            }),
+        );
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.read().is_empty()
+    }
+
+    pub fn update(&self, folders: Vec<(SmartFolder, i64)>) {
+        let unchanged = {
+            let rows = self.rows.read();
+            rows.len() == folders.len()
+                && rows
+                    .iter()
+                    .zip(folders.iter())
+                    .all(|((row, _label), (folder, _count))| row.id == folder.id)
+        };
+
+        if unchanged {
+            let mut rows = self.rows.write();
+            for ((row, label), (folder, count)) in rows.iter_mut().zip(folders.iter()) {
+                row.update(&folder.label, *count);
+                *label = folder.label.clone();
+            }
+            return;
+        }
+
+        for (row, _label) in self.rows.write().drain(..) {
+            self.list.remove(&row.widget());
+        }
+
+        let selected_id = match self.state.read().get_sidebar_selection() {
+            SidebarSelection::SmartFolder(id, _label) => Some(id.clone()),
+            _ => None,
+        };
+
+        for (folder, count) in folders {
+            let row = SmartFolderRow::new(&folder.id, &folder.label, count, &self.sender);
+            self.list.insert(&row.widget(), -1);
+            if selected_id.as_ref() == Some(&folder.id) {
+                self.list.select_row(Some(&row.widget()));
+            }
+            self.rows.write().push((row, folder.label));
+        }
+    }
+
+    pub fn deselect(&self) {
+        self.list.unselect_all();
+    }
+
+    pub fn get_selection(&self) -> Option<(String, String)> {
+        let row = self.list.get_selected_row()?;
+        let index = row.get_index();
+        if index < 0 {
+            return None;
+        }
+        self.rows
+            .read()
+            .get(index as usize)
+            .map(|(row, label)| (row.id.clone(), label.clone()))
+    }
+
+    pub fn cancel_selection(&self) {
+        GtkUtil::remove_source(*self.delayed_selection.read());
+        *self.delayed_selection.write() = None;
+    }
+}
@@ -0,0 +1,125 @@
+use crate::app::Action;
+use crate::i18n::i18n;
+use crate::util::{BuilderHelper, GtkUtil, Util};
+use gdk::EventType;
+use gio::{ActionMapExt, Menu, MenuItem, SimpleAction};
+use glib::{clone, Sender};
+use gtk::{
+    Box, ContainerExt, Inhibit, Label, LabelExt, ListBoxRow, ListBoxRowExt, Popover, PopoverExt, PositionType,
+    StateFlags, StyleContextExt, WidgetExt,
+};
+
+#[derive(Clone, Debug)]
+pub struct SmartFolderRow {
+    pub id: String,
+    widget: ListBoxRow,
+    title: Label,
+    item_count: Label,
+}
+
+impl SmartFolderRow {
+    pub fn new(id: &str, label: &str, item_count: i64, sender: &Sender<Action>) -> Self {
+        let builder = BuilderHelper::new("smart_folder");
+        let folder_box = builder.get::<Box>("smart_folder_row");
+        let title = builder.get::<Label>("smart_folder_title");
+        let item_count_label = builder.get::<Label>("item_count");
+
+        let row = SmartFolderRow {
+            id: id.to_owned(),
+            widget: Self::create_row(&folder_box, id, sender),
+            title,
+            item_count: item_count_label,
+        };
+        row.update(label, item_count);
+        row
+    }
+
+    fn create_row(widget: &Box, id: &str, sender: &Sender<Action>) -> ListBoxRow {
+        let row = ListBoxRow::new();
+        row.set_activatable(true);
+        row.set_can_focus(false);
+        row.add(widget);
+        let context = row.get_style_context();
+        context.remove_class("activatable");
+
+        let id = id.to_owned();
+        row.connect_button_press_event(clone!(@strong sender => @default-panic, move |row, event| {
+            if event.get_button() != 3 {
+                return Inhibit(false);
+            }
+
+            match event.get_event_type() {
+                EventType::ButtonRelease | EventType::DoubleButtonPress | EventType::TripleButtonPress => {
+                    return Inhibit(false)
+                }
+                _ => {}
+            }
+
+            let edit_action_name = format!("edit-{}", id);
+            let edit_action = SimpleAction::new(&edit_action_name, None);
+            edit_action.connect_activate(clone!(
+                @weak row,
+                @strong id,
+                @strong edit_action_name,
+                @strong sender => @default-panic, move |_action, _parameter|
+            {
+                Util::send(&sender, Action::SmartFolderDialog(Some(id.clone())));
+
+                if let Ok(main_window) = GtkUtil::get_main_window(&row) {
+                    main_window.remove_action(&edit_action_name);
+                }
+            }));
+
+            let delete_action_name = format!("delete-{}", id);
+            let delete_action = SimpleAction::new(&delete_action_name, None);
+            delete_action.connect_activate(clone!(
+                @weak row,
+                @strong id,
+                @strong delete_action_name,
+                @strong sender => @default-panic, move |_action, _parameter|
+            {
+                Util::send(&sender, Action::DeleteSmartFolder(id.clone()));
+
+                if let Ok(main_window) = GtkUtil::get_main_window(&row) {
+                    main_window.remove_action(&delete_action_name);
+                }
+            }));
+
+            if let Ok(main_window) = GtkUtil::get_main_window(row) {
+                main_window.add_action(&edit_action);
+                main_window.add_action(&delete_action);
+            }
+
+            let model = Menu::new();
+            let edit_item = MenuItem::new(Some(&i18n("Edit")), None);
+            edit_item.set_action_and_target_value(Some(&edit_action_name), None);
+            model.append_item(&edit_item);
+            let delete_item = MenuItem::new(Some(&i18n("Delete")), None);
+            delete_item.set_action_and_target_value(Some(&delete_action_name), None);
+            model.append_item(&delete_item);
+
+            let popover = Popover::new(Some(row));
+            popover.set_position(PositionType::Bottom);
+            popover.bind_model(Some(&model), Some("win"));
+            popover.show();
+            popover.connect_closed(clone!(@weak row => @default-panic, move |_popover| {
+                row.unset_state_flags(StateFlags::PRELIGHT);
+            }));
+            row.set_state_flags(StateFlags::PRELIGHT, false);
+
+            Inhibit(true)
+        }));
+
+        row
+    }
+
+    pub fn widget(&self) -> ListBoxRow {
+        self.widget.clone()
+    }
+
+    pub fn update(&self, label: &str, item_count: i64) {
+        self.title.set_label(label);
+        self.item_count.set_label(&item_count.to_string());
+        self.item_count.set_visible(item_count > 0);
+    }
+}
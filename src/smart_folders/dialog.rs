@@ -0,0 +1,218 @@
+use super::SmartFolder;
+use crate::app::Action;
+use crate::content_page::{DateRange, HeaderSelection};
+use crate::i18n::i18n;
+use crate::main_window_state::MainWindowState;
+use crate::settings::Settings;
+use crate::sidebar::models::SidebarSelection;
+use crate::util::{BuilderHelper, Util};
+use glib::{clone, Sender};
+use gtk::{
+    Button, ButtonExt, CheckButton, ComboBoxExt, ComboBoxTextExt, ContainerExt, EditableSignals, Entry, EntryExt,
+    GtkWindowExt, Label, LabelExt, ListBox, ListBoxRow, ListBoxRowExt, StyleContextExt, ToggleButtonExt, WidgetExt,
+    Window,
+};
+use news_flash::models::{CategoryID, CategoryType, FeedID};
+use news_flash::NewsFlash;
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+pub struct SmartFolderDialog {
+    pub widget: Window,
+}
+
+impl SmartFolderDialog {
+    pub fn new(
+        window: &gtk::ApplicationWindow,
+        sender: &Sender<Action>,
+        settings: &Arc<RwLock<Settings>>,
+        news_flash: &Arc<RwLock<Option<NewsFlash>>>,
+        state: &Arc<RwLock<MainWindowState>>,
+        folder: Option<SmartFolder>,
+    ) -> Self {
+        let builder = BuilderHelper::new("smart_folder_dialog");
+        let dialog = builder.get::<Window>("smart_folder_dialog");
+        dialog.set_transient_for(Some(window));
+
+        let name_entry = builder.get::<Entry>("name_entry");
+        let search_entry = builder.get::<Entry>("search_entry");
+        let header_combo = builder.get::<gtk::ComboBoxText>("header_combo");
+        let date_combo = builder.get::<gtk::ComboBoxText>("date_combo");
+        let sources_list = builder.get::<ListBox>("sources_list");
+        let cancel_button = builder.get::<Button>("cancel_button");
+        let save_button = builder.get::<Button>("save_button");
+
+        let folder = folder.unwrap_or_else(|| Self::folder_from_state(&state.read()));
+
+        header_combo.append(Some("all"), &i18n("All Articles"));
+        header_combo.append(Some("unread"), &i18n("Unread"));
+        header_combo.append(Some("marked"), &i18n("Starred"));
+        header_combo.set_active_id(Some(match folder.header {
+            HeaderSelection::All => "all",
+            HeaderSelection::Unread => "unread",
+            HeaderSelection::Marked => "marked",
+        }));
+
+        let mut date_ranges = DateRange::presets();
+        if !date_ranges.contains(&folder.date_range) {
+            date_ranges.push(folder.date_range.clone());
+        }
+        for range in &date_ranges {
+            date_combo.append(Some(&range.to_id()), &range.label());
+        }
+        date_combo.set_active_id(Some(&folder.date_range.to_id()));
+
+        name_entry.set_text(&folder.label);
+        search_entry.set_text(folder.search_term.as_deref().unwrap_or(""));
+
+        let (category_checks, feed_checks) = Self::fill_sources(&sources_list, news_flash, &folder);
+
+        cancel_button.connect_clicked(clone!(@weak dialog => @default-panic, move |_button| {
+            dialog.close();
+        }));
+
+        save_button.connect_clicked(clone!(
+            @weak dialog,
+            @weak name_entry,
+            @weak search_entry,
+            @weak header_combo,
+            @weak date_combo,
+            @strong settings,
+            @strong sender => @default-panic, move |_button|
+        {
+            let label = name_entry.get_text().as_str().trim().to_owned();
+            if label.is_empty() {
+                name_entry.get_style_context().add_class("error");
+                return;
+            }
+
+            let search_term = search_entry.get_text().as_str().trim().to_owned();
+            let header = match header_combo.get_active_id().as_ref().map(|id| id.as_str()) {
+                Some("unread") => HeaderSelection::Unread,
+                Some("marked") => HeaderSelection::Marked,
+                _ => HeaderSelection::All,
+            };
+            let date_range = date_combo
+                .get_active_id()
+                .and_then(|id| DateRange::from_id(id.as_str()))
+                .unwrap_or_default();
+
+            let folder = SmartFolder {
+                id: folder.id.clone(),
+                label,
+                search_term: if search_term.is_empty() { None } else { Some(search_term) },
+                header,
+                feeds: feed_checks
+                    .iter()
+                    .filter(|(check, _id)| check.get_active())
+                    .map(|(_check, id)| id.clone())
+                    .collect(),
+                categories: category_checks
+                    .iter()
+                    .filter(|(check, _id)| check.get_active())
+                    .map(|(_check, id)| id.clone())
+                    .collect(),
+                date_range,
+            };
+
+            if settings.write().save_smart_folder(folder).is_err() {
+                Util::send(
+                    &sender,
+                    Action::ErrorSimpleMessage("Failed to save smart folder.".to_owned()),
+                );
+            }
+            Util::send(&sender, Action::RefreshSidebar);
+            Util::send(&sender, Action::UpdateArticleList);
+            dialog.close();
+        }));
+
+        name_entry.connect_changed(|entry| {
+            entry.get_style_context().remove_class("error");
+        });
+
+        dialog.show_all();
+        SmartFolderDialog { widget: dialog }
+    }
+
+    fn folder_from_state(state: &MainWindowState) -> SmartFolder {
+        let search_term = state.get_search_term().clone();
+        let (label, feeds, categories) = match state.get_sidebar_selection() {
+            SidebarSelection::Feed(feed_id, _parent_id, label) => (label.clone(), vec![feed_id.clone()], Vec::new()),
+            SidebarSelection::Category(category_id, label) => (label.clone(), Vec::new(), vec![category_id.clone()]),
+            _ => (search_term.clone().unwrap_or_default(), Vec::new(), Vec::new()),
+        };
+
+        SmartFolder {
+            id: SmartFolder::generate_id(),
+            label,
+            search_term,
+            header: state.get_header_selection().clone(),
+            feeds,
+            categories,
+            date_range: DateRange::All,
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn fill_sources(
+        list: &ListBox,
+        news_flash: &Arc<RwLock<Option<NewsFlash>>>,
+        folder: &SmartFolder,
+    ) -> (Vec<(CheckButton, CategoryID)>, Vec<(CheckButton, FeedID)>) {
+        let mut category_checks = Vec::new();
+        let mut feed_checks = Vec::new();
+
+        if let Some(news_flash) = news_flash.read().as_ref() {
+            let mut categories = news_flash.get_categories().unwrap_or_default();
+            categories.retain(|category| category.category_type != CategoryType::Generated);
+            categories.sort_by(|a, b| a.label.to_lowercase().cmp(&b.label.to_lowercase()));
+            let mut feeds = news_flash
+                .get_feeds()
+                .map(|(feeds, _mappings)| feeds)
+                .unwrap_or_default();
+            feeds.sort_by(|a, b| a.label.to_lowercase().cmp(&b.label.to_lowercase()));
+
+            if !categories.is_empty() {
+                Self::add_heading(list, &i18n("Categories"));
+            }
+            for category in categories {
+                let check = Self::add_check(list, &category.label);
+                check.set_active(folder.categories.contains(&category.category_id));
+                category_checks.push((check, category.category_id));
+            }
+
+            if !feeds.is_empty() {
+                Self::add_heading(list, &i18n("Feeds"));
+            }
+            for feed in feeds {
+                let check = Self::add_check(list, &feed.label);
+                check.set_active(folder.feeds.contains(&feed.feed_id));
+                feed_checks.push((check, feed.feed_id));
+            }
+        }
+
+        (category_checks, feed_checks)
+    }
+
+    fn add_heading(list: &ListBox, title: &str) {
+        let label = Label::new(Some(title));
+        label.set_xalign(0.0);
+        label.set_margin_start(6);
+        label.set_margin_top(6);
+        label.get_style_context().add_class("dim-label");
+        let row = ListBoxRow::new();
+        row.set_activatable(false);
+        row.add(&label);
+        list.add(&row);
+    }
+
+    fn add_check(list: &ListBox, title: &str) -> CheckButton {
+        let check = CheckButton::with_label(title);
+        check.set_margin_start(12);
+        let row = ListBoxRow::new();
+        row.set_activatable(false);
+        row.add(&check);
+        list.add(&row);
+        check
+    }
+}
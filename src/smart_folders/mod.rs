@@ -0,0 +1,189 @@
+mod dialog;
+
+pub use self::dialog::SmartFolderDialog;
+
+use crate::content_page::{DateRange, HeaderSelection};
+use crate::i18n::i18n;
+use crate::util::Util;
+use chrono::Utc;
+use news_flash::models::{ArticleFilter, Category, CategoryID, FeedID, FeedMapping, Marked, Read};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SmartFolder {
+    pub id: String,
+    pub label: String,
+    #[serde(default)]
+    pub search_term: Option<String>,
+    pub header: HeaderSelection,
+    // empty means all feeds
+    #[serde(default)]
+    pub feeds: Vec<FeedID>,
+    #[serde(default)]
+    pub categories: Vec<CategoryID>,
+    #[serde(default)]
+    pub date_range: DateRange,
+}
+
+impl SmartFolder {
+    pub fn generate_id() -> String {
+        format!("smart-folder-{}", Utc::now().timestamp_nanos())
+    }
+
+    // added once, afterwards it can be edited or deleted like any other folder
+    pub fn podcasts() -> Self {
+        SmartFolder {
+            id: "podcasts".to_owned(),
+            label: i18n("Podcasts"),
+            search_term: Some("has:media".to_owned()),
+            header: HeaderSelection::All,
+            feeds: Vec::new(),
+            categories: Vec::new(),
+            date_range: DateRange::All,
+        }
+    }
+
+    pub fn feed_blacklist(
+        &self,
+        feed_ids: &[FeedID],
+        mappings: &[FeedMapping],
+        categories: &[Category],
+    ) -> Option<Vec<FeedID>> {
+        if self.feeds.is_empty() && self.categories.is_empty() {
+            return None;
+        }
+
+        let mut selected_feeds = Util::feeds_of_categories(&self.categories, categories, mappings);
+        selected_feeds.extend(self.feeds.iter().cloned());
+
+        Some(
+            feed_ids
+                .iter()
+                .filter(|feed_id| !selected_feeds.contains(*feed_id))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    // header selection and search term of the main window further narrow down the result
+    pub fn article_filter(
+        &self,
+        header: &HeaderSelection,
+        search_term: Option<String>,
+        feed_blacklist: Option<Vec<FeedID>>,
+    ) -> ArticleFilter<'static> {
+        let unread = if self.header == HeaderSelection::Unread || header == &HeaderSelection::Unread {
+            Some(Read::Unread)
+        } else {
+            None
+        };
+        let marked = if self.header == HeaderSelection::Marked || header == &HeaderSelection::Marked {
+            Some(Marked::Marked)
+        } else {
+            None
+        };
+        let search_term = match (&self.search_term, search_term) {
+            (Some(folder_term), Some(term)) => Some(format!("{} {}", folder_term, term)),
+            (Some(folder_term), None) => Some(folder_term.clone()),
+            (None, term) => term,
+        };
+        let (newer_than, older_than) = self.date_range.bounds();
+
+        ArticleFilter {
+            limit: None,
+            offset: None,
+            order: None,
+            unread,
+            marked,
+            feed: None,
+            feed_blacklist,
+            category: None,
+            category_blacklist: None,
+            tag: None,
+            ids: None,
+            newer_than,
+            older_than,
+            search_term,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SmartFolder;
+    use crate::content_page::{DateRange, HeaderSelection};
+    use news_flash::models::{Category, CategoryID, CategoryType, FeedID, FeedMapping, NEWSFLASH_TOPLEVEL};
+
+    fn folder(feeds: Vec<FeedID>, categories: Vec<CategoryID>) -> SmartFolder {
+        SmartFolder {
+            id: SmartFolder::generate_id(),
+            label: "Folder".to_owned(),
+            search_term: None,
+            header: HeaderSelection::All,
+            feeds,
+            categories,
+            date_range: DateRange::All,
+        }
+    }
+
+    fn category(id: &str, parent: &CategoryID) -> Category {
+        Category {
+            category_id: CategoryID::new(id),
+            label: id.to_owned(),
+            parent_id: parent.clone(),
+            sort_index: None,
+            category_type: CategoryType::Default,
+        }
+    }
+
+    fn mapping(feed: &str, category: &str) -> FeedMapping {
+        FeedMapping {
+            feed_id: FeedID::new(feed),
+            category_id: CategoryID::new(category),
+        }
+    }
+
+    #[test]
+    fn feed_blacklist() {
+        let feed_ids = vec![FeedID::new("feed_1"), FeedID::new("feed_2"), FeedID::new("feed_3")];
+        let categories = vec![
+            category("category_1", &NEWSFLASH_TOPLEVEL),
+            category("category_2", &CategoryID::new("category_1")),
+            category("category_3", &NEWSFLASH_TOPLEVEL),
+        ];
+        let mappings = vec![
+            mapping("feed_1", "category_1"),
+            mapping("feed_2", "category_2"),
+            mapping("feed_3", "category_3"),
+        ];
+
+        assert_eq!(
+            folder(vec![], vec![]).feed_blacklist(&feed_ids, &mappings, &categories),
+            None
+        );
+
+        let by_category = folder(vec![], vec![CategoryID::new("category_1")]);
+        assert_eq!(
+            by_category.feed_blacklist(&feed_ids, &mappings, &categories),
+            Some(vec![FeedID::new("feed_3")])
+        );
+
+        let by_feed = folder(vec![FeedID::new("feed_3")], vec![CategoryID::new("category_2")]);
+        assert_eq!(
+            by_feed.feed_blacklist(&feed_ids, &mappings, &categories),
+            Some(vec![FeedID::new("feed_1")])
+        );
+    }
+
+    #[test]
+    fn combines_with_window_state() {
+        let mut folder = folder(vec![], vec![]);
+        folder.header = HeaderSelection::Unread;
+        folder.search_term = Some("rust".to_owned());
+
+        let filter = folder.article_filter(&HeaderSelection::Marked, Some("gtk".to_owned()), None);
+        assert!(filter.unread.is_some());
+        assert!(filter.marked.is_some());
+        assert_eq!(filter.search_term, Some("rust gtk".to_owned()));
+    }
+}
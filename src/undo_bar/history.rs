@@ -0,0 +1,135 @@
+use super::models::UndoActionModel;
+
+const HISTORY_LIMIT: usize = 100;
+
+// Deferred actions (see `UndoActionModel::inverse`) stay on the undo stack only as long as they
+// are pending in the undo bar. Once they are executed they are dropped, or in case of article
+// batches replaced by the already applied change.
+#[derive(Debug, Default)]
+pub struct UndoHistory {
+    undo: Vec<UndoActionModel>,
+    redo: Vec<UndoActionModel>,
+}
+
+impl UndoHistory {
+    pub fn new() -> Self {
+        UndoHistory::default()
+    }
+
+    pub fn push(&mut self, action: UndoActionModel) {
+        self.redo.clear();
+        self.undo.push(action);
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+    }
+
+    pub fn undo(&mut self) -> Option<UndoActionModel> {
+        let action = self.undo.pop()?;
+        self.redo.push(action.clone());
+        Some(action)
+    }
+
+    pub fn redo(&mut self) -> Option<UndoActionModel> {
+        let action = self.redo.pop()?;
+        self.undo.push(action.clone());
+        Some(action)
+    }
+
+    pub fn executed(&mut self, action: &UndoActionModel) {
+        if let Some(pos) = self.undo.iter().rposition(|a| a == action) {
+            match action {
+                UndoActionModel::ArticleBatch(article_ids, operation) => {
+                    self.undo[pos] = UndoActionModel::ArticleChange(article_ids.clone(), operation.clone());
+                }
+                _ => {
+                    self.undo.remove(pos);
+                }
+            }
+        }
+    }
+
+    pub fn cancelled(&mut self, action: &UndoActionModel) {
+        if let Some(pos) = self.undo.iter().rposition(|a| a == action) {
+            let action = self.undo.remove(pos);
+            self.redo.push(action);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UndoHistory, HISTORY_LIMIT};
+    use crate::article_list::BatchOperation;
+    use crate::undo_bar::UndoActionModel;
+    use news_flash::models::{ArticleID, FeedID};
+
+    fn rename(label: &str) -> UndoActionModel {
+        UndoActionModel::RenameFeed(FeedID::new("feed"), "old".to_owned(), label.to_owned())
+    }
+
+    #[test]
+    fn undo_redo_order() {
+        let mut history = UndoHistory::new();
+        history.push(rename("a"));
+        history.push(rename("b"));
+
+        assert_eq!(history.undo(), Some(rename("b")));
+        assert_eq!(history.undo(), Some(rename("a")));
+        assert_eq!(history.undo(), None);
+
+        assert_eq!(history.redo(), Some(rename("a")));
+        assert_eq!(history.redo(), Some(rename("b")));
+        assert_eq!(history.redo(), None);
+        assert!(!history.undo.is_empty());
+    }
+
+    #[test]
+    fn push_clears_redo() {
+        let mut history = UndoHistory::new();
+        history.push(rename("a"));
+        history.undo();
+        assert!(!history.redo.is_empty());
+
+        history.push(rename("b"));
+        assert!(history.redo.is_empty());
+    }
+
+    #[test]
+    fn limit() {
+        let mut history = UndoHistory::new();
+        for i in 0..=HISTORY_LIMIT {
+            history.push(rename(&i.to_string()));
+        }
+
+        let mut count = 0;
+        while history.undo().is_some() {
+            count += 1;
+        }
+        assert_eq!(count, HISTORY_LIMIT);
+    }
+
+    #[test]
+    fn deferred_actions() {
+        let ids = vec![ArticleID::new("article")];
+        let delete = UndoActionModel::DeleteFeed(FeedID::new("feed"), "Feed".to_owned());
+        let batch = UndoActionModel::ArticleBatch(ids.clone(), BatchOperation::Star);
+
+        let mut history = UndoHistory::new();
+        history.push(batch.clone());
+        history.push(delete.clone());
+
+        history.executed(&delete);
+        history.executed(&batch);
+        assert_eq!(
+            history.undo(),
+            Some(UndoActionModel::ArticleChange(ids, BatchOperation::Star))
+        );
+        assert_eq!(history.undo(), None);
+
+        history.push(delete.clone());
+        history.cancelled(&delete);
+        assert!(history.undo.is_empty());
+        assert_eq!(history.redo(), Some(delete));
+    }
+}
@@ -1,10 +1,13 @@
+mod history;
 mod models;
 
 use crate::app::Action;
+use crate::article_list::{BatchOperation, BatchUpdate};
 use crate::i18n::i18n_f;
 use crate::util::{BuilderHelper, GtkUtil, Util};
 use glib::{clone, source::Continue, translate::ToGlib, Sender};
 use gtk::{Button, ButtonExt, InfoBar, InfoBarExt, Label, LabelExt, ResponseType, WidgetExt};
+use history::UndoHistory;
 use log::debug;
 pub use models::{UndoAction, UndoActionModel};
 use parking_lot::RwLock;
@@ -20,6 +23,7 @@ pub struct UndoBar {
     button: Button,
     current_action: Arc<RwLock<Option<UndoAction>>>,
     processing_actions: Arc<RwLock<HashSet<UndoActionModel>>>,
+    history: Arc<RwLock<UndoHistory>>,
     sender: Sender<Action>,
 }
 
@@ -31,6 +35,7 @@ impl UndoBar {
             button: builder.get::<Button>("undo_button"),
             current_action: Arc::new(RwLock::new(None)),
             processing_actions: Arc::new(RwLock::new(HashSet::new())),
+            history: Arc::new(RwLock::new(UndoHistory::new())),
             sender,
         };
         undo_bar.init();
@@ -42,27 +47,28 @@ impl UndoBar {
         self.button.connect_clicked(clone!(
             @weak self.widget as info_bar,
             @weak self.current_action as current_action,
+            @weak self.history as history,
             @strong self.sender as sender => @default-panic, move |_button|
         {
-            if let Some(current_action) = current_action.read().as_ref() {
-                GtkUtil::remove_source(Some(current_action.get_timeout()));
-            }
-            current_action.write().take();
-            info_bar.set_revealed(false);
+            if let Some(action) = Self::cancel_current(&info_bar, &current_action, &sender) {
+                history.write().cancelled(&action);
 
-            // update lists
-            Util::send(&sender, Action::UpdateSidebar);
-            Util::send(&sender, Action::UpdateArticleList);
+                // already applied actions need to be reverted
+                if let Some(inverse) = action.inverse() {
+                    Util::send(&sender, Action::ApplyUndoAction(inverse));
+                }
+            }
         }));
 
         self.widget.connect_response(clone!(
             @weak self.current_action as current_action,
             @weak self.processing_actions as processing_actions,
+            @weak self.history as history,
             @strong self.sender as sender => @default-panic, move |info_bar, response|
         {
             if response == ResponseType::Close {
                 if let Some(current_action) = current_action.read().as_ref() {
-                    Self::execute_action(&current_action.get_model(), &sender, &processing_actions);
+                    Self::execute_action(&current_action.get_model(), &sender, &processing_actions, &history);
                     GtkUtil::remove_source(Some(current_action.get_timeout()));
                 }
 
@@ -74,12 +80,35 @@ impl UndoBar {
         self.widget.show();
     }
 
+    fn cancel_current(
+        info_bar: &InfoBar,
+        current_action: &Arc<RwLock<Option<UndoAction>>>,
+        sender: &Sender<Action>,
+    ) -> Option<UndoActionModel> {
+        let action = current_action.write().take()?;
+        GtkUtil::remove_source(Some(action.get_timeout()));
+        info_bar.set_revealed(false);
+
+        // update lists
+        Util::send(sender, Action::UpdateSidebar);
+        Util::send(sender, Action::UpdateArticleList);
+
+        Some(action.get_model().clone())
+    }
+
     fn execute_action(
         action: &UndoActionModel,
         sender: &Sender<Action>,
         processing_actions: &Arc<RwLock<HashSet<UndoActionModel>>>,
+        history: &Arc<RwLock<UndoHistory>>,
     ) {
+        // already applied actions are only shown to offer undoing them
+        if action.inverse().is_some() {
+            return;
+        }
+
         let sender = sender.clone();
+        history.write().executed(action);
         processing_actions.write().insert(action.clone());
         match action {
             UndoActionModel::DeleteFeed(feed_id, _label) => {
@@ -91,24 +120,95 @@ impl UndoBar {
             UndoActionModel::DeleteTag(tag_id, _label) => {
                 Util::send(&sender, Action::DeleteTag(tag_id.clone()));
             }
+            UndoActionModel::MarkOlderRead(selection, days, timestamp) => {
+                Util::send(&sender, Action::MarkOlderRead(selection.clone(), *days, *timestamp));
+            }
+            UndoActionModel::ArticleBatch(article_ids, operation) => {
+                Util::send(
+                    &sender,
+                    Action::ExecuteArticleBatch(BatchUpdate {
+                        article_ids: article_ids.clone(),
+                        operation: operation.clone(),
+                    }),
+                );
+            }
+            UndoActionModel::ArticleChange(_, _)
+            | UndoActionModel::RenameFeed(_, _, _)
+            | UndoActionModel::RenameCategory(_, _, _)
+            | UndoActionModel::MoveFeed(_, _, _)
+            | UndoActionModel::MoveCategory(_, _, _)
+            | UndoActionModel::MarkAllRead(_, _) => {}
         }
     }
 
+    // actions that are not applied yet are shown in the bar and executed once it times out or is closed
     pub fn add_action(&self, action: UndoActionModel) {
-        if let Some(current_action) = self.current_action.read().as_ref() {
-            debug!("remove current action: {}", current_action.get_model());
-            GtkUtil::remove_source(Some(current_action.get_timeout()));
-            Self::execute_action(current_action.get_model(), &self.sender, &self.processing_actions);
+        self.history.write().push(action.clone());
+        self.show_action(action);
+    }
+
+    // pending actions of the bar are simply cancelled
+    pub fn undo(&self) -> Option<UndoActionModel> {
+        let action = self.history.write().undo()?;
+        if self.get_current_action().as_ref() == Some(&action) {
+            Self::cancel_current(&self.widget, &self.current_action, &self.sender);
+        }
+        action.inverse()
+    }
+
+    // cancelled actions are put back into the bar
+    pub fn redo(&self) -> Option<UndoActionModel> {
+        let action = self.history.write().redo()?;
+        if action.inverse().is_some() {
+            return Some(action);
         }
 
-        match &action {
-            UndoActionModel::DeleteCategory(_id, label) => {
-                self.label.set_label(&i18n_f("Deleted Category '{}'", &[label]))
+        self.show_action(action);
+        None
+    }
+
+    fn show_action(&self, action: UndoActionModel) {
+        let label = match &action {
+            UndoActionModel::DeleteCategory(_id, label) => i18n_f("Deleted Category '{}'", &[label]),
+            UndoActionModel::DeleteFeed(_id, label) => i18n_f("Deleted Feed '{}'", &[label]),
+            UndoActionModel::DeleteTag(_id, label) => i18n_f("Deleted Tag '{}'", &[label]),
+            UndoActionModel::MarkOlderRead(_selection, days, _timestamp) => {
+                i18n_f("Marking articles older than {} days as read", &[&days.to_string()])
             }
-            UndoActionModel::DeleteFeed(_id, label) => self.label.set_label(&i18n_f("Deleted Feed '{}'", &[label])),
-            UndoActionModel::DeleteTag(_id, label) => self.label.set_label(&i18n_f("Deleted Tag '{}'", &[label])),
+            UndoActionModel::ArticleBatch(article_ids, operation) => {
+                let count = article_ids.len().to_string();
+                match operation {
+                    BatchOperation::MarkRead => i18n_f("Marked {} articles as read", &[&count]),
+                    BatchOperation::MarkUnread => i18n_f("Marked {} articles as unread", &[&count]),
+                    BatchOperation::Star => i18n_f("Starred {} articles", &[&count]),
+                    BatchOperation::Unstar => i18n_f("Unstarred {} articles", &[&count]),
+                    BatchOperation::Tag(_tag_id) => i18n_f("Tagged {} articles", &[&count]),
+                    BatchOperation::Untag(_tag_id) => i18n_f("Removed tag from {} articles", &[&count]),
+                }
+            }
+            UndoActionModel::MarkAllRead(_selection, article_ids) => {
+                i18n_f("Marked {} articles as read", &[&article_ids.len().to_string()])
+            }
+            // small changes are only recorded in the history
+            UndoActionModel::ArticleChange(_, _)
+            | UndoActionModel::RenameFeed(_, _, _)
+            | UndoActionModel::RenameCategory(_, _, _)
+            | UndoActionModel::MoveFeed(_, _, _)
+            | UndoActionModel::MoveCategory(_, _, _) => return,
+        };
+
+        if let Some(current_action) = self.current_action.read().as_ref() {
+            debug!("remove current action: {}", current_action.get_model());
+            GtkUtil::remove_source(Some(current_action.get_timeout()));
+            Self::execute_action(
+                current_action.get_model(),
+                &self.sender,
+                &self.processing_actions,
+                &self.history,
+            );
         }
 
+        self.label.set_label(&label);
         self.widget.set_revealed(true);
 
         let source_id = gtk::timeout_add(
@@ -118,9 +218,10 @@ impl UndoBar {
                 @weak self.widget as widget,
                 @weak self.current_action as current_action,
                 @weak self.processing_actions as processing_actions,
+                @weak self.history as history,
                 @strong self.sender as sender => @default-panic, move ||
             {
-                Self::execute_action(&action, &sender, &processing_actions);
+                Self::execute_action(&action, &sender, &processing_actions, &history);
                 widget.set_revealed(false);
                 current_action.write().take();
                 Continue(false)
@@ -145,7 +246,7 @@ impl UndoBar {
 
     pub fn execute_pending_action(&self) {
         if let Some(current_action) = self.get_current_action() {
-            Self::execute_action(&current_action, &self.sender, &self.processing_actions);
+            Self::execute_action(&current_action, &self.sender, &self.processing_actions, &self.history);
         }
     }
 
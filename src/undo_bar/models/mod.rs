@@ -1,4 +1,6 @@
-use news_flash::models::{CategoryID, FeedID, TagID};
+use crate::article_list::BatchOperation;
+use crate::sidebar::models::SidebarSelection;
+use news_flash::models::{ArticleID, CategoryID, FeedID, TagID};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -7,6 +9,61 @@ pub enum UndoActionModel {
     DeleteFeed(FeedID, String),
     DeleteCategory(CategoryID, String),
     DeleteTag(TagID, String),
+    // (selection, days, timestamp)
+    MarkOlderRead(SidebarSelection, u32, i64),
+    ArticleBatch(Vec<ArticleID>, BatchOperation),
+    ArticleChange(Vec<ArticleID>, BatchOperation),
+    // (id, old label, new label)
+    RenameFeed(FeedID, String, String),
+    // (id, old label, new label)
+    RenameCategory(CategoryID, String, String),
+    // (id, old parent, new parent)
+    MoveFeed(FeedID, CategoryID, CategoryID),
+    // (id, old parent, new parent)
+    MoveCategory(CategoryID, CategoryID, CategoryID),
+    // articles of the selection that were unread before marking everything read
+    MarkAllRead(SidebarSelection, Vec<ArticleID>),
+}
+
+impl UndoActionModel {
+    // Actions that are already applied can be reverted by applying their inverse.
+    // All other actions are held back by the undo bar until it times out.
+    pub fn inverse(&self) -> Option<Self> {
+        match self {
+            UndoActionModel::DeleteFeed(_, _)
+            | UndoActionModel::DeleteCategory(_, _)
+            | UndoActionModel::DeleteTag(_, _) => None,
+            UndoActionModel::MarkOlderRead(_selection, _days, _timestamp) => None,
+            UndoActionModel::ArticleBatch(_article_ids, _operation) => None,
+            UndoActionModel::ArticleChange(article_ids, operation) => {
+                Some(UndoActionModel::ArticleChange(article_ids.clone(), operation.invert()))
+            }
+            UndoActionModel::RenameFeed(id, old_label, new_label) => Some(UndoActionModel::RenameFeed(
+                id.clone(),
+                new_label.clone(),
+                old_label.clone(),
+            )),
+            UndoActionModel::RenameCategory(id, old_label, new_label) => Some(UndoActionModel::RenameCategory(
+                id.clone(),
+                new_label.clone(),
+                old_label.clone(),
+            )),
+            UndoActionModel::MoveFeed(id, old_parent, new_parent) => Some(UndoActionModel::MoveFeed(
+                id.clone(),
+                new_parent.clone(),
+                old_parent.clone(),
+            )),
+            UndoActionModel::MoveCategory(id, old_parent, new_parent) => Some(UndoActionModel::MoveCategory(
+                id.clone(),
+                new_parent.clone(),
+                old_parent.clone(),
+            )),
+            UndoActionModel::MarkAllRead(_selection, article_ids) => Some(UndoActionModel::ArticleChange(
+                article_ids.clone(),
+                BatchOperation::MarkUnread,
+            )),
+        }
+    }
 }
 
 impl fmt::Display for UndoActionModel {
@@ -15,6 +72,28 @@ impl fmt::Display for UndoActionModel {
             UndoActionModel::DeleteFeed(id, label) => write!(f, "Delete Feed '{}' (id: {})", label, id),
             UndoActionModel::DeleteCategory(id, label) => write!(f, "Delete Category '{}' (id: {})", label, id),
             UndoActionModel::DeleteTag(id, label) => write!(f, "Delete Tag '{}' (id: {})", label, id),
+            UndoActionModel::MarkOlderRead(selection, days, _timestamp) => {
+                write!(f, "Mark older than {} days read ({:?})", days, selection)
+            }
+            UndoActionModel::ArticleBatch(article_ids, operation)
+            | UndoActionModel::ArticleChange(article_ids, operation) => {
+                write!(f, "{:?} {} articles", operation, article_ids.len())
+            }
+            UndoActionModel::RenameFeed(id, old_label, new_label) => {
+                write!(f, "Rename Feed '{}' to '{}' (id: {})", old_label, new_label, id)
+            }
+            UndoActionModel::RenameCategory(id, old_label, new_label) => {
+                write!(f, "Rename Category '{}' to '{}' (id: {})", old_label, new_label, id)
+            }
+            UndoActionModel::MoveFeed(id, old_parent, new_parent) => {
+                write!(f, "Move Feed {} from {} to {}", id, old_parent, new_parent)
+            }
+            UndoActionModel::MoveCategory(id, old_parent, new_parent) => {
+                write!(f, "Move Category {} from {} to {}", id, old_parent, new_parent)
+            }
+            UndoActionModel::MarkAllRead(selection, article_ids) => {
+                write!(f, "Mark {} articles read ({:?})", article_ids.len(), selection)
+            }
         }
     }
 }
@@ -24,18 +103,63 @@ impl PartialEq for UndoActionModel {
         match self {
             UndoActionModel::DeleteFeed(self_id, _self_title) => match other {
                 UndoActionModel::DeleteFeed(other_id, __other_title) => self_id == other_id,
-                UndoActionModel::DeleteCategory(_other_id, __other_title) => false,
-                UndoActionModel::DeleteTag(_other_id, __other_title) => false,
+                _ => false,
             },
             UndoActionModel::DeleteCategory(self_id, _title) => match other {
-                UndoActionModel::DeleteFeed(_other_id, __other_title) => false,
                 UndoActionModel::DeleteCategory(other_id, __other_title) => self_id == other_id,
-                UndoActionModel::DeleteTag(_other_id, __other_title) => false,
+                _ => false,
             },
             UndoActionModel::DeleteTag(self_id, _title) => match other {
-                UndoActionModel::DeleteFeed(_other_id, __other_title) => false,
-                UndoActionModel::DeleteCategory(_other_id, __other_title) => false,
                 UndoActionModel::DeleteTag(other_id, __other_title) => self_id == other_id,
+                _ => false,
+            },
+            UndoActionModel::MarkOlderRead(self_selection, self_days, self_timestamp) => match other {
+                UndoActionModel::MarkOlderRead(other_selection, other_days, other_timestamp) => {
+                    self_selection == other_selection && self_days == other_days && self_timestamp == other_timestamp
+                }
+                _ => false,
+            },
+            UndoActionModel::ArticleBatch(self_article_ids, self_operation) => match other {
+                UndoActionModel::ArticleBatch(other_article_ids, other_operation) => {
+                    self_article_ids == other_article_ids && self_operation == other_operation
+                }
+                _ => false,
+            },
+            UndoActionModel::ArticleChange(self_article_ids, self_operation) => match other {
+                UndoActionModel::ArticleChange(other_article_ids, other_operation) => {
+                    self_article_ids == other_article_ids && self_operation == other_operation
+                }
+                _ => false,
+            },
+            UndoActionModel::RenameFeed(self_id, self_old, self_new) => match other {
+                UndoActionModel::RenameFeed(other_id, other_old, other_new) => {
+                    self_id == other_id && self_old == other_old && self_new == other_new
+                }
+                _ => false,
+            },
+            UndoActionModel::RenameCategory(self_id, self_old, self_new) => match other {
+                UndoActionModel::RenameCategory(other_id, other_old, other_new) => {
+                    self_id == other_id && self_old == other_old && self_new == other_new
+                }
+                _ => false,
+            },
+            UndoActionModel::MoveFeed(self_id, self_old, self_new) => match other {
+                UndoActionModel::MoveFeed(other_id, other_old, other_new) => {
+                    self_id == other_id && self_old == other_old && self_new == other_new
+                }
+                _ => false,
+            },
+            UndoActionModel::MoveCategory(self_id, self_old, self_new) => match other {
+                UndoActionModel::MoveCategory(other_id, other_old, other_new) => {
+                    self_id == other_id && self_old == other_old && self_new == other_new
+                }
+                _ => false,
+            },
+            UndoActionModel::MarkAllRead(self_selection, self_article_ids) => match other {
+                UndoActionModel::MarkAllRead(other_selection, other_article_ids) => {
+                    self_selection == other_selection && self_article_ids == other_article_ids
+                }
+                _ => false,
             },
         }
     }
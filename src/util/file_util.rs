@@ -1,9 +1,11 @@
 use super::error::{UtilError, UtilErrorKind};
 use failure::ResultExt;
+use glib::{Checksum, ChecksumType};
+use serde::Serialize;
 use std::env;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::{Read, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub struct FileUtil;
 
@@ -30,4 +32,25 @@ impl FileUtil {
         file.read_to_string(&mut contents).context(UtilErrorKind::ReadFile)?;
         Ok(contents)
     }
+
+    // ids can be of any length and contain any character, so they are hashed to a valid file name
+    pub fn hashed_file_name(id: &str) -> String {
+        let mut checksum = Checksum::new(ChecksumType::Sha256);
+        checksum.update(id.as_bytes());
+        checksum
+            .get_string()
+            .unwrap_or_else(|| id.bytes().map(|byte| format!("{:02x}", byte)).collect())
+    }
+
+    // a crash while writing never leaves a truncated file behind
+    pub fn write_json_atomic<T: Serialize>(object: &T, path: &Path) -> Result<(), UtilError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context(UtilErrorKind::CreateFile)?;
+        }
+        let data = serde_json::to_string_pretty(object).context(UtilErrorKind::Serde)?;
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, data).context(UtilErrorKind::WriteFile)?;
+        fs::rename(&tmp_path, path).context(UtilErrorKind::WriteFile)?;
+        Ok(())
+    }
 }
@@ -22,7 +22,8 @@ use failure::ResultExt;
 use gio::{Cancellable, ProxyResolver, ProxyResolverExt};
 use glib::Sender;
 use lazy_static::lazy_static;
-use news_flash::models::{Category, CategoryID, Feed, FeedID, FeedMapping};
+use news_flash::models::{Article, ArticleFilter, Category, CategoryID, Feed, FeedID, FeedMapping};
+use news_flash::{NewsFlash, NewsFlashError};
 use serde::{de::DeserializeOwned, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs;
@@ -30,6 +31,7 @@ use std::future::Future;
 
 pub const CHANNEL_ERROR: &str = "Error sending message via glib channel";
 pub const RUNTIME_ERROR: &str = "Error creating tokio runtime";
+const ARTICLE_PAGE_SIZE: i64 = 500;
 
 lazy_static! {
     pub static ref NEWSFLASH_UNCATEGORIZED: CategoryID = CategoryID::new("NEWSFLASH_UNCATEGORIZED");
@@ -123,6 +125,31 @@ impl Util {
         count
     }
 
+    pub fn feeds_of_categories(
+        selected: &[CategoryID],
+        categories: &[Category],
+        feed_mappings: &[FeedMapping],
+    ) -> HashSet<FeedID> {
+        let mut selected_categories: HashSet<&CategoryID> = selected.iter().collect();
+        loop {
+            let children: Vec<&CategoryID> = categories
+                .iter()
+                .filter(|c| selected_categories.contains(&c.parent_id) && !selected_categories.contains(&c.category_id))
+                .map(|c| &c.category_id)
+                .collect();
+            if children.is_empty() {
+                break;
+            }
+            selected_categories.extend(children);
+        }
+
+        feed_mappings
+            .iter()
+            .filter(|m| selected_categories.contains(&m.category_id))
+            .map(|m| m.feed_id.clone())
+            .collect()
+    }
+
     pub fn create_mappings_for_uncategorized_feeds(feeds: &[Feed], mappings: &[FeedMapping]) -> Vec<FeedMapping> {
         let mut uncategorized_mappings = Vec::new();
         for feed in feeds {
@@ -137,6 +164,23 @@ impl Util {
         uncategorized_mappings
     }
 
+    // news_flash limits a query without `limit` to 20 articles, so all matching articles are loaded page by page
+    pub fn load_all_articles(news_flash: &NewsFlash, filter: ArticleFilter) -> Result<Vec<Article>, NewsFlashError> {
+        let offset = filter.offset.unwrap_or(0);
+        let mut articles = Vec::new();
+        loop {
+            let mut page_filter = filter.clone();
+            page_filter.limit = Some(ARTICLE_PAGE_SIZE);
+            page_filter.offset = Some(offset + articles.len() as i64);
+            let page = news_flash.get_articles(page_filter)?;
+            let exhausted = (page.len() as i64) < ARTICLE_PAGE_SIZE;
+            articles.extend(page);
+            if exhausted {
+                return Ok(articles);
+            }
+        }
+    }
+
     pub fn discover_gnome_proxy() -> Vec<ProxyModel> {
         let mut proxy_vec = Vec::new();
 